@@ -0,0 +1,540 @@
+//! Planned FFT engine for spek-core.
+//!
+//! A plan is built once per analysis and reused for every frame:
+//! - twiddle factors are precomputed (f64, rounded once to f32)
+//! - all buffers live in a reusable `FftScratch`
+//! - real input is packed into a half-size complex transform
+//!
+//! Any size >= 2 is supported:
+//! - mixed-radix Stockham passes (radix 2, 3, 4 + generic odd primes)
+//! - Bluestein (chirp-z) for sizes with a prime factor > `MAX_RADIX`
+//!
+//! The operation order is fixed by the plan, so results are
+//! bit-identical across runs and threads.
+
+use crate::analysis::AnalysisError;
+
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+/// Largest prime handled by a direct butterfly.
+const MAX_RADIX: usize = 61;
+
+/// Single-precision complex value.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Complex {
+    pub re: f32,
+    pub im: f32,
+}
+
+impl Complex {
+    pub const ZERO: Complex = Complex { re: 0.0, im: 0.0 };
+
+    pub fn new(re: f32, im: f32) -> Self {
+        Self { re, im }
+    }
+
+    /// exp(-i * angle), computed in f64.
+    fn twiddle(angle: f64) -> Self {
+        Self::new(angle.cos() as f32, -angle.sin() as f32)
+    }
+
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    /// Squared magnitude (power).
+    pub fn norm_sqr(self) -> f32 {
+        self.re * self.re + self.im * self.im
+    }
+
+    /// Multiply by -i.
+    fn mul_neg_i(self) -> Self {
+        Self::new(self.im, -self.re)
+    }
+
+    fn scale(self, s: f32) -> Self {
+        Self::new(self.re * s, self.im * s)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+
+    fn add(self, o: Complex) -> Complex {
+        Complex::new(self.re + o.re, self.im + o.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+
+    fn sub(self, o: Complex) -> Complex {
+        Complex::new(self.re - o.re, self.im - o.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+
+    fn mul(self, o: Complex) -> Complex {
+        Complex::new(
+            self.re * o.re - self.im * o.im,
+            self.re * o.im + self.im * o.re,
+        )
+    }
+}
+
+// ---------------------------------------------------------------------
+// Real-input plan (public entry point)
+// ---------------------------------------------------------------------
+
+/// Real-to-complex FFT plan.
+///
+/// Produces bins 0..=size/2 of the DFT of a real frame.
+#[derive(Debug)]
+pub struct FftPlan {
+    size: usize,
+
+    /// Complex transform: size/2 for even sizes, else size
+    inner: ComplexPlan,
+
+    /// exp(-2πik/size) for k in 0..=size/2 (even sizes only)
+    post: Vec<Complex>,
+}
+
+/// Reusable buffers for `FftPlan::forward`.
+#[derive(Debug)]
+pub struct FftScratch {
+    buffer: Vec<Complex>,
+    work: Vec<Complex>,
+}
+
+impl FftPlan {
+    /// Plan a real FFT of `size` samples.
+    pub fn new(size: usize) -> Result<Self, AnalysisError> {
+        if size < 2 {
+            return Err(AnalysisError::InvalidParameters);
+        }
+
+        if size % 2 == 0 {
+            let post = (0..=size / 2)
+                .map(|k| Complex::twiddle(2.0 * PI * k as f64 / size as f64))
+                .collect();
+
+            Ok(Self {
+                size,
+                inner: ComplexPlan::new(size / 2),
+                post,
+            })
+        } else {
+            Ok(Self {
+                size,
+                inner: ComplexPlan::new(size),
+                post: Vec::new(),
+            })
+        }
+    }
+
+    /// Input frame length.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of output bins (size/2 + 1).
+    pub fn bins(&self) -> usize {
+        self.size / 2 + 1
+    }
+
+    /// Allocate scratch buffers for this plan.
+    pub fn scratch(&self) -> FftScratch {
+        FftScratch {
+            buffer: vec![Complex::ZERO; self.inner.size],
+            work: vec![Complex::ZERO; self.inner.scratch_len()],
+        }
+    }
+
+    /// Transform `input` (len `size`) into `output` (len `bins()`).
+    pub fn forward(&self, input: &[f32], output: &mut [Complex], scratch: &mut FftScratch) {
+        let buffer = &mut scratch.buffer;
+
+        if self.post.is_empty() {
+            // Odd size: plain complex transform of the real frame
+            for (z, &x) in buffer.iter_mut().zip(input) {
+                *z = Complex::new(x, 0.0);
+            }
+
+            self.inner.transform(buffer, &mut scratch.work);
+            output.copy_from_slice(&buffer[..output.len()]);
+            return;
+        }
+
+        // Pack even/odd samples: z[n] = x[2n] + i x[2n+1]
+        for (z, pair) in buffer.iter_mut().zip(input.chunks_exact(2)) {
+            *z = Complex::new(pair[0], pair[1]);
+        }
+
+        self.inner.transform(buffer, &mut scratch.work);
+
+        // Split into even/odd spectra and combine
+        let half = self.inner.size;
+        for (k, out) in output.iter_mut().enumerate() {
+            let zk = buffer[k % half];
+            let zc = buffer[(half - k) % half].conj();
+
+            let even = (zk + zc).scale(0.5);
+            let odd = (zk - zc).scale(0.5).mul_neg_i();
+
+            *out = even + self.post[k] * odd;
+        }
+    }
+}
+
+// ---------------------------------------------------------------------
+// Complex plan
+// ---------------------------------------------------------------------
+
+#[derive(Debug)]
+struct ComplexPlan {
+    size: usize,
+
+    /// Radix of every Stockham pass (empty for Bluestein)
+    factors: Vec<usize>,
+
+    /// exp(-2πik/size) for k in 0..size
+    twiddles: Vec<Complex>,
+
+    /// Chirp-z fallback for large prime factors
+    bluestein: Option<Box<Bluestein>>,
+}
+
+impl ComplexPlan {
+    fn new(size: usize) -> Self {
+        let factors = factorize(size);
+
+        if factors.iter().any(|&p| p > MAX_RADIX) {
+            return Self {
+                size,
+                factors: Vec::new(),
+                twiddles: Vec::new(),
+                bluestein: Some(Box::new(Bluestein::new(size))),
+            };
+        }
+
+        let twiddles = (0..size)
+            .map(|k| Complex::twiddle(2.0 * PI * k as f64 / size as f64))
+            .collect();
+
+        Self {
+            size,
+            factors,
+            twiddles,
+            bluestein: None,
+        }
+    }
+
+    /// Scratch elements needed by `transform`.
+    fn scratch_len(&self) -> usize {
+        match &self.bluestein {
+            Some(b) => 2 * b.len,
+            None => self.size,
+        }
+    }
+
+    /// In-place forward transform of `data` (len `size`).
+    fn transform(&self, data: &mut [Complex], scratch: &mut [Complex]) {
+        if let Some(b) = &self.bluestein {
+            b.transform(data, scratch);
+            return;
+        }
+
+        let work = &mut scratch[..self.size];
+        let mut in_work = false;
+        let mut done = 1;
+
+        for &radix in &self.factors {
+            if in_work {
+                self.pass(radix, done, work, data);
+            } else {
+                self.pass(radix, done, data, work);
+            }
+
+            in_work = !in_work;
+            done *= radix;
+        }
+
+        if in_work {
+            data.copy_from_slice(work);
+        }
+    }
+
+    /// One Stockham pass: DFTs of length `done` -> `done * radix`.
+    ///
+    /// Layout: element k of sub-DFT c is stored at k * stride + c.
+    fn pass(&self, radix: usize, done: usize, src: &[Complex], dst: &mut [Complex]) {
+        let stride = self.size / (done * radix);
+        let root = self.size / radix;
+
+        let mut a = [Complex::ZERO; MAX_RADIX];
+        let mut tw = [Complex::ZERO; MAX_RADIX];
+
+        for k in 0..done {
+            for (j, t) in tw[..radix].iter_mut().enumerate() {
+                *t = self.twiddles[j * k * stride];
+            }
+
+            let src_base = k * radix * stride;
+
+            for c in 0..stride {
+                for j in 0..radix {
+                    a[j] = src[src_base + j * stride + c] * tw[j];
+                }
+
+                let out = |q: usize| (k + done * q) * stride + c;
+
+                match radix {
+                    2 => {
+                        dst[out(0)] = a[0] + a[1];
+                        dst[out(1)] = a[0] - a[1];
+                    }
+
+                    3 => {
+                        // exp(-2πi/3) = -1/2 - i·√3/2
+                        let sum = a[1] + a[2];
+                        let diff = (a[1] - a[2]).mul_neg_i().scale(0.866_025_4);
+                        let mid = a[0] - sum.scale(0.5);
+
+                        dst[out(0)] = a[0] + sum;
+                        dst[out(1)] = mid + diff;
+                        dst[out(2)] = mid - diff;
+                    }
+
+                    4 => {
+                        let s02 = a[0] + a[2];
+                        let d02 = a[0] - a[2];
+                        let s13 = a[1] + a[3];
+                        let d13 = (a[1] - a[3]).mul_neg_i();
+
+                        dst[out(0)] = s02 + s13;
+                        dst[out(1)] = d02 + d13;
+                        dst[out(2)] = s02 - s13;
+                        dst[out(3)] = d02 - d13;
+                    }
+
+                    _ => {
+                        for q in 0..radix {
+                            let mut acc = a[0];
+                            for (j, &x) in a[1..radix].iter().enumerate() {
+                                acc = acc + x * self.twiddles[((j + 1) * q % radix) * root];
+                            }
+                            dst[out(q)] = acc;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Split `n` into Stockham radices: 4s first, then 2, 3 and odd primes.
+fn factorize(mut n: usize) -> Vec<usize> {
+    let mut factors = Vec::new();
+
+    while n % 4 == 0 {
+        factors.push(4);
+        n /= 4;
+    }
+
+    let mut p = 2;
+    while n > 1 {
+        if p * p > n {
+            factors.push(n);
+            break;
+        }
+
+        while n % p == 0 {
+            factors.push(p);
+            n /= p;
+        }
+
+        p += if p == 2 { 1 } else { 2 };
+    }
+
+    factors
+}
+
+// ---------------------------------------------------------------------
+// Bluestein (chirp-z) transform
+// ---------------------------------------------------------------------
+
+/// DFT of any size as a power-of-two circular convolution.
+#[derive(Debug)]
+struct Bluestein {
+    /// Convolution length (power of two >= 2 * size - 1)
+    len: usize,
+
+    /// exp(-πi n²/size) for n in 0..size
+    chirp: Vec<Complex>,
+
+    /// FFT of the conjugate chirp, pre-scaled by 1/len
+    kernel: Vec<Complex>,
+
+    inner: ComplexPlan,
+}
+
+impl Bluestein {
+    fn new(size: usize) -> Self {
+        let len = (2 * size - 1).next_power_of_two();
+
+        // n² mod 2·size keeps the angle exact for large n
+        let chirp: Vec<Complex> = (0..size)
+            .map(|n| {
+                let sq = (n as u64 * n as u64) % (2 * size as u64);
+                Complex::twiddle(PI * sq as f64 / size as f64)
+            })
+            .collect();
+
+        let inner = ComplexPlan::new(len);
+
+        let mut kernel = vec![Complex::ZERO; len];
+        kernel[0] = chirp[0].conj();
+        for n in 1..size {
+            kernel[n] = chirp[n].conj();
+            kernel[len - n] = chirp[n].conj();
+        }
+
+        let mut work = vec![Complex::ZERO; inner.scratch_len()];
+        inner.transform(&mut kernel, &mut work);
+
+        let scale = 1.0 / len as f32;
+        for k in kernel.iter_mut() {
+            *k = k.scale(scale);
+        }
+
+        Self {
+            len,
+            chirp,
+            kernel,
+            inner,
+        }
+    }
+
+    fn transform(&self, data: &mut [Complex], scratch: &mut [Complex]) {
+        let (a, work) = scratch.split_at_mut(self.len);
+
+        for (i, x) in a.iter_mut().enumerate() {
+            *x = match data.get(i) {
+                Some(&d) => d * self.chirp[i],
+                None => Complex::ZERO,
+            };
+        }
+
+        self.inner.transform(a, work);
+
+        // Pointwise product, then inverse FFT via conjugation
+        for (x, &k) in a.iter_mut().zip(&self.kernel) {
+            *x = (*x * k).conj();
+        }
+
+        self.inner.transform(a, work);
+
+        for (i, d) in data.iter_mut().enumerate() {
+            *d = a[i].conj() * self.chirp[i];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sizes covering every code path:
+    /// radix 2/3/4, radix 5, Bluestein (97), generic radix 61 (122 = 2 * 61).
+    const SIZES: [usize; 5] = [12, 60, 97, 122, 4096];
+
+    /// Pseudo-random samples in -1.0..1.0.
+    fn noise(len: usize, mut seed: u32) -> Vec<f32> {
+        (0..len)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                (seed >> 8) as f32 / (1u32 << 23) as f32 - 1.0
+            })
+            .collect()
+    }
+
+    /// O(n²) reference DFT in f64.
+    fn naive_dft(input: &[Complex]) -> Vec<(f64, f64)> {
+        let n = input.len();
+
+        (0..n)
+            .map(|k| {
+                input.iter().enumerate().fold((0.0, 0.0), |(re, im), (j, x)| {
+                    let angle = -2.0 * PI * ((j * k) % n) as f64 / n as f64;
+                    let (sin, cos) = angle.sin_cos();
+                    (
+                        re + x.re as f64 * cos - x.im as f64 * sin,
+                        im + x.re as f64 * sin + x.im as f64 * cos,
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Largest bin error relative to the input's L2 norm.
+    fn max_error(output: &[Complex], reference: &[(f64, f64)], input: &[Complex]) -> f64 {
+        let norm = input.iter().map(|x| x.norm_sqr() as f64).sum::<f64>().sqrt();
+
+        output
+            .iter()
+            .zip(reference)
+            .map(|(x, &(re, im))| (x.re as f64 - re).hypot(x.im as f64 - im))
+            .fold(0.0, f64::max)
+            / norm
+    }
+
+    #[test]
+    fn real_plan_matches_naive_dft() {
+        for size in SIZES {
+            let input = noise(size, size as u32);
+            let plan = FftPlan::new(size).unwrap();
+            let mut scratch = plan.scratch();
+            let mut output = vec![Complex::ZERO; plan.bins()];
+
+            plan.forward(&input, &mut output, &mut scratch);
+
+            let complex: Vec<Complex> = input.iter().map(|&x| Complex::new(x, 0.0)).collect();
+            let reference = naive_dft(&complex);
+
+            assert_eq!(output.len(), size / 2 + 1);
+            let error = max_error(&output, &reference, &complex);
+            assert!(error < 1e-6, "size {}: {}", size, error);
+        }
+    }
+
+    #[test]
+    fn complex_plan_matches_naive_dft() {
+        for size in SIZES {
+            let re = noise(size, 1);
+            let im = noise(size, 2);
+            let input: Vec<Complex> = re.iter().zip(&im).map(|(&r, &i)| Complex::new(r, i)).collect();
+
+            let plan = ComplexPlan::new(size);
+            assert_eq!(plan.bluestein.is_some(), size == 97);
+
+            let mut data = input.clone();
+            let mut work = vec![Complex::ZERO; plan.scratch_len()];
+            plan.transform(&mut data, &mut work);
+
+            let error = max_error(&data, &naive_dft(&input), &input);
+            assert!(error < 1e-6, "size {}: {}", size, error);
+        }
+    }
+
+    #[test]
+    fn factorize_prefers_radix_4() {
+        assert_eq!(factorize(4096), [4; 6]);
+        assert_eq!(factorize(60), [4, 3, 5]);
+        assert_eq!(factorize(122), [2, 61]);
+        assert_eq!(factorize(97), [97]);
+    }
+}
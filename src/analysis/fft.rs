@@ -1,22 +1,41 @@
 //! FFT-based signal analysis for spek-core.
 //!
 //! This module implements the numerical spectrogram pipeline:
-//! PCM -> windowing -> FFT -> power -> dBFS -> scaling -> normalization
+//! PCM -> windowing -> FFT -> power -> calibration -> dBFS -> scaling -> normalization
 //!
 //! This module has NO rendering logic and NO platform dependencies.
 
+use crate::analysis::engine::{Complex, FftPlan, FftScratch};
+use crate::analysis::mix::{build_mix, MixPanel};
+use crate::analysis::window::{build_window, coherent_sum, energy_sum};
 use crate::analysis::{
-    AnalysisError, AnalysisSettings, Analyzer, IntensityScale, Spectrogram, SpectrogramSet,
-    WindowFunction,
+    AnalysisError, AnalysisSettings, Analyzer, DbRange, IntensityScale, Normalization,
+    Spectrogram, SpectrogramSet, StreamingAnalyzer,
 };
-use crate::audio::AudioBuffer;
+use crate::audio::{AudioBuffer, AudioError, PcmStream};
 
-use std::f32::consts::PI;
+use std::ops::{Add, AddAssign, Range};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::Scope;
+
+/// Spek-style visual noise cutoff
+const SPEK_CUTOFF: f32 = 0.005;
+
+/// Frames per worker task; bounds the batch buffer.
+const FRAMES_PER_TASK: usize = 16;
 
 /// Basic FFT analyzer.
 ///
 /// This is a single-shot, deterministic analyzer.
 /// No internal state is kept between calls.
+///
+/// In-memory and streamed audio share one code path,
+/// so both produce bit-identical spectrograms.
+///
+/// Frames are transformed on `AnalysisSettings::threads` workers,
+/// but always accumulated in frame order: the output is
+/// bit-identical for any thread count.
+#[derive(Debug, Default)]
 pub struct FftAnalyzer;
 
 impl FftAnalyzer {
@@ -31,145 +50,623 @@ impl Analyzer for FftAnalyzer {
         audio: &AudioBuffer,
         settings: &AnalysisSettings,
     ) -> Result<SpectrogramSet, AnalysisError> {
-        let channels = audio.meta.channels as usize;
+        if audio.samples.is_empty() {
+            return Err(AnalysisError::InvalidParameters);
+        }
+
+        self.analyze_stream(&mut audio.stream(), settings)
+    }
+}
+
+impl StreamingAnalyzer for FftAnalyzer {
+    fn analyze_stream(
+        &self,
+        stream: &mut dyn PcmStream,
+        settings: &AnalysisSettings,
+    ) -> Result<SpectrogramSet, AnalysisError> {
+        let channels = stream.meta().channels as usize;
+        let sample_rate = stream.meta().sample_rate;
         let fft_size = settings.fft_size;
         let hop = settings.hop_size;
 
-        if fft_size == 0 || hop == 0 || audio.samples.is_empty() {
+        if fft_size == 0 || hop == 0 || channels == 0 {
             return Err(AnalysisError::InvalidParameters);
         }
 
-        let samples_per_channel = audio.samples.len() / channels;
-        let time_bins = (samples_per_channel.saturating_sub(fft_size)) / hop;
-        let freq_bins = fft_size / 2;
+        // -------------------------------------------------------------
+        // Analysis plan
+        // -------------------------------------------------------------
+        let bins = bin_range(settings, fft_size, sample_rate)?;
+        let freq_bins = bins.len();
+        let bin_hz = sample_rate as f64 / fft_size as f64;
 
-        let window = build_window(settings.window, fft_size);
+        let window = build_window(settings.window, fft_size)?;
+        let gain = bin_gain(&window, settings.normalization)?;
+        let plan = FftPlan::new(fft_size)?;
+        let mix = build_mix(&settings.channels, channels)?;
+        let panels = mix.len();
 
-        let mut result = Vec::with_capacity(channels);
+        // Mixed history per panel, starting at absolute sample `base`
+        let mut history = vec![Vec::with_capacity(fft_size * 2); panels];
+        let mut base = 0usize;
+        let mut next_frame = 0usize;
+        let mut block = Vec::new();
+        let mut eof = false;
 
-        // Spek-style visual noise cutoff
-        const SPEK_CUTOFF: f32 = 0.005;
+        // Frames are summed into time bins of `span` frames each.
+        // No frame count is needed up front: with a cap, once 2 * cap
+        // bins are filled, adjacent bins are merged and `span` doubles.
+        let max_time_bins = settings.max_time_bins.filter(|&max| max > 0);
+        let mut span = 1usize;
 
-        for ch in 0..channels {
-            let mut spec = Spectrogram {
-                freq_bins,
-                time_bins,
-                data: vec![vec![0.0; time_bins]; freq_bins],
-            };
+        // Accumulated power per panel: [freq_bin][time_bin]
+        let mut power = vec![vec![Vec::new(); freq_bins]; panels];
+        let mut frames_per_bin: Vec<u32> = Vec::new();
+        let mut frame_centers: Vec<f64> = Vec::new();
 
-            for t in 0..time_bins {
-                let offset = t * hop;
-                let mut re = vec![0.0f32; fft_size];
-                let mut im = vec![0.0f32; fft_size];
+        // Unbinned power per panel, for export: [freq_bin][frame]
+        let mut frames = settings
+            .keep_frames
+            .then(|| vec![vec![Vec::new(); freq_bins]; panels]);
 
-                for i in 0..fft_size {
-                    let idx = (offset + i) * channels + ch;
-                    let sample = audio.samples.get(idx).copied().unwrap_or(0.0);
-                    re[i] = sample * window[i];
-                }
+        // Frames are transformed in batches, one task per worker
+        let workers = worker_count(settings.threads);
+        let batch = workers * FRAMES_PER_TASK;
+        let frame_len = panels * freq_bins;
 
-                fft_inplace(&mut re, &mut im);
+        // Reused for every batch
+        let mut spectra = vec![0.0f32; batch * frame_len];
 
-                for f in 0..freq_bins {
-                    let power = re[f] * re[f] + im[f] * im[f];
-                    let db = power_to_db(power, settings.min_db);
-                    let norm = normalize_db(db, settings.min_db);
-                    let scaled = apply_scale(norm, settings.scale);
+        std::thread::scope(|scope| -> Result<(), AnalysisError> {
+            let mut pool = Pool::new(scope, &plan, &window, bins.clone(), hop, workers);
 
-                    // -------------------------------------------------
-                    // CRITICAL: Spek-style hard cutoff
-                    // -------------------------------------------------
-                    spec.data[f][t] = if scaled < SPEK_CUTOFF {
-                        0.0
+            loop {
+                if !eof {
+                    if stream.read_block(&mut block).map_err(map_audio_error)? {
+                        push_mixed(&mut history, &mix, &block, channels);
                     } else {
-                        scaled
-                    };
+                        eof = true;
+                    }
+                }
+
+                // Frames fully covered by the history
+                let available = base + history[0].len();
+                let ready = frame_count(available, fft_size, hop);
+
+                // ---------------------------------------------------------
+                // Transform full batches (or the rest at the end)
+                // ---------------------------------------------------------
+                while next_frame < ready && (ready - next_frame >= batch || eof) {
+                    let count = (ready - next_frame).min(batch);
+                    let out = &mut spectra[..count * frame_len];
+
+                    pool.transform(&history, next_frame * hop - base, out);
+
+                    // Accumulate in frame order: identical for any worker count
+                    for (i, frame) in out.chunks_exact(frame_len).enumerate() {
+                        let n = next_frame + i;
+
+                        if n / span == frames_per_bin.len() {
+                            if max_time_bins.is_some_and(|max| frames_per_bin.len() == 2 * max) {
+                                span *= 2;
+
+                                power.iter_mut().flatten().for_each(merge_pairs);
+                                merge_pairs(&mut frames_per_bin);
+                                merge_pairs(&mut frame_centers);
+                            }
+
+                            power.iter_mut().flatten().for_each(|row| row.push(0.0));
+                            frames_per_bin.push(0);
+                            frame_centers.push(0.0);
+                        }
+
+                        let t = n / span;
+                        let spectra = frame.chunks_exact(freq_bins);
+
+                        for (data, spectrum) in power.iter_mut().zip(spectra) {
+                            for (row, &p) in data.iter_mut().zip(spectrum) {
+                                row[t] += p;
+                            }
+                        }
+
+                        frames_per_bin[t] += 1;
+                        frame_centers[t] += (n * hop) as f64 + fft_size as f64 / 2.0;
+
+                        if let Some(frames) = &mut frames {
+                            let spectra = frame.chunks_exact(freq_bins);
+
+                            for (data, spectrum) in frames.iter_mut().zip(spectra) {
+                                for (row, &p) in data.iter_mut().zip(spectrum) {
+                                    row.push(p);
+                                }
+                            }
+                        }
+                    }
+
+                    next_frame += count;
                 }
+
+                if eof {
+                    break;
+                }
+
+                // ---------------------------------------------------------
+                // Drop history no future frame needs
+                // ---------------------------------------------------------
+                let consumed = (next_frame * hop - base).min(history[0].len());
+                for samples in history.iter_mut() {
+                    samples.drain(..consumed);
+                }
+                base += consumed;
             }
 
-            result.push(spec);
+            Ok(())
+        })?;
+
+        // -------------------------------------------------------------
+        // Spread the (at most 2 * cap) bins over the capped grid
+        // -------------------------------------------------------------
+        let time_bins = max_time_bins.map_or(frames_per_bin.len(), |max| {
+            frames_per_bin.len().min(max)
+        });
+
+        if time_bins < frames_per_bin.len() {
+            power.iter_mut().flatten().for_each(|row| rebin(row, time_bins));
+            rebin(&mut frames_per_bin, time_bins);
+            rebin(&mut frame_centers, time_bins);
         }
 
-        Ok(SpectrogramSet { channels: result })
+        // -------------------------------------------------------------
+        // Power -> calibrated dBFS (in place)
+        // -------------------------------------------------------------
+        let bin_gain = |f: usize| if bins.start + f == 0 { gain.dc } else { gain.ac };
+
+        for data in power.iter_mut() {
+            for (f, row) in data.iter_mut().enumerate() {
+                let gain = bin_gain(f);
+
+                for (t, value) in row.iter_mut().enumerate() {
+                    let n = frames_per_bin[t];
+                    let p = if n > 1 { *value / n as f32 } else { *value };
+
+                    *value = power_to_db(p * gain);
+                }
+            }
+        }
+
+        for data in frames.iter_mut().flatten() {
+            for (f, row) in data.iter_mut().enumerate() {
+                let gain = bin_gain(f);
+                row.iter_mut().for_each(|value| *value = power_to_db(*value * gain));
+            }
+        }
+
+        // -------------------------------------------------------------
+        // dBFS range, shared by all panels (one legend)
+        // -------------------------------------------------------------
+        let (min_db, max_db) = select_range(&power, settings)?;
+
+        // -------------------------------------------------------------
+        // dBFS -> normalized intensity
+        // -------------------------------------------------------------
+        let mut frame_db = frames.map(Vec::into_iter);
+
+        let result = power
+            .into_iter()
+            .zip(mix)
+            .map(|(mut data, panel)| {
+                let db = settings.keep_db.then(|| data.clone());
+                let frame_db = frame_db.as_mut().and_then(Iterator::next);
+
+                for row in data.iter_mut() {
+                    for value in row.iter_mut() {
+                        *value = db_to_intensity(*value, min_db, max_db, settings.scale);
+                    }
+                }
+
+                Spectrogram {
+                    freq_bins,
+                    time_bins,
+                    data,
+                    db,
+                    frame_db,
+                    label: panel.label,
+                }
+            })
+            .collect();
+
+        Ok(SpectrogramSet {
+            channels: result,
+            min_db,
+            max_db,
+            scale: settings.scale,
+            min_freq: (bins.start as f64 * bin_hz) as f32,
+            max_freq: (bins.end as f64 * bin_hz) as f32,
+            frequencies: bins.map(|k| k as f64 * bin_hz).collect(),
+            times: frame_centers
+                .iter()
+                .zip(&frames_per_bin)
+                .map(|(&sum, &n)| sum / n.max(1) as f64 / sample_rate as f64)
+                .collect(),
+            frame_times: settings.keep_frames.then(|| {
+                (0..next_frame)
+                    .map(|n| ((n * hop) as f64 + fft_size as f64 / 2.0) / sample_rate as f64)
+                    .collect()
+            }),
+        })
     }
 }
 
-/// Build a window function.
-fn build_window(kind: WindowFunction, size: usize) -> Vec<f32> {
-    match kind {
-        WindowFunction::Rectangular => vec![1.0; size],
-        WindowFunction::Hann => (0..size)
-            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / size as f32).cos())
-            .collect(),
-        WindowFunction::Hamming => (0..size)
-            .map(|i| 0.54 - 0.46 * (2.0 * PI * i as f32 / size as f32).cos())
-            .collect(),
-        WindowFunction::Blackman => (0..size)
-            .map(|i| {
-                0.42
-                    - 0.5 * (2.0 * PI * i as f32 / size as f32).cos()
-                    + 0.08 * (4.0 * PI * i as f32 / size as f32).cos()
-            })
-            .collect(),
-        _ => vec![1.0; size],
+/// Per-thread FFT state, reused across batches.
+struct Worker {
+    scratch: FftScratch,
+    windowed: Vec<f32>,
+    spectrum: Vec<Complex>,
+
+    /// FFT bins kept (frequency crop)
+    bins: Range<usize>,
+}
+
+impl Worker {
+    fn new(plan: &FftPlan, bins: Range<usize>) -> Self {
+        Self {
+            scratch: plan.scratch(),
+            windowed: vec![0.0; plan.size()],
+            spectrum: vec![Complex::ZERO; plan.bins()],
+            bins,
+        }
+    }
+
+    /// Power spectra of consecutive frames, laid out [frame][channel][freq].
+    ///
+    /// The first frame starts at `start` in every history panel.
+    fn run(
+        &mut self,
+        plan: &FftPlan,
+        window: &[f32],
+        history: &[Vec<f32>],
+        start: usize,
+        hop: usize,
+        out: &mut [f32],
+    ) {
+        let size = plan.size();
+        let freq_bins = self.bins.len();
+        let frame_len = history.len() * freq_bins;
+
+        for (i, frame) in out.chunks_exact_mut(frame_len).enumerate() {
+            let offset = start + i * hop;
+
+            for (samples, power) in history.iter().zip(frame.chunks_exact_mut(freq_bins)) {
+                for ((w, &x), &c) in self
+                    .windowed
+                    .iter_mut()
+                    .zip(&samples[offset..offset + size])
+                    .zip(window)
+                {
+                    *w = x * c;
+                }
+
+                plan.forward(&self.windowed, &mut self.spectrum, &mut self.scratch);
+
+                for (p, bin) in power.iter_mut().zip(&self.spectrum[self.bins.clone()]) {
+                    *p = bin.norm_sqr();
+                }
+            }
+        }
     }
 }
 
-/// In-place radix-2 FFT (Cooley–Tukey).
-fn fft_inplace(re: &mut [f32], im: &mut [f32]) {
-    let n = re.len();
-    let mut j = 0;
+/// FFT workers of one analysis.
+///
+/// Threads are spawned once and fed tasks over channels;
+/// the calling thread computes the first task of every batch itself.
+/// Every frame is computed by the same code on its own,
+/// so the result does not depend on the split.
+struct Pool<'env> {
+    plan: &'env FftPlan,
+    window: &'env [f32],
+    hop: usize,
+    local: Worker,
+    threads: Vec<PoolThread>,
+}
 
-    for i in 1..n {
-        let mut bit = n >> 1;
-        while j & bit != 0 {
-            j ^= bit;
-            bit >>= 1;
+/// Channels to one worker thread.
+struct PoolThread {
+    tasks: Sender<Task>,
+    done: Receiver<Task>,
+
+    /// Buffers handed back with the last result
+    idle: Task,
+}
+
+/// Frames for a worker thread, with their own copy of the samples.
+#[derive(Default)]
+struct Task {
+    /// Mixed samples per panel, from the first frame on
+    samples: Vec<Vec<f32>>,
+
+    /// Power spectra, laid out [frame][channel][freq]
+    spectra: Vec<f32>,
+}
+
+impl<'env> Pool<'env> {
+    fn new<'scope>(
+        scope: &'scope Scope<'scope, 'env>,
+        plan: &'env FftPlan,
+        window: &'env [f32],
+        bins: Range<usize>,
+        hop: usize,
+        workers: usize,
+    ) -> Self {
+        let threads = (1..workers)
+            .map(|_| {
+                let (tasks, inbox) = mpsc::channel::<Task>();
+                let (outbox, done) = mpsc::channel();
+                let mut worker = Worker::new(plan, bins.clone());
+
+                scope.spawn(move || {
+                    for mut task in inbox {
+                        worker.run(plan, window, &task.samples, 0, hop, &mut task.spectra);
+
+                        if outbox.send(task).is_err() {
+                            break;
+                        }
+                    }
+                });
+
+                PoolThread {
+                    tasks,
+                    done,
+                    idle: Task::default(),
+                }
+            })
+            .collect();
+
+        Self {
+            plan,
+            window,
+            hop,
+            local: Worker::new(plan, bins),
+            threads,
         }
-        j |= bit;
+    }
+
+    /// Transform a batch of up to `workers * FRAMES_PER_TASK` frames.
+    ///
+    /// The first frame starts at `start` in every history panel.
+    fn transform(&mut self, history: &[Vec<f32>], start: usize, out: &mut [f32]) {
+        let frame_len = history.len() * self.local.bins.len();
+        let task_len = FRAMES_PER_TASK * frame_len;
+        let size = self.plan.size();
+
+        let (first, rest) = out.split_at_mut(task_len.min(out.len()));
+        let mut pending = Vec::new();
+
+        for (i, (thread, chunk)) in self
+            .threads
+            .iter_mut()
+            .zip(rest.chunks_mut(task_len))
+            .enumerate()
+        {
+            let offset = start + (i + 1) * FRAMES_PER_TASK * self.hop;
+            let span = (chunk.len() / frame_len - 1) * self.hop + size;
+
+            let mut task = std::mem::take(&mut thread.idle);
+            task.samples.resize_with(history.len(), Vec::new);
+            for (samples, panel) in task.samples.iter_mut().zip(history) {
+                samples.clear();
+                samples.extend_from_slice(&panel[offset..offset + span]);
+            }
+            task.spectra.resize(chunk.len(), 0.0);
 
-        if i < j {
-            re.swap(i, j);
-            im.swap(i, j);
+            thread.tasks.send(task).expect("FFT worker stopped");
+            pending.push((thread, chunk));
         }
+
+        self.local
+            .run(self.plan, self.window, history, start, self.hop, first);
+
+        for (thread, chunk) in pending {
+            let task = thread.done.recv().expect("FFT worker stopped");
+            chunk.copy_from_slice(&task.spectra);
+            thread.idle = task;
+        }
+    }
+}
+
+/// Append one block, mixed into the panels, to the history.
+fn push_mixed(history: &mut [Vec<f32>], mix: &[MixPanel], block: &[f32], channels: usize) {
+    for frame in block.chunks_exact(channels) {
+        for (samples, panel) in history.iter_mut().zip(mix) {
+            samples.push(panel.apply(frame));
+        }
+    }
+
+    #[cfg(test)]
+    tests::HISTORY_PEAK.with(|peak| peak.set(peak.get().max(history[0].len())));
+}
+
+/// Halve a time axis by summing adjacent bins (even length).
+fn merge_pairs<T: Copy + Add<Output = T>>(values: &mut Vec<T>) {
+    for i in 0..values.len() / 2 {
+        values[i] = values[2 * i] + values[2 * i + 1];
+    }
+    values.truncate(values.len() / 2);
+}
+
+/// Sum a time axis of `values.len()` bins into `bins` bins;
+/// bin `j` goes to `j * bins / values.len()`.
+fn rebin<T: Copy + Default + AddAssign>(values: &mut Vec<T>, bins: usize) {
+    let mut out = vec![T::default(); bins];
+    for (j, &value) in values.iter().enumerate() {
+        out[j * bins / values.len()] += value;
+    }
+    *values = out;
+}
+
+/// Frames analyzed in `samples` samples per channel.
+///
+/// A frame is only counted once a full hop follows it.
+fn frame_count(samples: usize, fft_size: usize, hop: usize) -> usize {
+    samples.saturating_sub(fft_size) / hop
+}
+
+/// FFT bins covering `freq_min..freq_max`.
+///
+/// Bin `k` spans `k * rate / size .. (k + 1) * rate / size`, the same
+/// edge-aligned layout as `min_freq` / `max_freq` and `frequencies`;
+/// every bin overlapping the requested band is kept.
+fn bin_range(
+    settings: &AnalysisSettings,
+    fft_size: usize,
+    sample_rate: u32,
+) -> Result<Range<usize>, AnalysisError> {
+    let nyquist = sample_rate as f32 / 2.0;
+    let low = settings.freq_min.unwrap_or(0.0);
+    let high = settings.freq_max.unwrap_or(nyquist).min(nyquist);
+
+    if fft_size < 2 || !low.is_finite() || !high.is_finite() || low < 0.0 || low >= high {
+        return Err(AnalysisError::InvalidParameters);
+    }
+
+    let bin_hz = sample_rate as f64 / fft_size as f64;
+    let max = fft_size / 2;
+
+    let start = ((low as f64 / bin_hz).floor() as usize).min(max - 1);
+    let end = ((high as f64 / bin_hz).ceil() as usize).clamp(start + 1, max);
+
+    Ok(start..end)
+}
+
+/// Resolve the configured thread count (0 = all cores).
+fn worker_count(threads: usize) -> usize {
+    match threads {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
     }
+}
 
-    let mut len = 2;
-    while len <= n {
-        let half = len / 2;
-        let step = PI * 2.0 / len as f32;
-        for i in (0..n).step_by(len) {
-            for j in 0..half {
-                let angle = step * j as f32;
-                let wr = angle.cos();
-                let wi = -angle.sin();
+/// Power calibration factors for the DC bin and all other bins.
+#[derive(Debug, Copy, Clone)]
+struct BinGain {
+    dc: f32,
+    ac: f32,
+}
 
-                let k = i + j;
-                let l = k + half;
+/// Derive the power calibration from the window.
+///
+/// Non-DC bins are one-sided: a sine splits its power between +f and -f,
+/// so they are scaled by 4 (AES17: full-scale sine = 0 dBFS).
+fn bin_gain(window: &[f32], normalization: Normalization) -> Result<BinGain, AnalysisError> {
+    let dc = match normalization {
+        // |X| / sum(w) = amplitude
+        Normalization::Amplitude => 1.0 / coherent_sum(window).powi(2),
 
-                let tr = wr * re[l] - wi * im[l];
-                let ti = wr * im[l] + wi * re[l];
+        // Parseval: sum_k |X|^2 = N * sum_i (x w)^2
+        Normalization::Energy => 1.0 / (window.len() as f64 * energy_sum(window)),
+    };
+
+    if !dc.is_finite() {
+        return Err(AnalysisError::InvalidParameters);
+    }
+
+    Ok(BinGain {
+        dc: dc as f32,
+        ac: (4.0 * dc) as f32,
+    })
+}
 
-                re[l] = re[k] - tr;
-                im[l] = im[k] - ti;
-                re[k] += tr;
-                im[k] += ti;
+/// Map stream errors into analysis errors.
+fn map_audio_error(err: AudioError) -> AnalysisError {
+    match err {
+        AudioError::Cancelled => AnalysisError::Cancelled,
+        _ => AnalysisError::ProcessingFailed,
+    }
+}
+
+/// Choose the dBFS range mapped to intensity 0.0..1.0.
+///
+/// `db` holds the calibrated levels of all panels.
+fn select_range(
+    db: &[Vec<Vec<f32>>],
+    settings: &AnalysisSettings,
+) -> Result<(f32, f32), AnalysisError> {
+    let span = settings.max_db - settings.min_db;
+
+    if !span.is_finite() || span <= 0.0 {
+        return Err(AnalysisError::InvalidParameters);
+    }
+
+    let levels = || db.iter().flatten().flatten().copied();
+
+    let (min_db, max_db) = match settings.range {
+        DbRange::Fixed => (settings.min_db, settings.max_db),
+
+        DbRange::Peak => {
+            let peak = levels().fold(f32::NEG_INFINITY, f32::max);
+            (peak - span, peak)
+        }
+
+        DbRange::Percentile { low, high } => {
+            if !(0.0..=100.0).contains(&low) || !(0.0..=100.0).contains(&high) || low >= high {
+                return Err(AnalysisError::InvalidParameters);
+            }
+
+            let mut sorted: Vec<f32> = levels().collect();
+            let low = percentile(&mut sorted, low);
+            let high = percentile(&mut sorted, high);
+
+            // Flat input (e.g. silence): fall back to the configured span
+            if high - low < 1.0 {
+                (high - span, high)
+            } else {
+                (low, high)
             }
         }
-        len <<= 1;
+    };
+
+    // Empty grid: nothing to range over
+    if !min_db.is_finite() || !max_db.is_finite() {
+        return Ok((settings.min_db, settings.max_db));
+    }
+
+    Ok((min_db, max_db))
+}
+
+/// Nearest-rank percentile (0..100); reorders `values`.
+fn percentile(values: &mut [f32], p: f32) -> f32 {
+    if values.is_empty() {
+        return f32::NAN;
     }
+
+    let rank = (p as f64 / 100.0 * (values.len() - 1) as f64).round() as usize;
+    *values.select_nth_unstable_by(rank, f32::total_cmp).1
 }
 
-/// Convert power to dBFS.
-fn power_to_db(power: f32, min_db: f32) -> f32 {
+/// Convert calibrated power to dBFS.
+fn power_to_db(power: f32) -> f32 {
     let eps = 1e-12;
-    let db = 10.0 * (power + eps).log10();
-    db.max(min_db)
+    10.0 * (power + eps).log10()
+}
+
+/// Map a dBFS level to the intensity stored in the spectrogram.
+///
+/// Normalization, intensity scale and the Spek noise cutoff in one place,
+/// shared with the legend gradient so a level has one color everywhere.
+pub fn db_to_intensity(db: f32, min_db: f32, max_db: f32, scale: IntensityScale) -> f32 {
+    let scaled = apply_scale(normalize_db(db, min_db, max_db), scale);
+
+    // CRITICAL: Spek-style hard cutoff
+    if scaled < SPEK_CUTOFF {
+        0.0
+    } else {
+        scaled
+    }
 }
 
 /// Normalize dBFS into 0.0–1.0.
-fn normalize_db(db: f32, min_db: f32) -> f32 {
-    ((db - min_db) / (0.0 - min_db)).clamp(0.0, 1.0)
+fn normalize_db(db: f32, min_db: f32, max_db: f32) -> f32 {
+    ((db - min_db) / (max_db - min_db)).clamp(0.0, 1.0)
 }
 
 /// Apply intensity scaling.
@@ -182,3 +679,505 @@ fn apply_scale(v: f32, scale: IntensityScale) -> f32 {
         IntensityScale::Power(p) => v.powf(p),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{ChannelMode, WindowFunction};
+    use crate::audio::{AudioMetadata, BufferStream, RangeStream, TimeRange};
+
+    use std::cell::Cell;
+
+    const SAMPLE_RATE: u32 = 48000;
+
+    const WINDOWS: [WindowFunction; 10] = [
+        WindowFunction::Rectangular,
+        WindowFunction::Hann,
+        WindowFunction::Hamming,
+        WindowFunction::Blackman,
+        WindowFunction::Nuttall,
+        WindowFunction::BlackmanHarris,
+        WindowFunction::FlatTop,
+        WindowFunction::Kaiser(8.6),
+        WindowFunction::Gaussian(0.4),
+        WindowFunction::Tukey(0.5),
+    ];
+
+    /// Mono sine centered on bin `fft_size / 8`.
+    fn sine(amplitude: f32, fft_size: usize) -> AudioBuffer {
+        let bin = fft_size / 8;
+        let len = fft_size * 8;
+
+        let samples = (0..len)
+            .map(|i| {
+                let phase = 2.0 * std::f64::consts::PI * (bin * i) as f64 / fft_size as f64;
+                (amplitude as f64 * phase.sin()) as f32
+            })
+            .collect();
+
+        AudioBuffer {
+            samples,
+            meta: AudioMetadata {
+                sample_rate: SAMPLE_RATE,
+                channels: 1,
+                total_samples: len as u64,
+                bit_depth: None,
+                codec: None,
+                tags: Vec::new(),
+            },
+        }
+    }
+
+    /// Linear-scale settings, one time bin per frame.
+    fn settings(
+        audio: &AudioBuffer,
+        window: WindowFunction,
+        normalization: Normalization,
+    ) -> AnalysisSettings {
+        AnalysisSettings {
+            fft_size: audio.samples.len() / 8,
+            hop_size: audio.samples.len() / 16,
+            window,
+            channels: ChannelMode::Split,
+            scale: IntensityScale::Linear,
+            normalization,
+            min_db: -120.0,
+            max_db: 0.0,
+            range: DbRange::Fixed,
+            freq_min: None,
+            freq_max: None,
+            max_time_bins: None,
+            keep_db: false,
+            keep_frames: false,
+            threads: 1,
+        }
+    }
+
+    /// dBFS per bin of the first time bin (linear scale inverts exactly).
+    fn column_db(
+        audio: &AudioBuffer,
+        window: WindowFunction,
+        normalization: Normalization,
+    ) -> Vec<f32> {
+        let min_db = -120.0;
+        let settings = settings(audio, window, normalization);
+        let set = FftAnalyzer::new().analyze(audio, &settings).unwrap();
+
+        set.channels[0]
+            .data
+            .iter()
+            .map(|row| row[0] * -min_db + min_db)
+            .collect()
+    }
+
+    #[test]
+    fn amplitude_peak_reads_sine_level() {
+        // -6.02 dBFS keeps the reading clear of the 0 dB clamp
+        let expected = 20.0 * 0.5f32.log10();
+
+        for fft_size in [256, 1000, 1009, 3000, 4096] {
+            let audio = sine(0.5, fft_size);
+
+            for window in WINDOWS {
+                let db = column_db(&audio, window, Normalization::Amplitude);
+                let peak = db[fft_size / 8];
+
+                assert!(
+                    (peak - expected).abs() < 0.01,
+                    "{:?} @ {}: {} dB",
+                    window,
+                    fft_size,
+                    peak
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn amplitude_full_scale_sine_reaches_top() {
+        for fft_size in [256, 4096] {
+            let audio = sine(1.0, fft_size);
+
+            for window in WINDOWS {
+                let db = column_db(&audio, window, Normalization::Amplitude);
+                assert!(db[fft_size / 8] > -0.01, "{:?} @ {}", window, fft_size);
+            }
+        }
+    }
+
+    #[test]
+    fn energy_sum_reads_sine_level() {
+        let expected = 20.0 * 0.5f32.log10();
+
+        for fft_size in [256, 1000, 1009, 3000, 4096] {
+            let audio = sine(0.5, fft_size);
+
+            for window in WINDOWS {
+                let db = column_db(&audio, window, Normalization::Energy);
+                let total: f64 = db
+                    .iter()
+                    .filter(|&&d| d > -100.0)
+                    .map(|&d| 10f64.powf(d as f64 / 10.0))
+                    .sum();
+                let total = 10.0 * total.log10() as f32;
+
+                assert!(
+                    (total - expected).abs() < 0.05,
+                    "{:?} @ {}: {} dB",
+                    window,
+                    fft_size,
+                    total
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn max_db_maps_to_full_intensity() {
+        let audio = sine(0.5, 1024);
+        let mut settings = settings(&audio, WindowFunction::Hann, Normalization::Amplitude);
+        settings.max_db = -20.0;
+
+        let set = FftAnalyzer::new().analyze(&audio, &settings).unwrap();
+
+        assert_eq!((set.min_db, set.max_db), (-120.0, -20.0));
+        assert_eq!(set.channels[0].data[128][0], 1.0);
+    }
+
+    #[test]
+    fn peak_range_tracks_loudest_bin() {
+        let audio = sine(0.5, 1024);
+        let mut settings = settings(&audio, WindowFunction::Hann, Normalization::Amplitude);
+        settings.range = DbRange::Peak;
+
+        let set = FftAnalyzer::new().analyze(&audio, &settings).unwrap();
+
+        assert!((set.max_db - 20.0 * 0.5f32.log10()).abs() < 0.01);
+        assert!((set.max_db - set.min_db - 120.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn percentile_range_rejects_inverted_bounds() {
+        let audio = sine(0.5, 1024);
+        let mut settings = settings(&audio, WindowFunction::Hann, Normalization::Amplitude);
+        settings.range = DbRange::Percentile { low: 90.0, high: 10.0 };
+
+        assert!(matches!(
+            FftAnalyzer::new().analyze(&audio, &settings),
+            Err(AnalysisError::InvalidParameters)
+        ));
+    }
+
+    /// Stereo chirp + pseudo-noise, `len` frames.
+    fn chirp(len: usize) -> AudioBuffer {
+        let mut seed = 1u32;
+        let samples = (0..len * 2)
+            .map(|i| {
+                seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                let noise = (seed >> 8) as f32 / (1u32 << 24) as f32 - 0.5;
+                let t = (i / 2) as f32 / SAMPLE_RATE as f32;
+                0.5 * (2000.0 * t * t).sin() + 0.1 * noise
+            })
+            .collect();
+
+        AudioBuffer {
+            samples,
+            meta: AudioMetadata {
+                sample_rate: SAMPLE_RATE,
+                channels: 2,
+                total_samples: len as u64,
+                bit_depth: None,
+                codec: None,
+                tags: Vec::new(),
+            },
+        }
+    }
+
+    thread_local! {
+        /// Longest mixed history of the last analysis on this thread.
+        pub(super) static HISTORY_PEAK: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Stream reporting an estimated `total_samples`, like ffmpeg.
+    struct Estimated<'a> {
+        inner: BufferStream<'a>,
+        meta: AudioMetadata,
+    }
+
+    impl PcmStream for Estimated<'_> {
+        fn meta(&self) -> &AudioMetadata {
+            &self.meta
+        }
+
+        fn read_block(&mut self, block: &mut Vec<f32>) -> Result<bool, AudioError> {
+            self.inner.read_block(block)
+        }
+    }
+
+    fn assert_identical(a: &SpectrogramSet, b: &SpectrogramSet) {
+        assert_eq!((a.min_db, a.max_db), (b.min_db, b.max_db));
+        assert_eq!(a.frequencies, b.frequencies);
+        assert_eq!(a.times, b.times);
+
+        for (x, y) in a.channels.iter().zip(&b.channels) {
+            assert_eq!(x.time_bins, y.time_bins);
+
+            let bits = |s: &Spectrogram| -> Vec<u32> {
+                s.data.iter().flatten().map(|v| v.to_bits()).collect()
+            };
+            assert_eq!(bits(x), bits(y));
+        }
+    }
+
+    #[test]
+    fn streaming_matches_in_memory_analysis() {
+        let audio = chirp(48000 * 2);
+        let analyzer = FftAnalyzer::new();
+
+        for max_time_bins in [Some(333), None] {
+            let mut settings = settings(&audio, WindowFunction::Hann, Normalization::Amplitude);
+            settings.fft_size = 1000;
+            settings.hop_size = 97;
+            settings.max_time_bins = max_time_bins;
+            settings.range = DbRange::Peak;
+
+            let reference = analyzer.analyze(&audio, &settings).unwrap();
+            assert_eq!(reference.channels[0].time_bins, max_time_bins.unwrap_or(979));
+
+            // Whole file
+            let streamed = analyzer.analyze_stream(&mut audio.stream(), &settings).unwrap();
+            assert_identical(&streamed, &reference);
+
+            // Estimated lengths: unknown, too short, too long
+            for total_samples in [0, 1000, 48000 * 5] {
+                let mut stream = Estimated {
+                    inner: audio.stream(),
+                    meta: AudioMetadata {
+                        total_samples,
+                        ..audio.meta.clone()
+                    },
+                };
+                let streamed = analyzer.analyze_stream(&mut stream, &settings).unwrap();
+                assert_identical(&streamed, &reference);
+            }
+
+            // Crops, against the same excerpt in memory
+            for (start, end) in [(0.25, Some(1.5)), (0.5, None)] {
+                let range = TimeRange { start, end };
+                let (skip, len) = range.frames(SAMPLE_RATE).unwrap();
+                let first = skip as usize * 2;
+                let last = len.map_or(audio.samples.len(), |len| first + len as usize * 2);
+
+                let excerpt = AudioBuffer {
+                    samples: audio.samples[first..last].to_vec(),
+                    meta: AudioMetadata {
+                        total_samples: (last - first) as u64 / 2,
+                        ..audio.meta.clone()
+                    },
+                };
+                let reference = analyzer.analyze(&excerpt, &settings).unwrap();
+
+                let mut stream = RangeStream::new(Box::new(audio.stream()), range).unwrap();
+                assert!(stream.exact_length());
+
+                let streamed = analyzer.analyze_stream(&mut stream, &settings).unwrap();
+                assert_identical(&streamed, &reference);
+            }
+        }
+    }
+
+    #[test]
+    fn kept_frames_ignore_the_time_bin_cap() {
+        let audio = chirp(48000 * 2);
+        let analyzer = FftAnalyzer::new();
+
+        let mut settings = settings(&audio, WindowFunction::Hann, Normalization::Amplitude);
+        settings.fft_size = 1000;
+        settings.hop_size = 97;
+        settings.channels = ChannelMode::Split;
+        settings.max_time_bins = Some(333);
+
+        let capped = analyzer.analyze(&audio, &settings).unwrap();
+        assert!(capped.frame_times.is_none());
+
+        settings.keep_frames = true;
+        let kept = analyzer.analyze(&audio, &settings).unwrap();
+
+        // The binned grid does not change
+        assert_identical(&kept, &capped);
+        assert!(capped.channels.iter().all(|c| c.frame_db.is_none()));
+
+        // One column per frame, same values as without a cap
+        settings.keep_frames = false;
+        settings.keep_db = true;
+        settings.max_time_bins = None;
+        let full = analyzer.analyze(&audio, &settings).unwrap();
+
+        assert_eq!(kept.frame_times.as_ref(), Some(&full.times));
+        assert_eq!(full.times[0], 500.0 / SAMPLE_RATE as f64);
+
+        for (kept, full) in kept.channels.iter().zip(&full.channels) {
+            let frame_db = kept.frame_db.as_ref().unwrap();
+            assert_eq!(frame_db[0].len(), 979);
+            assert_eq!(frame_db, full.db.as_ref().unwrap());
+        }
+    }
+
+    #[test]
+    fn history_stays_bounded_without_a_known_length() {
+        // 60 s, far more frames than bins: merged several times
+        let audio = chirp(48000 * 60);
+        let mut settings = settings(&audio, WindowFunction::Hann, Normalization::Amplitude);
+        settings.fft_size = 1024;
+        settings.hop_size = 256;
+        settings.max_time_bins = Some(100);
+
+        let reference = FftAnalyzer::new().analyze(&audio, &settings).unwrap();
+        assert_eq!(reference.channels[0].time_bins, 100);
+
+        // Mean frame centers increase across the grid and cover the file
+        let times = &reference.times;
+        assert!(times.windows(2).all(|t| t[0] < t[1]));
+        assert!(times[0] < 0.5 && times[99] > 59.5);
+
+        HISTORY_PEAK.with(|peak| peak.set(0));
+
+        let mut stream = Estimated {
+            inner: audio.stream(),
+            meta: AudioMetadata {
+                total_samples: 0,
+                ..audio.meta.clone()
+            },
+        };
+        assert!(!stream.exact_length());
+
+        let streamed = FftAnalyzer::new().analyze_stream(&mut stream, &settings).unwrap();
+        assert_identical(&streamed, &reference);
+
+        // One batch of hops, the window and one decoded block
+        let mut block = Vec::new();
+        audio.stream().read_block(&mut block).unwrap();
+        let bound = FRAMES_PER_TASK * 256 + 1024 + block.len() / 2;
+
+        let peak = HISTORY_PEAK.with(Cell::get);
+        assert!(peak <= bound, "history grew to {} samples (bound {})", peak, bound);
+    }
+
+    #[test]
+    fn thread_count_does_not_change_output() {
+        // Long enough for many batches
+        let audio = chirp(48000 * 3);
+
+        let run = |threads: usize| {
+            let mut settings = settings(&audio, WindowFunction::Hann, Normalization::Amplitude);
+            settings.fft_size = 1000;
+            settings.hop_size = 97;
+            settings.max_time_bins = Some(333);
+            settings.threads = threads;
+
+            FftAnalyzer::new().analyze(&audio, &settings).unwrap()
+        };
+
+        let single = run(1);
+        for threads in [2, 3, 8] {
+            let multi = run(threads);
+
+            for (a, b) in single.channels.iter().zip(&multi.channels) {
+                for (x, y) in a.data.iter().flatten().zip(b.data.iter().flatten()) {
+                    assert_eq!(x.to_bits(), y.to_bits(), "{} threads", threads);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mid_side_of_dual_mono_has_silent_side() {
+        let mono = sine(0.5, 1024);
+        let audio = AudioBuffer {
+            samples: mono.samples.iter().flat_map(|&x| [x, x]).collect(),
+            meta: AudioMetadata {
+                channels: 2,
+                ..mono.meta.clone()
+            },
+        };
+
+        let settings_for = |audio: &AudioBuffer| {
+            settings(audio, WindowFunction::Hann, Normalization::Amplitude)
+        };
+
+        let mut settings = settings_for(&mono);
+        settings.channels = ChannelMode::MidSide;
+
+        let set = FftAnalyzer::new().analyze(&audio, &settings).unwrap();
+        let labels: Vec<_> = set.channels.iter().map(|s| s.label.as_deref()).collect();
+
+        assert_eq!(labels, [Some("Mid"), Some("Side")]);
+
+        // (x + x) / 2 == x exactly: Mid matches the mono analysis
+        let reference = FftAnalyzer::new().analyze(&mono, &settings_for(&mono)).unwrap();
+        assert_eq!(set.channels[0].data, reference.channels[0].data);
+        assert!(set.channels[1].data.iter().flatten().all(|&v| v == 0.0));
+    }
+
+    #[test]
+    fn split_gives_every_channel_its_own_labeled_panel() {
+        let loud = sine(0.5, 1024);
+        let quiet = sine(0.25, 1024);
+
+        // L: loud sine, R: silence, C: quiet sine
+        let audio = AudioBuffer {
+            samples: loud
+                .samples
+                .iter()
+                .zip(&quiet.samples)
+                .flat_map(|(&l, &c)| [l, 0.0, c])
+                .collect(),
+            meta: AudioMetadata {
+                channels: 3,
+                ..loud.meta.clone()
+            },
+        };
+
+        let settings = settings(&loud, WindowFunction::Hann, Normalization::Amplitude);
+        let set = FftAnalyzer::new().analyze(&audio, &settings).unwrap();
+        let labels: Vec<_> = set.channels.iter().map(|s| s.label.as_deref()).collect();
+
+        assert_eq!(labels, [Some("L"), Some("R"), Some("C")]);
+
+        // Unit weights pass each channel through exactly
+        let mono = |audio: &AudioBuffer| FftAnalyzer::new().analyze(audio, &settings).unwrap();
+        assert_eq!(set.channels[0].data, mono(&loud).channels[0].data);
+        assert!(set.channels[1].data.iter().flatten().all(|&v| v == 0.0));
+        assert_eq!(set.channels[2].data, mono(&quiet).channels[0].data);
+    }
+
+    #[test]
+    fn freq_crop_keeps_matching_bins() {
+        let audio = sine(0.5, 1024);
+        let full_settings = settings(&audio, WindowFunction::Hann, Normalization::Amplitude);
+
+        // 48 kHz / 1024 = 46.875 Hz per bin: 1000..2000 Hz -> bins 21..43
+        let mut crop_settings = full_settings.clone();
+        crop_settings.freq_min = Some(1000.0);
+        crop_settings.freq_max = Some(2000.0);
+
+        let full = FftAnalyzer::new().analyze(&audio, &full_settings).unwrap();
+        let crop = FftAnalyzer::new().analyze(&audio, &crop_settings).unwrap();
+
+        assert_eq!(crop.channels[0].freq_bins, 22);
+        assert_eq!(crop.channels[0].data, full.channels[0].data[21..43]);
+        assert_eq!(crop.min_freq, 21.0 * 46.875);
+        assert_eq!(crop.max_freq, 43.0 * 46.875);
+
+        // One convention: `frequencies` are the lower bin edges of the axis
+        for set in [&full, &crop] {
+            let last = set.frequencies[set.frequencies.len() - 1];
+            assert_eq!(set.frequencies[0], set.min_freq as f64);
+            assert_eq!(last + 46.875, set.max_freq as f64);
+        }
+        assert_eq!(full.max_freq, 24000.0);
+
+        crop_settings.freq_min = Some(3000.0);
+        assert!(FftAnalyzer::new().analyze(&audio, &crop_settings).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! Channel mixing for spek-core.
+//!
+//! Every channel mode is expressed as a mix matrix:
+//! each output panel is a weighted sum of the input channels,
+//! applied to the PCM before windowing.
+//!
+//! Default channel order follows WAVE / FLAC / ffmpeg:
+//! L R C LFE Ls Rs (5.1), L R C LFE Lb Rb Ls Rs (7.1).
+
+use crate::analysis::{AnalysisError, ChannelMode};
+
+/// One analyzed signal (one spectrogram panel).
+#[derive(Debug, Clone)]
+pub struct MixPanel {
+    /// Weight per input channel
+    pub weights: Vec<f32>,
+
+    /// Panel label for the legend, if any
+    pub label: Option<String>,
+}
+
+impl MixPanel {
+    /// Mix one interleaved frame (all input channels).
+    #[inline]
+    pub fn apply(&self, frame: &[f32]) -> f32 {
+        self.weights
+            .iter()
+            .zip(frame)
+            .fold(0.0, |acc, (&w, &x)| acc + w * x)
+    }
+}
+
+/// Build the mix matrix for `channels` input channels.
+///
+/// Returns `InvalidParameters` if the mode does not fit the input,
+/// e.g. `SelectChannel(2)` on a stereo file.
+pub fn build_mix(mode: &ChannelMode, channels: usize) -> Result<Vec<MixPanel>, AnalysisError> {
+    if channels == 0 {
+        return Err(AnalysisError::InvalidParameters);
+    }
+
+    let panels = match mode {
+        // Plain average of all channels
+        ChannelMode::Combined => vec![MixPanel {
+            weights: vec![1.0 / channels as f32; channels],
+            label: None,
+        }],
+
+        ChannelMode::Split => (0..channels)
+            .map(|ch| MixPanel {
+                weights: unit(ch, channels),
+                label: (channels > 1).then(|| channel_label(ch, channels)),
+            })
+            .collect(),
+
+        ChannelMode::MidSide => {
+            if channels < 2 {
+                return Err(AnalysisError::InvalidParameters);
+            }
+
+            let mut mid = vec![0.0; channels];
+            let mut side = vec![0.0; channels];
+            mid[..2].copy_from_slice(&[0.5, 0.5]);
+            side[..2].copy_from_slice(&[0.5, -0.5]);
+
+            vec![
+                MixPanel {
+                    weights: mid,
+                    label: Some("Mid".to_string()),
+                },
+                MixPanel {
+                    weights: side,
+                    label: Some("Side".to_string()),
+                },
+            ]
+        }
+
+        ChannelMode::SelectChannel(ch) => {
+            if *ch >= channels {
+                return Err(AnalysisError::InvalidParameters);
+            }
+
+            vec![MixPanel {
+                weights: unit(*ch, channels),
+                label: Some(channel_label(*ch, channels)),
+            }]
+        }
+
+        ChannelMode::Matrix(rows) => {
+            let valid = !rows.is_empty()
+                && rows.iter().all(|row| {
+                    row.weights.len() == channels && row.weights.iter().all(|w| w.is_finite())
+                });
+
+            if !valid {
+                return Err(AnalysisError::InvalidParameters);
+            }
+
+            rows.clone()
+        }
+    };
+
+    Ok(panels)
+}
+
+/// Weights selecting a single channel.
+fn unit(ch: usize, channels: usize) -> Vec<f32> {
+    let mut weights = vec![0.0; channels];
+    weights[ch] = 1.0;
+    weights
+}
+
+/// Short name of channel `ch` in the default layout for `channels`.
+pub fn channel_label(ch: usize, channels: usize) -> String {
+    let names: &[&str] = match channels {
+        1 => &["M"],
+        2 => &["L", "R"],
+        3 => &["L", "R", "C"],
+        4 => &["L", "R", "Ls", "Rs"],
+        5 => &["L", "R", "C", "Ls", "Rs"],
+        6 => &["L", "R", "C", "LFE", "Ls", "Rs"],
+        7 => &["L", "R", "C", "LFE", "Cs", "Ls", "Rs"],
+        8 => &["L", "R", "C", "LFE", "Lb", "Rb", "Ls", "Rs"],
+        _ => &[],
+    };
+
+    match names.get(ch) {
+        Some(name) => name.to_string(),
+        None => format!("Ch {}", ch + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(panels: &[MixPanel]) -> Vec<Option<&str>> {
+        panels.iter().map(|p| p.label.as_deref()).collect()
+    }
+
+    fn row(weights: &[f32]) -> MixPanel {
+        MixPanel {
+            weights: weights.to_vec(),
+            label: Some("Row".to_string()),
+        }
+    }
+
+    #[test]
+    fn mid_side_sums_and_differences_the_front_pair() {
+        let panels = build_mix(&ChannelMode::MidSide, 6).unwrap();
+        let frame = [0.5, 0.25, 1.0, 1.0, 1.0, 1.0];
+
+        assert_eq!(labels(&panels), [Some("Mid"), Some("Side")]);
+        assert_eq!(panels[0].apply(&frame), 0.375);
+        assert_eq!(panels[1].apply(&frame), 0.125);
+
+        assert!(matches!(
+            build_mix(&ChannelMode::MidSide, 1),
+            Err(AnalysisError::InvalidParameters)
+        ));
+    }
+
+    #[test]
+    fn select_channel_picks_one_channel_in_range() {
+        let panels = build_mix(&ChannelMode::SelectChannel(3), 6).unwrap();
+
+        assert_eq!(labels(&panels), [Some("LFE")]);
+        assert_eq!(panels[0].apply(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]), 4.0);
+
+        for (ch, channels) in [(2, 2), (6, 6), (usize::MAX, 8)] {
+            assert!(matches!(
+                build_mix(&ChannelMode::SelectChannel(ch), channels),
+                Err(AnalysisError::InvalidParameters)
+            ));
+        }
+    }
+
+    #[test]
+    fn matrix_rows_must_match_the_input() {
+        let rows = vec![row(&[0.5, 0.5]), row(&[1.0, -1.0])];
+        let panels = build_mix(&ChannelMode::Matrix(rows), 2).unwrap();
+
+        assert_eq!(labels(&panels), [Some("Row"), Some("Row")]);
+        assert_eq!(panels[1].apply(&[0.75, 0.25]), 0.5);
+
+        let invalid = [
+            // Empty matrix
+            vec![],
+            // Too narrow, too wide
+            vec![row(&[1.0])],
+            vec![row(&[0.5, 0.5]), row(&[1.0, 0.0, 0.0])],
+            // Non-finite weight
+            vec![row(&[f32::NAN, 1.0])],
+        ];
+
+        for rows in invalid {
+            assert!(matches!(
+                build_mix(&ChannelMode::Matrix(rows), 2),
+                Err(AnalysisError::InvalidParameters)
+            ));
+        }
+    }
+
+    #[test]
+    fn surround_layouts_are_labeled_in_wave_order() {
+        let names = |channels: usize| -> Vec<String> {
+            (0..channels).map(|ch| channel_label(ch, channels)).collect()
+        };
+
+        assert_eq!(names(1), ["M"]);
+        assert_eq!(names(2), ["L", "R"]);
+        assert_eq!(names(6), ["L", "R", "C", "LFE", "Ls", "Rs"]);
+        assert_eq!(names(8), ["L", "R", "C", "LFE", "Lb", "Rb", "Ls", "Rs"]);
+
+        // Unknown layouts and out-of-layout channels are numbered
+        assert_eq!(names(10)[9], "Ch 10");
+        assert_eq!(channel_label(6, 6), "Ch 7");
+
+        // Split: one unit-weight panel per channel, mono unlabeled
+        let panels = build_mix(&ChannelMode::Split, 6).unwrap();
+        let frame = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        assert_eq!(labels(&panels)[3], Some("LFE"));
+        assert!(panels.iter().zip(frame).all(|(p, x)| p.apply(&frame) == x));
+        assert_eq!(labels(&build_mix(&ChannelMode::Split, 1).unwrap()), [None]);
+    }
+}
@@ -3,7 +3,11 @@
 //! This module defines the data model for numerical
 //! spectrograms. It contains NO DSP logic and NO rendering.
 
+pub mod engine;
 pub mod fft;
+pub mod mix;
+pub mod report;
+pub mod window;
 
 /// Scaling applied to intensity values.
 #[derive(Debug, Copy, Clone)]
@@ -23,8 +27,73 @@ pub enum WindowFunction {
     Hamming,
     Blackman,
     Nuttall,
-    Kaiser,
+    BlackmanHarris,
     FlatTop,
+
+    /// Kaiser window with shape parameter beta (>= 0, e.g. 8.6)
+    Kaiser(f32),
+
+    /// Gaussian window with standard deviation sigma,
+    /// relative to half the window size (> 0, e.g. 0.4)
+    Gaussian(f32),
+
+    /// Tukey (tapered cosine) window with taper ratio alpha
+    /// (0.0 = rectangular, 1.0 = Hann)
+    Tukey(f32),
+}
+
+/// Channel handling before the FFT.
+#[derive(Debug, Clone)]
+pub enum ChannelMode {
+    /// All channels averaged into one signal (one panel)
+    Combined,
+
+    /// One panel per input channel, in file order
+    Split,
+
+    /// Mid (L+R)/2 and Side (L-R)/2 of the front pair (channels 0 and 1)
+    MidSide,
+
+    /// A single input channel (0-based)
+    SelectChannel(usize),
+
+    /// User downmix: one panel per row, one weight per input channel
+    Matrix(Vec<mix::MixPanel>),
+}
+
+/// Power normalization applied to every FFT bin.
+///
+/// Both modes remove the dependency on FFT size and window,
+/// so a full-scale sine reads 0 dBFS:
+#[derive(Debug, Copy, Clone)]
+pub enum Normalization {
+    /// Coherent gain correction.
+    ///
+    /// The PEAK bin of a full-scale sine reads 0 dBFS.
+    /// Best for reading tone levels.
+    Amplitude,
+
+    /// Window energy correction.
+    ///
+    /// The TOTAL power of a full-scale sine, summed over its bins,
+    /// reads 0 dBFS. Best for noise and broadband content.
+    Energy,
+}
+
+/// How the dBFS range mapped to intensity 0.0..1.0 is chosen.
+#[derive(Debug, Copy, Clone)]
+pub enum DbRange {
+    /// Use `min_db` / `max_db` as given.
+    Fixed,
+
+    /// Top of the range at the loudest bin of the file.
+    ///
+    /// The span `max_db - min_db` is kept.
+    Peak,
+
+    /// Both ends at percentiles (0..100) of all bin levels,
+    /// e.g. `{ low: 1.0, high: 99.9 }`. Robust against single spikes.
+    Percentile { low: f32, high: f32 },
 }
 
 /// Parameters controlling the signal analysis stage.
@@ -39,11 +108,60 @@ pub struct AnalysisSettings {
     /// Window function
     pub window: WindowFunction,
 
+    /// Channel mixing (one spectrogram per output panel)
+    pub channels: ChannelMode,
+
     /// Intensity scaling after dB mapping
     pub scale: IntensityScale,
 
+    /// Power normalization (dBFS calibration)
+    pub normalization: Normalization,
+
     /// Minimum dBFS floor (e.g. -120.0)
     pub min_db: f32,
+
+    /// dBFS mapped to full intensity (e.g. 0.0)
+    pub max_db: f32,
+
+    /// dBFS range selection
+    pub range: DbRange,
+
+    /// Lowest frequency kept, in Hz (`None` = 0 Hz)
+    pub freq_min: Option<f32>,
+
+    /// Highest frequency kept, in Hz (`None` = Nyquist)
+    ///
+    /// Only bins in `freq_min..freq_max` are post-processed and stored;
+    /// the dBFS range is selected within them.
+    pub freq_max: Option<f32>,
+
+    /// Upper bound on the number of time bins.
+    ///
+    /// Consecutive frames sharing a bin are power-averaged,
+    /// so memory is bounded by the output grid, not the audio length.
+    /// `None` keeps one time bin per frame.
+    ///
+    /// The frame count is not needed up front: frames are summed into
+    /// at most `2 * max` bins, adjacent bins merge whenever that fills up,
+    /// and the result is spread over `max` bins at the end of the stream.
+    pub max_time_bins: Option<usize>,
+
+    /// Also keep the calibrated dBFS values (`Spectrogram::db`).
+    ///
+    /// Doubles the memory of the result.
+    pub keep_db: bool,
+
+    /// Also keep the dBFS of every single frame (`Spectrogram::frame_db`),
+    /// independent of `max_time_bins`.
+    ///
+    /// Memory then grows with the audio length. `data` and `db`
+    /// are unaffected.
+    pub keep_frames: bool,
+
+    /// Worker threads for the FFT stage (0 = all available cores).
+    ///
+    /// Output is bit-identical for every value.
+    pub threads: usize,
 }
 
 /// Numerical spectrogram for a single channel.
@@ -55,15 +173,59 @@ pub struct Spectrogram {
     pub freq_bins: usize,
     pub time_bins: usize,
     pub data: Vec<Vec<f32>>,
+
+    /// dBFS before range mapping and scaling, same layout as `data`
+    /// (only with `AnalysisSettings::keep_db`)
+    pub db: Option<Vec<Vec<f32>>>,
+
+    /// dBFS per frame, `[freq_bin][frame]`, not binned
+    /// (only with `AnalysisSettings::keep_frames`)
+    pub frame_db: Option<Vec<Vec<f32>>>,
+
+    /// Panel label (e.g. "L", "LFE"), `None` for a single mix
+    pub label: Option<String>,
 }
 
 /// Multi-channel spectrogram output.
 ///
 /// - Combined mode: one entry
 /// - Split mode: one entry per channel
+///
+/// Entries are stacked top to bottom when rendered.
 #[derive(Debug)]
 pub struct SpectrogramSet {
     pub channels: Vec<Spectrogram>,
+
+    /// dBFS mapped to intensity 0.0 (after range selection)
+    pub min_db: f32,
+
+    /// dBFS mapped to intensity 1.0 (after range selection)
+    pub max_db: f32,
+
+    /// Intensity scale applied after normalization
+    pub scale: IntensityScale,
+
+    /// Frequency of the bottom edge of the lowest bin, in Hz
+    pub min_freq: f32,
+
+    /// Frequency of the top edge of the highest bin (Nyquist), in Hz
+    pub max_freq: f32,
+
+    /// Lower edge of each bin, in Hz: `k * rate / fft_size`
+    ///
+    /// Bin `k` covers `frequencies[k] .. frequencies[k] + rate / fft_size`
+    /// on the frequency axis (Spek's edge-aligned layout), so
+    /// `frequencies[0] == min_freq`. The value is also the DFT bin frequency.
+    pub frequencies: Vec<f64>,
+
+    /// Center of each time bin, in seconds from the start of the analyzed audio
+    ///
+    /// The mean center of the frames averaged into the bin.
+    pub times: Vec<f64>,
+
+    /// Window center of each frame, in seconds
+    /// (only with `AnalysisSettings::keep_frames`)
+    pub frame_times: Option<Vec<f64>>,
 }
 
 /// Signal analysis interface.
@@ -78,6 +240,19 @@ pub trait Analyzer {
     ) -> Result<SpectrogramSet, AnalysisError>;
 }
 
+/// Incremental signal analysis interface.
+///
+/// Consumes PCM blocks as they are decoded and keeps only
+/// `fft_size` samples (plus one batch of hops) of history per channel,
+/// whether or not the stream length is known.
+pub trait StreamingAnalyzer {
+    fn analyze_stream(
+        &self,
+        stream: &mut dyn crate::audio::PcmStream,
+        settings: &AnalysisSettings,
+    ) -> Result<SpectrogramSet, AnalysisError>;
+}
+
 /// Analysis errors.
 #[derive(Debug)]
 pub enum AnalysisError {
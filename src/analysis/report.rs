@@ -0,0 +1,387 @@
+//! Spectral quality report for spek-core.
+//!
+//! Estimates the effective bandwidth of the audio from a finished
+//! `SpectrogramSet` and looks for the brick-wall lowpass that
+//! lossy encoders and band-limited resamplers leave behind.
+//!
+//! Works on the analysis grid only: no PCM, no FFT, no pixels.
+//! The same spectrogram always yields the same report.
+
+use crate::analysis::{IntensityScale, SpectrogramSet};
+
+/// Lowpass shelves of common lossy encoders, in Hz.
+///
+/// (cutoff, typical source)
+pub const ENCODER_SHELVES: [(f32, &str); 3] = [
+    (16000.0, "MP3 <= 128 kbps / AAC low bitrate"),
+    (19500.0, "MP3 ~192 kbps / AAC ~160 kbps"),
+    (20500.0, "MP3 >= 256 kbps / AAC high bitrate"),
+];
+
+/// Sample rates a hi-res file is typically upsampled from.
+pub const SOURCE_RATES: [u32; 2] = [44100, 48000];
+
+/// Thresholds of the detector.
+#[derive(Debug, Clone)]
+pub struct ReportSettings {
+    /// Level above the column floor that counts as content, in dB
+    pub content_db: f32,
+
+    /// Minimum level drop across a brick wall, in dB
+    pub wall_db: f32,
+
+    /// Width of a brick-wall transition, relative to Nyquist
+    pub transition: f32,
+
+    /// Columns whose peak is less than this above the floor are skipped, in dB
+    pub silence_db: f32,
+
+    /// Distance to an encoder shelf still counted as a match, in Hz
+    pub shelf_tolerance: f32,
+}
+
+impl Default for ReportSettings {
+    fn default() -> Self {
+        Self {
+            content_db: 12.0,
+            wall_db: 24.0,
+            transition: 0.015,
+            silence_db: 30.0,
+            shelf_tolerance: 300.0,
+        }
+    }
+}
+
+/// Overall assessment of the file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// Too little signal to judge
+    Inconclusive,
+
+    /// No brick wall below the converter's anti-alias filter
+    FullBandwidth,
+
+    /// Brick wall at a known encoder shelf
+    LossyTranscode {
+        /// Matched shelf, in Hz
+        shelf: f32,
+
+        /// Typical encoder settings for that shelf
+        encoder: &'static str,
+    },
+
+    /// Hi-res file band-limited to the Nyquist of a lower rate
+    Upsampled { source_rate: u32 },
+
+    /// Brick wall at another frequency (unknown lowpass)
+    Lowpassed { cutoff: f32 },
+}
+
+/// Result of `build_report`.
+#[derive(Debug, Clone)]
+pub struct SpectralReport {
+    /// Highest frequency with content, per time bin (`None` = silent), in Hz
+    pub bandwidth: Vec<Option<f32>>,
+
+    /// Brick-wall cutoff, per time bin (`None` = no wall), in Hz
+    pub cutoff: Vec<Option<f32>>,
+
+    /// Median brick-wall cutoff over the whole file, in Hz
+    pub median_cutoff: Option<f32>,
+
+    pub verdict: Verdict,
+
+    /// Confidence of the verdict (0.0..1.0)
+    pub confidence: f32,
+}
+
+impl SpectralReport {
+    /// Cutoff worth marking on the frequency axis, if any.
+    pub fn marker(&self) -> Option<f32> {
+        match self.verdict {
+            Verdict::Inconclusive | Verdict::FullBandwidth => None,
+            _ => self.median_cutoff,
+        }
+    }
+}
+
+/// Analyze `set` (produced from audio at `sample_rate`).
+pub fn build_report(
+    set: &SpectrogramSet,
+    sample_rate: u32,
+    settings: &ReportSettings,
+) -> SpectralReport {
+    let freq_bins = set.channels.iter().map(|s| s.freq_bins).min().unwrap_or(0);
+    let time_bins = set.channels.iter().map(|s| s.time_bins).min().unwrap_or(0);
+
+    let nyquist = sample_rate as f32 / 2.0;
+    let bin_hz = (set.max_freq - set.min_freq) / freq_bins.max(1) as f32;
+
+    // -----------------------------------------------------------------
+    // 1. Per-column bandwidth and brick-wall cutoff
+    // -----------------------------------------------------------------
+    let smooth = (freq_bins / 256).max(1);
+    let span = ((settings.transition * nyquist / bin_hz).ceil() as usize).max(2);
+
+    let mut bandwidth = Vec::with_capacity(time_bins);
+    let mut cutoff = Vec::with_capacity(time_bins);
+    let mut profile = vec![0.0f32; freq_bins];
+
+    for t in 0..time_bins {
+        // Loudest panel per bin
+        for (f, level) in profile.iter_mut().enumerate() {
+            *level = set
+                .channels
+                .iter()
+                .map(|s| to_db(s.data[f][t], set))
+                .fold(f32::NEG_INFINITY, f32::max);
+        }
+
+        let column = column_edge(&profile, smooth, span, settings);
+
+        let freq = |edge: usize| set.min_freq + (edge + 1) as f32 * bin_hz;
+        bandwidth.push(column.map(|(edge, _)| freq(edge)));
+        cutoff.push(column.and_then(|(edge, wall)| wall.then(|| freq(edge))));
+    }
+
+    // -----------------------------------------------------------------
+    // 2. Vote over time
+    // -----------------------------------------------------------------
+    let signal = bandwidth.iter().flatten().count();
+
+    let mut walls: Vec<f32> = cutoff.iter().flatten().copied().collect();
+    walls.sort_by(f32::total_cmp);
+    let median_cutoff = walls.get(walls.len() / 2).copied();
+
+    let (verdict, confidence) = match median_cutoff {
+        _ if signal < MIN_COLUMNS => (Verdict::Inconclusive, 0.0),
+
+        Some(median) if median < WALL_LIMIT * nyquist => {
+            let tolerance = (2.0 * span as f32 * bin_hz).max(settings.shelf_tolerance);
+
+            // Columns at the wall vs. columns with content above it
+            let agree = walls.iter().filter(|&&c| (c - median).abs() <= tolerance).count();
+            let above = bandwidth
+                .iter()
+                .flatten()
+                .filter(|&&b| b > median + tolerance)
+                .count();
+
+            let consistency = agree as f32 / (agree + above).max(1) as f32;
+            let coverage = (agree as f32 / (MIN_COVERAGE * signal as f32)).min(1.0);
+
+            if consistency < MIN_CONSISTENCY || agree < MIN_COLUMNS {
+                (Verdict::FullBandwidth, 1.0 - consistency * coverage)
+            } else {
+                (classify(median, sample_rate, settings), consistency * coverage)
+            }
+        }
+
+        // No wall, or only the converter's own anti-alias filter
+        _ => {
+            let low = walls.iter().filter(|&&c| c < WALL_LIMIT * nyquist).count();
+
+            (Verdict::FullBandwidth, 1.0 - low as f32 / signal as f32)
+        }
+    };
+
+    SpectralReport {
+        bandwidth,
+        cutoff,
+        median_cutoff,
+        verdict,
+        confidence,
+    }
+}
+
+/// Fewest usable columns for any verdict.
+const MIN_COLUMNS: usize = 8;
+
+/// Walls above this fraction of Nyquist are anti-alias filters.
+const WALL_LIMIT: f32 = 0.95;
+
+/// Share of columns that must hit the wall for full confidence.
+const MIN_COVERAGE: f32 = 0.5;
+
+/// Share of wall columns (vs. columns above it) needed for a verdict.
+const MIN_CONSISTENCY: f32 = 0.75;
+
+/// Content edge of one column: (highest content bin, is a brick wall).
+///
+/// `None` for silent columns.
+fn column_edge(
+    profile: &[f32],
+    smooth: usize,
+    span: usize,
+    settings: &ReportSettings,
+) -> Option<(usize, bool)> {
+    let bins = profile.len();
+    if bins == 0 {
+        return None;
+    }
+
+    // Moving average against single-bin noise
+    let level = |f: usize| {
+        let lo = f.saturating_sub(smooth);
+        let hi = (f + smooth + 1).min(bins);
+        profile[lo..hi].iter().sum::<f32>() / (hi - lo) as f32
+    };
+
+    // Floor: 10th percentile of the column
+    let mut sorted = profile.to_vec();
+    let rank = bins / 10;
+    let floor = *sorted.select_nth_unstable_by(rank, f32::total_cmp).1;
+    let peak = profile.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+    if peak - floor < settings.silence_db {
+        return None;
+    }
+
+    let threshold = floor + settings.content_db;
+    let smoothed = (0..bins).rev().find(|&f| level(f) >= threshold)?;
+
+    // Undo the smoothing spread: last raw bin above the threshold
+    let edge = (smoothed.saturating_sub(smooth)..=smoothed)
+        .rev()
+        .find(|&f| profile[f] >= threshold)
+        .unwrap_or(smoothed);
+
+    // Brick wall: steep drop right at the edge, nothing above it
+    let below = level(edge.saturating_sub(span / 2));
+    let above = profile[(edge + span).min(bins)..]
+        .iter()
+        .copied()
+        .fold(floor, f32::max);
+
+    let wall = edge + span < bins && below - above >= settings.wall_db;
+
+    Some((edge, wall))
+}
+
+/// Name a brick wall at `cutoff` Hz.
+fn classify(cutoff: f32, sample_rate: u32, settings: &ReportSettings) -> Verdict {
+    // A lossy source is the stronger finding, even when upsampled
+    for (shelf, encoder) in ENCODER_SHELVES {
+        if (cutoff - shelf).abs() <= settings.shelf_tolerance {
+            return Verdict::LossyTranscode { shelf, encoder };
+        }
+    }
+
+    // Resampler lowpass sits just below the source Nyquist
+    if sample_rate >= 88200 {
+        for source_rate in SOURCE_RATES {
+            let source_nyquist = source_rate as f32 / 2.0;
+
+            if cutoff > 0.85 * source_nyquist && cutoff <= source_nyquist + settings.shelf_tolerance {
+                return Verdict::Upsampled { source_rate };
+            }
+        }
+    }
+
+    Verdict::Lowpassed { cutoff }
+}
+
+/// Grid intensity back to dBFS (0.0 maps to the floor).
+fn to_db(value: f32, set: &SpectrogramSet) -> f32 {
+    let norm = match set.scale {
+        IntensityScale::Linear => value,
+        IntensityScale::Sqrt => value * value,
+        IntensityScale::Cbrt => value * value * value,
+        IntensityScale::Log => (10f32.powf(3.0 * value) - 1.0) / 1000.0,
+        IntensityScale::Power(p) => value.powf(1.0 / p),
+    };
+
+    set.min_db + norm * (set.max_db - set.min_db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::Spectrogram;
+
+    const RATE: u32 = 44100;
+    const BINS: usize = 1024;
+    const COLUMNS: usize = 64;
+
+    /// Grid with pseudo-noise content up to `edge_hz`, floor above it.
+    ///
+    /// `rolloff_db`: level lost per kHz (0 = flat up to a brick wall).
+    fn grid(edge_hz: f32, rolloff_db: f32) -> SpectrogramSet {
+        let bin_hz = RATE as f32 / 2.0 / BINS as f32;
+        let mut seed = 7u32;
+
+        let data = (0..BINS)
+            .map(|f| {
+                let freq = f as f32 * bin_hz;
+
+                (0..COLUMNS)
+                    .map(|_| {
+                        seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                        let jitter = (seed >> 8) as f32 / (1u32 << 24) as f32 * 6.0;
+
+                        let db = if freq < edge_hz {
+                            -30.0 - rolloff_db * freq / 1000.0 - jitter
+                        } else {
+                            -120.0
+                        };
+
+                        ((db + 120.0) / 120.0).max(0.0)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        SpectrogramSet {
+            channels: vec![Spectrogram {
+                freq_bins: BINS,
+                time_bins: COLUMNS,
+                data,
+                db: None,
+                frame_db: None,
+                label: None,
+            }],
+            min_db: -120.0,
+            max_db: 0.0,
+            scale: IntensityScale::Linear,
+            min_freq: 0.0,
+            max_freq: RATE as f32 / 2.0,
+            frequencies: Vec::new(),
+            times: Vec::new(),
+            frame_times: None,
+        }
+    }
+
+    #[test]
+    fn detects_encoder_shelf() {
+        let report = build_report(&grid(16000.0, 0.5), RATE, &ReportSettings::default());
+
+        assert!(matches!(
+            report.verdict,
+            Verdict::LossyTranscode { shelf, .. } if shelf == 16000.0
+        ));
+        assert!(report.confidence > 0.9);
+        assert!((report.marker().unwrap() - 16000.0).abs() < 100.0);
+    }
+
+    #[test]
+    fn natural_rolloff_is_full_bandwidth() {
+        // -4 dB / kHz: content fades out gradually towards Nyquist
+        let report = build_report(&grid(f32::INFINITY, 4.0), RATE, &ReportSettings::default());
+
+        assert_eq!(report.verdict, Verdict::FullBandwidth);
+        assert_eq!(report.confidence, 1.0);
+        assert_eq!(report.marker(), None);
+    }
+
+    #[test]
+    fn detects_upsampling() {
+        // Same bins spread over 0..48 kHz (96 kHz file): wall at 21 kHz,
+        // the lowpass of a 44.1 kHz source
+        let mut set = grid(21000.0 * (RATE as f32 / 2.0) / 48000.0, 0.0);
+        set.max_freq = 48000.0;
+
+        let report = build_report(&set, 96000, &ReportSettings::default());
+
+        assert_eq!(report.verdict, Verdict::Upsampled { source_rate: 44100 });
+    }
+}
@@ -1,49 +1,222 @@
-//! Public spectrogram generation API.
+//! Spectrogram generation pipeline.
 //!
-//! This module defines the single, stable entry point into spek-core.
+//! This module wires the core stages together:
 //!
-//! spek-core follows the Spek / spek-rs model strictly:
-//! - ffmpeg renders the spectrogram image
-//! - spek-core only overlays legend information
+//! AudioSource -> Analyzer -> Renderer -> LegendRenderer -> overlay
 //!
-//! No DSP, no FFT, no color mapping happens here.
+//! The streaming variant decodes and analyzes block by block,
+//! so peak memory is bounded by the output grid, not the audio length
+//! (without `keep_data`), even when the stream length is only an estimate.
+//!
+//! Every stage is injected as a trait object.
+//! The palette and the legend font are resolved from the settings.
+//! This function contains NO DSP, NO color mapping and NO drawing itself.
 
-use crate::api::result::SpectrogramResult;
-use crate::api::settings::SpekSettings;
+use crate::api::image::ImageBuffer;
+use crate::api::result::{
+    ChannelData, QualityReport, SpectrogramData, SpectrogramResult, Verdict,
+};
+use crate::api::settings::{
+    ChannelMode, DbRange, FontFamily, FontWeight, FrequencyScale, Normalization,
+    PaletteInterpolation, PaletteLutSize, PaletteSource, ScaleMode, SpekSettings, WindowFunction,
+};
 
-use crate::audio::{AudioSource, AudioError};
-use crate::legend::{
-    LegendRenderer, LegendContext, LegendSettings, LegendMargins,
+use crate::analysis::fft::db_to_intensity;
+use crate::analysis::mix::MixPanel;
+use crate::analysis::report::{self, build_report, ReportSettings, SpectralReport};
+use crate::analysis::{
+    self, AnalysisError, AnalysisSettings, Analyzer, IntensityScale, SpectrogramSet,
+    StreamingAnalyzer,
+};
+use crate::audio::{
+    AudioError, AudioMetadata, AudioSource, PcmStream, StreamingAudioSource, TimeRange,
+};
+use crate::color::{
+    ColorMapper, Interpolation, LutSize, Palette, PaletteError, SaturatedColorMapper, Stop,
+    UserPalette,
 };
+use crate::legend::font::{self, BitmapFont, FontRasterizer};
+use crate::legend::{LegendRenderer, LegendContext, LegendSettings};
+use crate::render::{self, RenderError, Renderer};
 
 /// Generate a spectrogram image including legend.
 ///
-/// This is the ONLY public entry point of spek-core.
-///
 /// One call → one deterministic result.
 pub fn generate_spectrogram(
     source: &dyn AudioSource,
+    analyzer: &dyn Analyzer,
+    renderer: &dyn Renderer,
     legend: &dyn LegendRenderer,
     settings: &SpekSettings,
 ) -> Result<SpectrogramResult, GenerateError> {
+    // Fail on a bad palette or font before any decoding work
+    let color = color_mapper(settings)?;
+    let font = legend_font(settings)?;
+
     // ---------------------------------------------------------------------
-    // 1. Load spectrogram image + metadata from audio source
+    // 1. Decode audio
     // ---------------------------------------------------------------------
-    let source_result = source
-        .load()
+    let audio = source
+        .load_range(time_range(settings))
         .map_err(map_audio_error)?;
 
-    let mut image = source_result.image;
-    let meta = source_result.meta;
+    // ---------------------------------------------------------------------
+    // 2. Analyze PCM into a numerical spectrogram
+    // ---------------------------------------------------------------------
+    let spectrogram = analyzer
+        .analyze(&audio, &analysis_settings(settings))
+        .map_err(map_analysis_error)?;
 
+    finish(
+        spectrogram,
+        &audio.meta,
+        renderer,
+        legend,
+        color.as_ref(),
+        font.as_ref(),
+        settings,
+    )
+}
+
+/// Generate a spectrogram image including legend from a PCM stream.
+///
+/// Audio is decoded and analyzed incrementally.
+/// Output is bit-identical to `generate_spectrogram` for the same audio.
+pub fn generate_spectrogram_streaming(
+    source: &dyn StreamingAudioSource,
+    analyzer: &dyn StreamingAnalyzer,
+    renderer: &dyn Renderer,
+    legend: &dyn LegendRenderer,
+    settings: &SpekSettings,
+) -> Result<SpectrogramResult, GenerateError> {
+    let color = color_mapper(settings)?;
+    let font = legend_font(settings)?;
+
+    // ---------------------------------------------------------------------
+    // 1. Open the PCM stream
     // ---------------------------------------------------------------------
-    // 2. Build legend context
+    let mut stream = CountingStream {
+        inner: source
+            .open_range(time_range(settings))
+            .map_err(map_audio_error)?,
+        frames: 0,
+    };
+
     // ---------------------------------------------------------------------
+    // 2. Analyze blocks as they are decoded
+    // ---------------------------------------------------------------------
+    let spectrogram = analyzer
+        .analyze_stream(&mut stream, &analysis_settings(settings))
+        .map_err(map_analysis_error)?;
+
+    // An estimated length is replaced by what was actually decoded
+    let mut meta = stream.meta().clone();
+    if !stream.exact_length() {
+        meta.total_samples = stream.frames;
+    }
+
+    finish(
+        spectrogram,
+        &meta,
+        renderer,
+        legend,
+        color.as_ref(),
+        font.as_ref(),
+        settings,
+    )
+}
+
+/// `PcmStream` wrapper counting the frames actually decoded.
+struct CountingStream<'a> {
+    inner: Box<dyn PcmStream + 'a>,
+    frames: u64,
+}
+
+impl PcmStream for CountingStream<'_> {
+    fn meta(&self) -> &AudioMetadata {
+        self.inner.meta()
+    }
+
+    fn exact_length(&self) -> bool {
+        self.inner.exact_length()
+    }
+
+    fn read_block(&mut self, block: &mut Vec<f32>) -> Result<bool, AudioError> {
+        let more = self.inner.read_block(block)?;
+        self.frames += (block.len() / (self.meta().channels as usize).max(1)) as u64;
+        Ok(more)
+    }
+}
+
+/// Shared pipeline tail: render, legend, overlay.
+fn finish(
+    spectrogram: SpectrogramSet,
+    meta: &AudioMetadata,
+    renderer: &dyn Renderer,
+    legend: &dyn LegendRenderer,
+    color: &dyn ColorMapper,
+    font: &dyn FontRasterizer,
+    settings: &SpekSettings,
+) -> Result<SpectrogramResult, GenerateError> {
+    // ---------------------------------------------------------------------
+    // 3. Render the spectrogram area
+    // ---------------------------------------------------------------------
+    let freq_scale = match settings.render.freq_scale {
+        FrequencyScale::Linear => render::FrequencyScale::Linear,
+        FrequencyScale::Log => render::FrequencyScale::Log,
+        FrequencyScale::Mel => render::FrequencyScale::Mel,
+        FrequencyScale::Bark => render::FrequencyScale::Bark,
+        FrequencyScale::Erb => render::FrequencyScale::Erb,
+    };
+
+    let plot = renderer
+        .render(
+            &spectrogram,
+            &render::RenderSettings {
+                width: settings.render.width,
+                height: settings.render.height,
+                freq_scale,
+            },
+            color,
+        )
+        .map_err(map_render_error)?;
+
+    // ---------------------------------------------------------------------
+    // 4. Assess bandwidth (lossy transcode / upsampling)
+    // ---------------------------------------------------------------------
+    let report = build_report(&spectrogram, meta.sample_rate, &ReportSettings::default());
+
+    let cutoff = match settings.render.cutoff_marker {
+        true => report.marker().map(f64::from),
+        false => None,
+    };
+
+    // ---------------------------------------------------------------------
+    // 5. Build legend context
+    // ---------------------------------------------------------------------
+    let legend_settings = LegendSettings {
+        font_size: settings.legend.font_size,
+        freq_ticks: 20,
+        time_ticks: 10,
+        db_ticks: 6,
+    };
+
     let legend_context = LegendContext {
         audio: meta.clone(),
-        duration_sec: meta.duration_sec,
-        min_db: settings.spectrogram.min_db,
-        max_db: settings.spectrogram.max_db,
+        duration_sec: meta.duration_sec(),
+        time_offset: time_range(settings).start,
+        min_db: spectrogram.min_db,
+        max_db: spectrogram.max_db,
+        scale: spectrogram.scale,
+        panels: spectrogram
+            .channels
+            .iter()
+            .map(|s| s.label.clone())
+            .collect(),
+        min_freq: spectrogram.min_freq as f64,
+        max_freq: spectrogram.max_freq as f64,
+        freq_scale,
+        cutoff,
 
         // Optional informational fields
         file_name: None,
@@ -54,49 +227,290 @@ pub fn generate_spectrogram(
     };
 
     // ---------------------------------------------------------------------
-    // 3. Generate legend commands
+    // 6. Lay out the legend and place the spectrogram inside its margins
+    //
+    // The plot keeps the requested size, the image grows around it.
+    // ---------------------------------------------------------------------
+    let margins = legend.layout(
+        &legend_settings,
+        &legend_context,
+        font,
+        plot.width as u32,
+        plot.height as u32,
+    );
+
+    let mut image = ImageBuffer::new(
+        plot.width + (margins.left + margins.right) as usize,
+        plot.height + (margins.top + margins.bottom) as usize,
+    );
+    image.blit(&plot, margins.left as usize, margins.top as usize);
+
+    // ---------------------------------------------------------------------
+    // 7. Generate legend commands
     // ---------------------------------------------------------------------
     let legend_commands = legend.generate(
-        &LegendSettings {
-            font_size: 14,
-            freq_ticks: 10,
-            time_ticks: 10,
-            db_ticks: 6,
-        },
+        &legend_settings,
         &legend_context,
-        LegendMargins {
-            left: 80,
-            right: 100,
-            top: 60,
-            bottom: 60,
-        },
+        font,
+        margins,
         image.width as u32,
         image.height as u32,
     );
 
     // ---------------------------------------------------------------------
-    // 4. Apply legend overlay
+    // 8. Apply legend overlay (same colors as the spectrogram)
     // ---------------------------------------------------------------------
     crate::legend::overlay::apply_legend_overlay(
         &mut image,
         &legend_commands,
+        color,
+        font,
     );
 
     // ---------------------------------------------------------------------
-    // 5. Assemble result
+    // 9. Assemble result
     // ---------------------------------------------------------------------
     Ok(SpectrogramResult {
         image,
-        duration_seconds: meta.duration_sec,
+        duration_seconds: meta.duration_sec(),
         sample_rate: meta.sample_rate,
         channels: meta.channels as u32,
+        min_db: spectrogram.min_db,
+        max_db: spectrogram.max_db,
+        report: quality_report(report),
+        data: spectrogram_data(spectrogram, time_range(settings).start),
+    })
+}
+
+/// Hand the per-frame spectrogram to the caller, if it was kept.
+///
+/// Intensities use the same range and scale as the image.
+/// Times are shifted from the excerpt to the file start.
+fn spectrogram_data(spectrogram: SpectrogramSet, time_offset: f64) -> Option<SpectrogramData> {
+    let (min_db, max_db, scale) = (spectrogram.min_db, spectrogram.max_db, spectrogram.scale);
+
+    let channels = spectrogram
+        .channels
+        .into_iter()
+        .map(|s| {
+            let db = s.frame_db?;
+            let intensity = db
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|&v| db_to_intensity(v, min_db, max_db, scale))
+                        .collect()
+                })
+                .collect();
+
+            Some(ChannelData {
+                label: s.label,
+                db,
+                intensity,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(SpectrogramData {
+        frequencies: spectrogram.frequencies,
+        times: spectrogram
+            .frame_times?
+            .iter()
+            .map(|t| t + time_offset)
+            .collect(),
+        min_db: spectrogram.min_db,
+        max_db: spectrogram.max_db,
+        channels,
     })
 }
 
+/// Translate the analysis report into the public result type.
+fn quality_report(report: SpectralReport) -> QualityReport {
+    let verdict = match report.verdict {
+        report::Verdict::Inconclusive => Verdict::Inconclusive,
+        report::Verdict::FullBandwidth => Verdict::FullBandwidth,
+        report::Verdict::LossyTranscode { shelf, encoder } => Verdict::LossyTranscode {
+            shelf_hz: shelf,
+            encoder: encoder.to_string(),
+        },
+        report::Verdict::Upsampled { source_rate } => Verdict::Upsampled { source_rate },
+        report::Verdict::Lowpassed { cutoff } => Verdict::Lowpassed { cutoff_hz: cutoff },
+    };
+
+    QualityReport {
+        verdict,
+        confidence: report.confidence,
+        cutoff_hz: report.marker(),
+        bandwidth_hz: report.bandwidth,
+        cutoff_over_time_hz: report.cutoff,
+    }
+}
+
 /// Public error type for spectrogram generation.
 #[derive(Debug)]
 pub enum GenerateError {
+    InvalidSettings,
+    FontFailed,
+    PaletteFailed,
     DecodeFailed,
+    AnalysisFailed,
+    RenderFailed,
+}
+
+/// Color mapper of the palette selected in the settings.
+///
+/// Built-in palettes are looked up by name, stops are validated;
+/// a saturation other than 1.0 wraps the palette.
+fn color_mapper(settings: &SpekSettings) -> Result<Box<dyn ColorMapper>, GenerateError> {
+    let lut = |size: PaletteLutSize| match size {
+        PaletteLutSize::Entries256 => LutSize::Small,
+        PaletteLutSize::Entries4096 => LutSize::Large,
+    };
+    let interp = |interpolation: PaletteInterpolation| match interpolation {
+        PaletteInterpolation::Srgb => Interpolation::Srgb,
+        PaletteInterpolation::Oklab => Interpolation::Oklab,
+    };
+
+    let saturation = settings.render.saturation;
+    if !saturation.is_finite() || saturation < 0.0 {
+        return Err(GenerateError::InvalidSettings);
+    }
+
+    let palette = match &settings.render.palette {
+        PaletteSource::Builtin(name) => Palette::from_name(name)
+            .map(Palette::mapper)
+            .ok_or(GenerateError::InvalidSettings)?,
+
+        PaletteSource::File {
+            path,
+            interpolation,
+            lut_size,
+        } => Box::new(
+            UserPalette::from_path(path, interp(*interpolation), lut(*lut_size))
+                .map_err(map_palette_error)?,
+        ),
+
+        PaletteSource::Stops {
+            stops,
+            interpolation,
+            lut_size,
+        } => {
+            let stops: Vec<Stop> = stops
+                .iter()
+                .map(|&(position, color)| Stop { position, color })
+                .collect();
+            Box::new(
+                UserPalette::from_stops(&stops, interp(*interpolation), lut(*lut_size))
+                    .map_err(map_palette_error)?,
+            )
+        }
+    };
+
+    if saturation == 1.0 {
+        return Ok(palette);
+    }
+
+    Ok(Box::new(SaturatedColorMapper::new(palette, saturation)))
+}
+
+/// Load the legend font selected in the settings.
+///
+/// TrueType / OpenType sources need the `truetype` feature,
+/// without it they are rejected as invalid settings.
+fn legend_font(settings: &SpekSettings) -> Result<Box<dyn FontRasterizer>, GenerateError> {
+    let weight = match settings.legend.font_weight {
+        FontWeight::Regular => font::FontWeight::Regular,
+        FontWeight::Bold => font::FontWeight::Bold,
+    };
+
+    match &settings.legend.font_family {
+        FontFamily::Builtin => Ok(Box::new(BitmapFont::with_weight(weight))),
+
+        #[cfg(feature = "truetype")]
+        FontFamily::File(path) => font::TrueTypeFont::from_path(path)
+            .map(|f| Box::new(f.with_weight(weight)) as Box<dyn FontRasterizer>)
+            .map_err(map_font_error),
+
+        #[cfg(feature = "truetype")]
+        FontFamily::Data(data) => font::TrueTypeFont::from_bytes(data.to_vec())
+            .map(|f| Box::new(f.with_weight(weight)) as Box<dyn FontRasterizer>)
+            .map_err(map_font_error),
+
+        #[cfg(not(feature = "truetype"))]
+        FontFamily::File(_) | FontFamily::Data(_) => Err(GenerateError::InvalidSettings),
+    }
+}
+
+/// Excerpt of the audio to decode.
+fn time_range(settings: &SpekSettings) -> TimeRange {
+    TimeRange {
+        start: settings.spectrogram.time_start.unwrap_or(0.0),
+        end: settings.spectrogram.time_end,
+    }
+}
+
+/// Translate public settings into analysis settings.
+///
+/// Time resolution is capped at the output width:
+/// more bins than pixel columns would never be visible.
+/// Kept data comes from the separate per-frame grid.
+fn analysis_settings(settings: &SpekSettings) -> AnalysisSettings {
+    let max_time_bins = Some(settings.render.width);
+    let settings = &settings.spectrogram;
+
+    AnalysisSettings {
+        fft_size: settings.fft_size,
+        hop_size: settings.hop_size,
+        window: match settings.window {
+            WindowFunction::Rectangular => analysis::WindowFunction::Rectangular,
+            WindowFunction::Hann => analysis::WindowFunction::Hann,
+            WindowFunction::Hamming => analysis::WindowFunction::Hamming,
+            WindowFunction::Blackman => analysis::WindowFunction::Blackman,
+            WindowFunction::Nuttall => analysis::WindowFunction::Nuttall,
+            WindowFunction::BlackmanHarris => analysis::WindowFunction::BlackmanHarris,
+            WindowFunction::FlatTop => analysis::WindowFunction::FlatTop,
+            WindowFunction::Kaiser(beta) => analysis::WindowFunction::Kaiser(beta),
+            WindowFunction::Gaussian(sigma) => analysis::WindowFunction::Gaussian(sigma),
+            WindowFunction::Tukey(alpha) => analysis::WindowFunction::Tukey(alpha),
+        },
+        channels: match &settings.channels {
+            ChannelMode::Combined => analysis::ChannelMode::Combined,
+            ChannelMode::Split => analysis::ChannelMode::Split,
+            ChannelMode::MidSide => analysis::ChannelMode::MidSide,
+            ChannelMode::SelectChannel(ch) => analysis::ChannelMode::SelectChannel(*ch),
+            ChannelMode::Matrix(rows) => analysis::ChannelMode::Matrix(
+                rows.iter()
+                    .map(|row| MixPanel {
+                        weights: row.weights.clone(),
+                        label: row.label.clone(),
+                    })
+                    .collect(),
+            ),
+        },
+        scale: match settings.scale {
+            ScaleMode::Linear => IntensityScale::Linear,
+            ScaleMode::Sqrt => IntensityScale::Sqrt,
+            ScaleMode::Cbrt => IntensityScale::Cbrt,
+            ScaleMode::Log => IntensityScale::Log,
+        },
+        normalization: match settings.normalization {
+            Normalization::Amplitude => analysis::Normalization::Amplitude,
+            Normalization::Energy => analysis::Normalization::Energy,
+        },
+        min_db: settings.min_db,
+        max_db: settings.max_db,
+        range: match settings.range {
+            DbRange::Fixed => analysis::DbRange::Fixed,
+            DbRange::Peak => analysis::DbRange::Peak,
+            DbRange::Percentile { low, high } => analysis::DbRange::Percentile { low, high },
+        },
+        freq_min: settings.freq_min,
+        freq_max: settings.freq_max,
+        max_time_bins,
+        keep_db: false,
+        keep_frames: settings.keep_data,
+        threads: settings.threads,
+    }
 }
 
 /// Map backend audio errors to public API errors.
@@ -105,6 +519,172 @@ fn map_audio_error(err: AudioError) -> GenerateError {
         AudioError::UnsupportedFormat => GenerateError::DecodeFailed,
         AudioError::DecodeFailed => GenerateError::DecodeFailed,
         AudioError::IoError => GenerateError::DecodeFailed,
+        AudioError::ChecksumMismatch => GenerateError::DecodeFailed,
         AudioError::Cancelled => GenerateError::DecodeFailed,
+        AudioError::InvalidRange => GenerateError::InvalidSettings,
+    }
+}
+
+/// Map palette loading errors to public API errors.
+fn map_palette_error(err: PaletteError) -> GenerateError {
+    match err {
+        PaletteError::IoError => GenerateError::PaletteFailed,
+        PaletteError::Syntax { .. } => GenerateError::PaletteFailed,
+        PaletteError::TooFewStops => GenerateError::PaletteFailed,
+        PaletteError::PositionOutOfRange => GenerateError::PaletteFailed,
+        PaletteError::Unsorted => GenerateError::PaletteFailed,
+        PaletteError::Unsupported => GenerateError::PaletteFailed,
+    }
+}
+
+/// Map font loading errors to public API errors.
+#[cfg(feature = "truetype")]
+fn map_font_error(err: font::FontError) -> GenerateError {
+    match err {
+        font::FontError::IoError => GenerateError::FontFailed,
+        font::FontError::InvalidFont => GenerateError::FontFailed,
+    }
+}
+
+/// Map analysis errors to public API errors.
+fn map_analysis_error(err: AnalysisError) -> GenerateError {
+    match err {
+        AnalysisError::InvalidParameters => GenerateError::AnalysisFailed,
+        AnalysisError::ProcessingFailed => GenerateError::AnalysisFailed,
+        AnalysisError::Cancelled => GenerateError::AnalysisFailed,
+    }
+}
+
+/// Map render errors to public API errors.
+fn map_render_error(err: RenderError) -> GenerateError {
+    match err {
+        RenderError::InvalidDimensions => GenerateError::RenderFailed,
+        RenderError::EmptySpectrogram => GenerateError::RenderFailed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::fft::FftAnalyzer;
+    use crate::api::png;
+    use crate::api::settings::{LegendSettings, RenderSettings, SpectrogramSettings};
+    use crate::audio::AudioBuffer;
+    use crate::legend::simple::SimpleLegendRenderer;
+    use crate::render::basic::BasicRenderer;
+
+    /// In-memory `AudioSource`.
+    struct Memory(AudioBuffer);
+
+    impl AudioSource for Memory {
+        fn load(&self) -> Result<AudioBuffer, AudioError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    /// Two seconds of stereo: a sweep left, a quiet tone right.
+    fn source() -> Memory {
+        let samples = (0..96000 * 2)
+            .map(|i| {
+                let t = (i / 2) as f32 / 48000.0;
+                match i % 2 {
+                    0 => 0.5 * (4000.0 * t * t).sin(),
+                    _ => 0.01 * (2.0 * std::f32::consts::PI * 1000.0 * t).sin(),
+                }
+            })
+            .collect();
+
+        Memory(AudioBuffer {
+            samples,
+            meta: AudioMetadata {
+                sample_rate: 48000,
+                channels: 2,
+                total_samples: 96000,
+                bit_depth: Some(16),
+                codec: None,
+                tags: Vec::new(),
+            },
+        })
+    }
+
+    fn settings(width: usize, keep_data: bool) -> SpekSettings {
+        SpekSettings {
+            spectrogram: SpectrogramSettings {
+                fft_size: 1024,
+                hop_size: 256,
+                window: WindowFunction::Hann,
+                channels: ChannelMode::Split,
+                min_db: -120.0,
+                max_db: 0.0,
+                range: DbRange::Peak,
+                scale: ScaleMode::Sqrt,
+                normalization: Normalization::Amplitude,
+                threads: 2,
+                freq_min: None,
+                freq_max: None,
+                time_start: Some(0.5),
+                time_end: None,
+                keep_data,
+            },
+            render: RenderSettings {
+                width,
+                height: 128,
+                freq_scale: FrequencyScale::Linear,
+                cutoff_marker: false,
+                palette: PaletteSource::Builtin("spek".to_string()),
+                saturation: 1.0,
+            },
+            legend: LegendSettings {
+                font_size: 12,
+                font_family: FontFamily::Builtin,
+                font_weight: FontWeight::Regular,
+            },
+        }
+    }
+
+    fn generate(settings: &SpekSettings) -> SpectrogramResult {
+        generate_spectrogram(
+            &source(),
+            &FftAnalyzer::new(),
+            &BasicRenderer::new(),
+            &SimpleLegendRenderer::new(),
+            settings,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn keeping_data_leaves_image_and_report_unchanged() {
+        let plain = generate(&settings(200, false));
+        let kept = generate(&settings(200, true));
+
+        assert!(plain.data.is_none());
+        assert_eq!(png::encode(&plain.image, &[]), png::encode(&kept.image, &[]));
+        assert_eq!((plain.min_db, plain.max_db), (kept.min_db, kept.max_db));
+        assert_eq!(format!("{:?}", plain.report), format!("{:?}", kept.report));
+    }
+
+    #[test]
+    fn kept_data_has_one_time_bin_per_frame() {
+        let narrow = generate(&settings(100, true)).data.unwrap();
+        let wide = generate(&settings(400, true)).data.unwrap();
+
+        // 1.5 s excerpt: (72000 - 1024) / 256 frames, at any width
+        assert_eq!(narrow.times.len(), 277);
+        assert_eq!(narrow.times, wide.times);
+        assert_eq!(narrow.times[0], 0.5 + 512.0 / 48000.0);
+        assert!((narrow.times[1] - narrow.times[0] - 256.0 / 48000.0).abs() < 1e-12);
+
+        for (channel, label) in narrow.channels.iter().zip(["L", "R"]) {
+            assert_eq!(channel.label.as_deref(), Some(label));
+            assert_eq!((channel.db.len(), channel.db[0].len()), (512, 277));
+            assert_eq!(channel.intensity[0].len(), 277);
+        }
+        assert_eq!(narrow.channels[0].db, wide.channels[0].db);
+
+        // Intensities use the image's range and scale
+        let db = narrow.channels[1].db[21][100];
+        let intensity = db_to_intensity(db, narrow.min_db, narrow.max_db, IntensityScale::Sqrt);
+        assert_eq!(narrow.channels[1].intensity[21][100], intensity);
     }
 }
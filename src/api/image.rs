@@ -13,3 +13,33 @@ pub struct ImageBuffer {
     pub height: usize,
     pub data: Vec<u8>,
 }
+
+impl ImageBuffer {
+    /// Create an opaque black image.
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut data = vec![0u8; width * height * 4];
+        for px in data.chunks_exact_mut(4) {
+            px[3] = 255;
+        }
+
+        Self { width, height, data }
+    }
+
+    /// Copy `src` into this image with its top-left corner at (x, y).
+    ///
+    /// Pixels outside the destination are clipped.
+    pub fn blit(&mut self, src: &ImageBuffer, x: usize, y: usize) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        let w = src.width.min(self.width - x);
+        let h = src.height.min(self.height - y);
+
+        for row in 0..h {
+            let s = row * src.width * 4;
+            let d = ((y + row) * self.width + x) * 4;
+            self.data[d..d + w * 4].copy_from_slice(&src.data[s..s + w * 4]);
+        }
+    }
+}
@@ -8,6 +8,8 @@
 // ---------------------------------------------------------------------
 
 pub mod image;
+pub mod npy;
+pub mod png;
 pub mod generate;
 pub mod settings;
 pub mod result;
@@ -16,15 +18,17 @@ pub mod result;
 // Public API
 // ---------------------------------------------------------------------
 
-use crate::audio::AudioSource;
+use crate::audio::{AudioSource, StreamingAudioSource};
 
 use crate::analysis::fft::FftAnalyzer;
 use crate::render::basic::BasicRenderer;
 use crate::legend::simple::SimpleLegendRenderer;
-use crate::color::spek::SpekColorMapper;
 
 use generate::GenerateError;
-use settings::{SpectrogramSettings, SpekSettings, RenderSettings};
+use settings::{
+    FontFamily, FontWeight, FrequencyScale, LegendSettings, PaletteSource, SpectrogramSettings,
+    SpekSettings, RenderSettings,
+};
 use result::SpectrogramResult;
 
 /// Errors returned by spek-core.
@@ -50,31 +54,68 @@ pub fn generate_spectrogram(
     // Instantiate default core components
     // -----------------------------------------------------------------
     let analyzer = FftAnalyzer::new();
-    let color_mapper = SpekColorMapper::new();
-    let renderer = BasicRenderer::new(&color_mapper);
+    let renderer = BasicRenderer::new();
     let legend = SimpleLegendRenderer::new();
 
-    // -----------------------------------------------------------------
-    // Build full internal settings
-    // -----------------------------------------------------------------
-    let spek_settings = SpekSettings {
-        spectrogram: settings.clone(),
-        render: RenderSettings {
-            width: 1024,
-            height: 512,
-        },
-    };
-
     generate::generate_spectrogram(
         source,
         &analyzer,
         &renderer,
         &legend,
-        &spek_settings,
+        &default_settings(settings),
     )
-    .map_err(|e| match e {
+    .map_err(map_generate_error)
+}
+
+/// Generate a spectrogram image with a mandatory legend from a PCM stream.
+///
+/// Same output as `generate_spectrogram`, with memory use
+/// independent of the audio length.
+pub fn generate_spectrogram_streaming(
+    source: &dyn StreamingAudioSource,
+    settings: &SpectrogramSettings,
+) -> Result<SpectrogramResult, SpekError> {
+    let analyzer = FftAnalyzer::new();
+    let renderer = BasicRenderer::new();
+    let legend = SimpleLegendRenderer::new();
+
+    generate::generate_spectrogram_streaming(
+        source,
+        &analyzer,
+        &renderer,
+        &legend,
+        &default_settings(settings),
+    )
+    .map_err(map_generate_error)
+}
+
+/// Build full internal settings around the spectrogram settings.
+fn default_settings(settings: &SpectrogramSettings) -> SpekSettings {
+    SpekSettings {
+        spectrogram: settings.clone(),
+        render: RenderSettings {
+            width: 1024,
+            height: 512,
+            freq_scale: FrequencyScale::Linear,
+            cutoff_marker: false,
+            palette: PaletteSource::Builtin("spek".to_string()),
+            saturation: 1.0,
+        },
+        legend: LegendSettings {
+            font_size: 14,
+            font_family: FontFamily::Builtin,
+            font_weight: FontWeight::Regular,
+        },
+    }
+}
+
+fn map_generate_error(e: GenerateError) -> SpekError {
+    match e {
+        GenerateError::InvalidSettings => SpekError::InvalidInput,
+        GenerateError::FontFailed => SpekError::InvalidInput,
+        GenerateError::PaletteFailed => SpekError::InvalidInput,
         GenerateError::DecodeFailed => SpekError::DecodeError,
         GenerateError::AnalysisFailed => SpekError::AnalysisError,
         GenerateError::RenderFailed => SpekError::RenderError,
-    })
+    }
 }
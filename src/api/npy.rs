@@ -0,0 +1,347 @@
+//! NumPy export of the numeric spectrogram.
+//!
+//! `.npy` (format 1.0) for single arrays, `.npz` (uncompressed zip)
+//! for all of them. Like `api::png`, this module only produces bytes;
+//! writing files is up to the caller.
+//!
+//! Output is deterministic: fixed entry order, fixed zip timestamps.
+
+use crate::api::png::crc32;
+use crate::api::result::SpectrogramData;
+
+/// All arrays of a spectrogram as (name, `.npy` bytes).
+///
+/// - `frequencies`: float64 `[freq_bin]`, lower edge of each bin as drawn, Hz
+/// - `times`: float64 `[time_bin]`, window center of each frame,
+///   seconds from the file start
+/// - `db_range`: float32 `[2]`, dBFS mapped to intensity 0.0 and 1.0
+/// - `labels`: unicode `[channel]`, panel labels (empty for a single mix)
+/// - `db_<n>`: float32 `[freq_bin][time_bin]`, calibrated dBFS of panel `n`
+/// - `intensity_<n>`: float32 `[freq_bin][time_bin]`, 0.0..1.0 of panel `n`
+pub fn arrays(data: &SpectrogramData) -> Vec<(String, Vec<u8>)> {
+    let mut arrays = vec![
+        ("frequencies".to_string(), f64_array(&data.frequencies)),
+        ("times".to_string(), f64_array(&data.times)),
+        ("db_range".to_string(), f32_array(&[2], &[data.min_db, data.max_db])),
+        (
+            "labels".to_string(),
+            str_array(data.channels.iter().map(|c| c.label.as_deref().unwrap_or(""))),
+        ),
+    ];
+
+    for (n, channel) in data.channels.iter().enumerate() {
+        arrays.push((format!("db_{}", n), f32_matrix(&channel.db)));
+        arrays.push((format!("intensity_{}", n), f32_matrix(&channel.intensity)));
+    }
+
+    arrays
+}
+
+/// Pack `.npy` arrays into an `.npz` archive (stored, no compression).
+///
+/// Entries are named `<name>.npy`, as `numpy.savez` does.
+/// Archives beyond the classic zip limits (4 GiB, 65535 entries)
+/// get ZIP64 records; smaller ones are plain zip.
+pub fn npz(arrays: &[(String, Vec<u8>)]) -> Vec<u8> {
+    zip(arrays, ZIP64_LIMIT)
+}
+
+/// Sizes and offsets from this value on are stored in ZIP64 fields.
+const ZIP64_LIMIT: u64 = u32::MAX as u64;
+
+/// Zip archive writer; `limit` is lowered by the tests.
+fn zip(arrays: &[(String, Vec<u8>)], limit: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut directory = Vec::new();
+
+    for (name, data) in arrays {
+        let name = format!("{}.npy", name);
+        let offset = out.len() as u64;
+        let size = data.len() as u64;
+        let crc = crc32(data);
+
+        // Local file header: ZIP64 extra with both sizes
+        let large = size >= limit;
+        let extra = if large { zip64_extra(&[size, size]) } else { Vec::new() };
+
+        out.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        zip_entry_fields(&mut out, &name, field(size, limit), crc, &extra);
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(&extra);
+        out.extend_from_slice(data);
+
+        // Central directory record: ZIP64 extra with the overflowing fields
+        let fields: Vec<u64> = [size, size, offset]
+            .into_iter()
+            .filter(|&value| value >= limit)
+            .collect();
+        let extra = if fields.is_empty() { Vec::new() } else { zip64_extra(&fields) };
+
+        directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        directory.extend_from_slice(&version(!extra.is_empty()).to_le_bytes());
+        zip_entry_fields(&mut directory, &name, field(size, limit), crc, &extra);
+        directory.extend_from_slice(&[0; 10]); // comment, disk, attributes
+        directory.extend_from_slice(&field(offset, limit).to_le_bytes());
+        directory.extend_from_slice(name.as_bytes());
+        directory.extend_from_slice(&extra);
+    }
+
+    let directory_offset = out.len() as u64;
+    let directory_size = directory.len() as u64;
+    let entries = arrays.len() as u64;
+    out.extend_from_slice(&directory);
+
+    if entries >= 0xffff || directory_size >= limit || directory_offset >= limit {
+        let record_offset = out.len() as u64;
+
+        // ZIP64 end of central directory record
+        out.extend_from_slice(&0x0606_4b50u32.to_le_bytes());
+        out.extend_from_slice(&44u64.to_le_bytes()); // remaining record size
+        out.extend_from_slice(&45u16.to_le_bytes()); // version made by
+        out.extend_from_slice(&45u16.to_le_bytes()); // version needed
+        out.extend_from_slice(&[0; 8]); // disks
+        out.extend_from_slice(&entries.to_le_bytes());
+        out.extend_from_slice(&entries.to_le_bytes());
+        out.extend_from_slice(&directory_size.to_le_bytes());
+        out.extend_from_slice(&directory_offset.to_le_bytes());
+
+        // ZIP64 end of central directory locator
+        out.extend_from_slice(&0x0706_4b50u32.to_le_bytes());
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&record_offset.to_le_bytes());
+        out.extend_from_slice(&1u32.to_le_bytes());
+    }
+
+    // End of central directory (saturated fields point to ZIP64)
+    let entries = entries.min(0xffff) as u16;
+    out.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&entries.to_le_bytes());
+    out.extend_from_slice(&entries.to_le_bytes());
+    out.extend_from_slice(&field(directory_size, limit).to_le_bytes());
+    out.extend_from_slice(&field(directory_offset, limit).to_le_bytes());
+    out.extend_from_slice(&[0; 2]);
+
+    out
+}
+
+/// Zip header fields shared by local and central records,
+/// from "version needed" to "extra field length".
+fn zip_entry_fields(out: &mut Vec<u8>, name: &str, size: u32, crc: u32, extra: &[u8]) {
+    out.extend_from_slice(&version(!extra.is_empty()).to_le_bytes()); // version needed
+    out.extend_from_slice(&0u16.to_le_bytes()); // flags
+    out.extend_from_slice(&0u16.to_le_bytes()); // stored
+    out.extend_from_slice(&0u16.to_le_bytes()); // time 00:00:00
+    out.extend_from_slice(&0x0021u16.to_le_bytes()); // date 1980-01-01
+    out.extend_from_slice(&crc.to_le_bytes());
+    out.extend_from_slice(&size.to_le_bytes());
+    out.extend_from_slice(&size.to_le_bytes());
+    out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(extra.len() as u16).to_le_bytes());
+}
+
+/// 32-bit zip field: the value, or 0xFFFFFFFF if it is stored in ZIP64.
+fn field(value: u64, limit: u64) -> u32 {
+    if value >= limit {
+        u32::MAX
+    } else {
+        value as u32
+    }
+}
+
+/// Zip version: 2.0 for plain entries, 4.5 for ZIP64.
+fn version(zip64: bool) -> u16 {
+    if zip64 {
+        45
+    } else {
+        20
+    }
+}
+
+/// ZIP64 extended information extra field.
+fn zip64_extra(fields: &[u64]) -> Vec<u8> {
+    let mut extra = Vec::with_capacity(4 + fields.len() * 8);
+    extra.extend_from_slice(&0x0001u16.to_le_bytes());
+    extra.extend_from_slice(&(fields.len() as u16 * 8).to_le_bytes());
+    for field in fields {
+        extra.extend_from_slice(&field.to_le_bytes());
+    }
+    extra
+}
+
+// -------------------------------------------------------------------------
+// .npy arrays
+// -------------------------------------------------------------------------
+
+fn f64_array(values: &[f64]) -> Vec<u8> {
+    let payload = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    npy("<f8", &[values.len()], payload)
+}
+
+fn f32_array(shape: &[usize], values: &[f32]) -> Vec<u8> {
+    let payload = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    npy("<f4", shape, payload)
+}
+
+/// Row-major `[rows][columns]` matrix.
+fn f32_matrix(rows: &[Vec<f32>]) -> Vec<u8> {
+    let columns = rows.first().map_or(0, Vec::len);
+    let values: Vec<f32> = rows.iter().flatten().copied().collect();
+    f32_array(&[rows.len(), columns], &values)
+}
+
+/// Fixed-width UTF-32 strings (NumPy `<U`).
+fn str_array<'a>(values: impl Iterator<Item = &'a str>) -> Vec<u8> {
+    let values: Vec<Vec<char>> = values.map(|v| v.chars().collect()).collect();
+    let width = values.iter().map(Vec::len).max().unwrap_or(0).max(1);
+
+    let mut payload = Vec::with_capacity(values.len() * width * 4);
+    for value in &values {
+        for i in 0..width {
+            let c = value.get(i).map_or(0, |&c| c as u32);
+            payload.extend_from_slice(&c.to_le_bytes());
+        }
+    }
+
+    npy(&format!("<U{}", width), &[values.len()], payload)
+}
+
+/// `.npy` format 1.0: magic, header dict padded to 64 bytes, raw data.
+fn npy(descr: &str, shape: &[usize], payload: Vec<u8>) -> Vec<u8> {
+    let shape = match shape {
+        [n] => format!("({},)", n),
+        _ => format!(
+            "({})",
+            shape.iter().map(usize::to_string).collect::<Vec<_>>().join(", ")
+        ),
+    };
+
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+        descr, shape
+    );
+    // Magic (6) + version (2) + length (2) + header + '\n'
+    let padding = (64 - (10 + header.len() + 1) % 64) % 64;
+    header.extend(std::iter::repeat_n(' ', padding));
+    header.push('\n');
+
+    let mut out = b"\x93NUMPY\x01\x00".to_vec();
+    out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    out.extend_from_slice(header.as_bytes());
+    out.extend(payload);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(bytes: &[u8], at: usize) -> u64 {
+        u16::from_le_bytes(bytes[at..at + 2].try_into().unwrap()) as u64
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u64 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()) as u64
+    }
+
+    fn u64_at(bytes: &[u8], at: usize) -> u64 {
+        u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap())
+    }
+
+    /// Minimal reader for stored zip archives, ZIP64 included.
+    fn unzip(zip: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let end = zip.len() - 22;
+        assert_eq!(u32_at(zip, end), 0x0605_4b50);
+
+        let mut entries = u16_at(zip, end + 10);
+        let mut directory = u32_at(zip, end + 16) as usize;
+
+        if entries == 0xffff || directory == u32::MAX as usize {
+            assert_eq!(u32_at(zip, end - 20), 0x0706_4b50);
+            let record = u64_at(zip, end - 12) as usize;
+
+            assert_eq!(u32_at(zip, record), 0x0606_4b50);
+            entries = u64_at(zip, record + 32);
+            directory = u64_at(zip, record + 48) as usize;
+        }
+
+        (0..entries)
+            .map(|_| {
+                assert_eq!(u32_at(zip, directory), 0x0201_4b50);
+                let crc = u32_at(zip, directory + 16);
+                let name_len = u16_at(zip, directory + 28) as usize;
+                let extra_len = u16_at(zip, directory + 30) as usize;
+                let name = &zip[directory + 46..directory + 46 + name_len];
+                let extra = directory + 46 + name_len;
+
+                // Saturated fields are read from the ZIP64 extra, in order
+                let mut zip64 = (extra + 4..extra + extra_len).step_by(8);
+                let mut field = |value: u64| match value {
+                    0xffff_ffff => u64_at(zip, zip64.next().unwrap()),
+                    value => value,
+                };
+                let size = field(u32_at(zip, directory + 24)) as usize;
+                field(u32_at(zip, directory + 20));
+                let offset = field(u32_at(zip, directory + 42)) as usize;
+                directory = extra + extra_len;
+
+                assert_eq!(u32_at(zip, offset), 0x0403_4b50);
+                assert_eq!(&zip[offset + 30..offset + 30 + name_len], name);
+                let start = offset + 30 + name_len + u16_at(zip, offset + 28) as usize;
+                let data = zip[start..start + size].to_vec();
+
+                assert_eq!(crc32(&data) as u64, crc);
+                (String::from_utf8(name.to_vec()).unwrap(), data)
+            })
+            .collect()
+    }
+
+    fn entries(count: usize, size: usize) -> Vec<(String, Vec<u8>)> {
+        (0..count)
+            .map(|i| (format!("a{}", i), vec![i as u8; size * (i % 5)]))
+            .collect()
+    }
+
+    fn named(arrays: &[(String, Vec<u8>)]) -> Vec<(String, Vec<u8>)> {
+        arrays
+            .iter()
+            .map(|(name, data)| (format!("{}.npy", name), data.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn small_archives_are_plain_zip() {
+        let arrays = entries(5, 150);
+        let zip = npz(&arrays);
+
+        assert_eq!(unzip(&zip), named(&arrays));
+        assert!(!zip.windows(4).any(|w| w == 0x0606_4b50u32.to_le_bytes()));
+    }
+
+    #[test]
+    fn zip64_records_cover_large_sizes_offsets_and_counts() {
+        // Sizes, offsets and the directory beyond a lowered limit
+        let arrays = entries(5, 150);
+        let zip = zip(&arrays, 100);
+
+        assert_eq!(unzip(&zip), named(&arrays));
+        assert!(zip.windows(4).any(|w| w == 0x0606_4b50u32.to_le_bytes()));
+
+        // More entries than the 16-bit count holds
+        let arrays = entries(70_000, 1);
+        assert_eq!(unzip(&npz(&arrays)), named(&arrays));
+    }
+
+    #[test]
+    fn npy_header_is_aligned() {
+        let array = f32_array(&[3, 2], &[0.0; 6]);
+        let header_len = u16::from_le_bytes([array[8], array[9]]) as usize;
+
+        assert_eq!(&array[..8], b"\x93NUMPY\x01\x00");
+        assert_eq!((10 + header_len) % 64, 0);
+        assert!(std::str::from_utf8(&array[10..10 + header_len])
+            .unwrap()
+            .contains("'shape': (3, 2)"));
+        assert_eq!(array.len(), 10 + header_len + 6 * 4);
+    }
+}
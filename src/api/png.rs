@@ -0,0 +1,983 @@
+//! Native PNG encoder for spek-core.
+//!
+//! Deterministic by construction: one filter heuristic, one fixed set of
+//! deflate parameters, no timestamps and no external compressor.
+//! The same image and metadata always give the same bytes,
+//! on every platform and across dependency updates.
+//!
+//! Text metadata records how an image was made (see `metadata`).
+
+use crate::api::image::ImageBuffer;
+use crate::api::result::SpectrogramResult;
+use crate::api::settings::{FontFamily, PaletteSource, SpekSettings};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+
+/// Encode an RGBA image as PNG with (keyword, text) metadata.
+///
+/// Fully opaque images are stored as RGB.
+/// Values that are not Latin-1 go into iTXt (UTF-8) chunks, all others into tEXt.
+pub fn encode(image: &ImageBuffer, text: &[(String, String)]) -> Vec<u8> {
+    let opaque = image.data.chunks_exact(4).all(|px| px[3] == 255);
+    let channels = if opaque { 3 } else { 4 };
+
+    let mut out = SIGNATURE.to_vec();
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(image.width as u32).to_be_bytes());
+    header.extend_from_slice(&(image.height as u32).to_be_bytes());
+    // 8 bit, RGB / RGBA, deflate, adaptive filtering, no interlace
+    header.extend_from_slice(&[8, if opaque { 2 } else { 6 }, 0, 0, 0]);
+    chunk(&mut out, b"IHDR", &header);
+
+    for (keyword, value) in text {
+        text_chunk(&mut out, keyword, value);
+    }
+
+    let filtered = filter(image, channels);
+    chunk(&mut out, b"IDAT", &zlib(&filtered));
+    chunk(&mut out, b"IEND", &[]);
+
+    out
+}
+
+/// Metadata describing how `result` was made.
+///
+/// Input file name, sample rate and every analysis, render and legend
+/// setting, as (keyword, text) pairs for `encode`.
+/// No version and no date: metadata must not break byte-identical output.
+pub fn metadata(
+    settings: &SpekSettings,
+    file_name: Option<&str>,
+    result: &SpectrogramResult,
+) -> Vec<(String, String)> {
+    let s = &settings.spectrogram;
+    let r = &settings.render;
+    let l = &settings.legend;
+
+    let auto = |v: Option<String>| v.unwrap_or_else(|| "auto".to_string());
+
+    let palette = match &r.palette {
+        PaletteSource::Builtin(name) => name.clone(),
+        PaletteSource::File {
+            path,
+            interpolation,
+            lut_size,
+        } => format!("{} ({:?}, {:?})", path.display(), interpolation, lut_size),
+        PaletteSource::Stops {
+            stops,
+            interpolation,
+            lut_size,
+        } => format!("{:?} ({:?}, {:?})", stops, interpolation, lut_size),
+    };
+
+    let font = match &l.font_family {
+        FontFamily::Builtin => "builtin".to_string(),
+        FontFamily::File(path) => path.display().to_string(),
+        FontFamily::Data(data) => format!("{} bytes in memory", data.len()),
+    };
+
+    let mut text = vec![("Software".to_string(), "spek-core".to_string())];
+
+    if let Some(file_name) = file_name {
+        text.push(("Source".to_string(), file_name.to_string()));
+    }
+
+    let fields = [
+        // Input and resolved values
+        ("sample_rate", result.sample_rate.to_string()),
+        ("input_channels", result.channels.to_string()),
+        ("duration", result.duration_seconds.to_string()),
+        ("db_shown", format!("{} .. {}", result.min_db, result.max_db)),
+        // Analysis
+        ("fft_size", s.fft_size.to_string()),
+        ("hop_size", s.hop_size.to_string()),
+        ("window", format!("{:?}", s.window)),
+        ("channels", format!("{:?}", s.channels)),
+        ("min_db", s.min_db.to_string()),
+        ("max_db", s.max_db.to_string()),
+        ("range", format!("{:?}", s.range)),
+        ("scale", format!("{:?}", s.scale)),
+        ("normalization", format!("{:?}", s.normalization)),
+        ("threads", s.threads.to_string()),
+        ("freq_min", auto(s.freq_min.map(|v| v.to_string()))),
+        ("freq_max", auto(s.freq_max.map(|v| v.to_string()))),
+        ("time_start", auto(s.time_start.map(|v| v.to_string()))),
+        ("time_end", auto(s.time_end.map(|v| v.to_string()))),
+        // Rendering
+        ("width", r.width.to_string()),
+        ("height", r.height.to_string()),
+        ("freq_scale", format!("{:?}", r.freq_scale)),
+        ("cutoff_marker", r.cutoff_marker.to_string()),
+        ("palette", palette),
+        // Legend
+        ("font", font),
+        ("font_size", l.font_size.to_string()),
+        ("font_weight", format!("{:?}", l.font_weight)),
+    ];
+
+    text.extend(
+        fields
+            .into_iter()
+            .map(|(key, value)| (format!("spek:{}", key), value)),
+    );
+
+    text
+}
+
+// -------------------------------------------------------------------------
+// Chunks
+// -------------------------------------------------------------------------
+
+/// Append a chunk: length, type, data, CRC over type + data.
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// tEXt (Latin-1) or iTXt (uncompressed UTF-8) chunk.
+fn text_chunk(out: &mut Vec<u8>, keyword: &str, value: &str) {
+    let mut data = keyword.as_bytes().to_vec();
+    data.push(0);
+
+    if value.chars().all(|c| c != '\0' && (c as u32) <= 0xff) {
+        data.extend(value.chars().map(|c| c as u8));
+        chunk(out, b"tEXt", &data);
+    } else {
+        // Compression flag, method, empty language tag, empty translated keyword
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        data.extend_from_slice(value.as_bytes());
+        chunk(out, b"iTXt", &data);
+    }
+}
+
+// -------------------------------------------------------------------------
+// Filtering
+// -------------------------------------------------------------------------
+
+/// Filter every row with the type giving the smallest sum of
+/// absolute (signed) bytes, the usual libpng heuristic.
+/// Ties go to the lower filter type.
+fn filter(image: &ImageBuffer, channels: usize) -> Vec<u8> {
+    let stride = image.width * channels;
+    let mut out = Vec::with_capacity((stride + 1) * image.height);
+
+    let mut prev = vec![0u8; stride];
+    let mut row = vec![0u8; stride];
+    let mut candidates = vec![vec![0u8; stride]; 5];
+
+    for y in 0..image.height {
+        let src = &image.data[y * image.width * 4..(y + 1) * image.width * 4];
+        for (dst, px) in row.chunks_exact_mut(channels).zip(src.chunks_exact(4)) {
+            dst.copy_from_slice(&px[..channels]);
+        }
+
+        for (kind, candidate) in candidates.iter_mut().enumerate() {
+            for i in 0..stride {
+                let a = if i >= channels { row[i - channels] } else { 0 };
+                let b = prev[i];
+                let c = if i >= channels { prev[i - channels] } else { 0 };
+
+                let predictor = match kind {
+                    0 => 0,
+                    1 => a,
+                    2 => b,
+                    3 => ((a as u16 + b as u16) / 2) as u8,
+                    _ => paeth(a, b, c),
+                };
+                candidate[i] = row[i].wrapping_sub(predictor);
+            }
+        }
+
+        let cost = |c: &Vec<u8>| c.iter().map(|&v| (v as i8).unsigned_abs() as u64).sum::<u64>();
+        let best = (0..5).min_by_key(|&k| (cost(&candidates[k]), k)).unwrap_or(0);
+
+        out.push(best as u8);
+        out.extend_from_slice(&candidates[best]);
+
+        std::mem::swap(&mut prev, &mut row);
+    }
+
+    out
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+// -------------------------------------------------------------------------
+// zlib / deflate (RFC 1950 / 1951)
+//
+// Lazy LZ77 matching over a 32 KiB window with hash chains,
+// dynamic Huffman blocks. All parameters are fixed.
+// -------------------------------------------------------------------------
+
+const WINDOW: usize = 1 << 15;
+const HASH_BITS: usize = 15;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 128;
+
+/// Tokens per deflate block.
+const BLOCK_TOKENS: usize = 1 << 16;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115,
+    131, 163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12,
+    13, 13,
+];
+
+/// Transmission order of the code length code lengths.
+const CL_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+#[derive(Debug, Copy, Clone)]
+enum Token {
+    Literal(u8),
+    Match { length: u16, distance: u16 },
+}
+
+/// zlib stream: header (deflate, 32 KiB window), data, Adler-32.
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x9c];
+    out.extend(deflate(data));
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let tokens = lz77(data);
+    let mut writer = BitWriter::default();
+
+    let blocks: Vec<&[Token]> = match tokens.is_empty() {
+        true => vec![&[]],
+        false => tokens.chunks(BLOCK_TOKENS).collect(),
+    };
+
+    let last = blocks.len() - 1;
+    for (i, block) in blocks.into_iter().enumerate() {
+        write_block(&mut writer, block, i == last);
+    }
+
+    writer.finish()
+}
+
+/// Greedy LZ77 with one step of lazy evaluation.
+fn lz77(data: &[u8]) -> Vec<Token> {
+    let mut matcher = Matcher {
+        data,
+        head: vec![usize::MAX; 1 << HASH_BITS],
+        prev: vec![usize::MAX; WINDOW],
+    };
+
+    let mut tokens = Vec::with_capacity(data.len() / 2);
+    let mut i = 0;
+
+    while i < data.len() {
+        let (length, distance) = matcher.longest(i);
+        matcher.insert(i);
+
+        if length < MIN_MATCH {
+            tokens.push(Token::Literal(data[i]));
+            i += 1;
+            continue;
+        }
+
+        // A longer match one byte later wins
+        if matcher.longest(i + 1).0 > length {
+            tokens.push(Token::Literal(data[i]));
+            i += 1;
+            continue;
+        }
+
+        tokens.push(Token::Match {
+            length: length as u16,
+            distance: distance as u16,
+        });
+        for j in i + 1..i + length {
+            matcher.insert(j);
+        }
+        i += length;
+    }
+
+    tokens
+}
+
+/// Hash chains over 3-byte prefixes.
+struct Matcher<'a> {
+    data: &'a [u8],
+    head: Vec<usize>,
+    prev: Vec<usize>,
+}
+
+impl Matcher<'_> {
+    fn hash(&self, i: usize) -> usize {
+        let d = self.data;
+        ((d[i] as usize) << 10 ^ (d[i + 1] as usize) << 5 ^ d[i + 2] as usize) & ((1 << HASH_BITS) - 1)
+    }
+
+    fn insert(&mut self, i: usize) {
+        if i + MIN_MATCH <= self.data.len() {
+            let h = self.hash(i);
+            self.prev[i % WINDOW] = self.head[h];
+            self.head[h] = i;
+        }
+    }
+
+    /// Longest earlier match at `i` as (length, distance); length 0 if none.
+    fn longest(&self, i: usize) -> (usize, usize) {
+        let d = self.data;
+        if i + MIN_MATCH > d.len() {
+            return (0, 0);
+        }
+
+        let max = MAX_MATCH.min(d.len() - i);
+        let (mut best, mut best_distance) = (0, 0);
+        let mut candidate = self.head[self.hash(i)];
+
+        for _ in 0..MAX_CHAIN {
+            if candidate == usize::MAX || i - candidate > WINDOW {
+                break;
+            }
+
+            if d[candidate + best.min(max - 1)] == d[i + best.min(max - 1)] {
+                let length = d[candidate..candidate + max]
+                    .iter()
+                    .zip(&d[i..i + max])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+
+                if length > best {
+                    best = length;
+                    best_distance = i - candidate;
+                    if best == max {
+                        break;
+                    }
+                }
+            }
+
+            let next = self.prev[candidate % WINDOW];
+            if next == usize::MAX || next >= candidate {
+                break;
+            }
+            candidate = next;
+        }
+
+        (best, best_distance)
+    }
+}
+
+/// Write one dynamic Huffman block.
+fn write_block(w: &mut BitWriter, tokens: &[Token], last: bool) {
+    // Symbol frequencies
+    let mut lit_freq = [0u32; 286];
+    let mut dist_freq = [0u32; 30];
+    lit_freq[256] = 1;
+
+    for token in tokens {
+        match *token {
+            Token::Literal(b) => lit_freq[b as usize] += 1,
+            Token::Match { length, distance } => {
+                lit_freq[257 + length_code(length)] += 1;
+                dist_freq[dist_code(distance)] += 1;
+            }
+        }
+    }
+
+    let lit_len = code_lengths(&lit_freq, 15);
+    let dist_len = code_lengths(&dist_freq, 15);
+    let lit_codes = canonical_codes(&lit_len);
+    let dist_codes = canonical_codes(&dist_len);
+
+    let hlit = (257..=286).rev().find(|&n| lit_len[n - 1] > 0).unwrap_or(257);
+    let hdist = (1..=30).rev().find(|&n| dist_len[n - 1] > 0).unwrap_or(1);
+
+    // Code lengths, run-length encoded with symbols 16 / 17 / 18
+    let lengths: Vec<u8> = lit_len[..hlit].iter().chain(&dist_len[..hdist]).copied().collect();
+    let runs = run_lengths(&lengths);
+
+    let mut cl_freq = [0u32; 19];
+    for &(symbol, _) in &runs {
+        cl_freq[symbol as usize] += 1;
+    }
+    let cl_len = code_lengths(&cl_freq, 7);
+    let cl_codes = canonical_codes(&cl_len);
+    let hclen = (4..=19).rev().find(|&n| cl_len[CL_ORDER[n - 1]] > 0).unwrap_or(4);
+
+    // Header
+    w.write(last as u32, 1);
+    w.write(2, 2);
+    w.write((hlit - 257) as u32, 5);
+    w.write((hdist - 1) as u32, 5);
+    w.write((hclen - 4) as u32, 4);
+
+    for &symbol in &CL_ORDER[..hclen] {
+        w.write(cl_len[symbol] as u32, 3);
+    }
+
+    for &(symbol, extra) in &runs {
+        let s = symbol as usize;
+        w.code(cl_codes[s], cl_len[s]);
+        match symbol {
+            16 => w.write(extra as u32, 2),
+            17 => w.write(extra as u32, 3),
+            18 => w.write(extra as u32, 7),
+            _ => {}
+        }
+    }
+
+    // Data
+    for token in tokens {
+        match *token {
+            Token::Literal(b) => w.code(lit_codes[b as usize], lit_len[b as usize]),
+            Token::Match { length, distance } => {
+                let lc = length_code(length);
+                w.code(lit_codes[257 + lc], lit_len[257 + lc]);
+                w.write((length - LENGTH_BASE[lc]) as u32, LENGTH_EXTRA[lc]);
+
+                let dc = dist_code(distance);
+                w.code(dist_codes[dc], dist_len[dc]);
+                w.write((distance - DIST_BASE[dc]) as u32, DIST_EXTRA[dc]);
+            }
+        }
+    }
+
+    w.code(lit_codes[256], lit_len[256]);
+}
+
+fn length_code(length: u16) -> usize {
+    LENGTH_BASE.partition_point(|&base| base <= length) - 1
+}
+
+fn dist_code(distance: u16) -> usize {
+    DIST_BASE.partition_point(|&base| base <= distance) - 1
+}
+
+/// Code length sequence as (symbol, extra bits value).
+fn run_lengths(lengths: &[u8]) -> Vec<(u8, u8)> {
+    let mut runs = Vec::new();
+    let mut i = 0;
+
+    while i < lengths.len() {
+        let value = lengths[i];
+        let mut run = lengths[i..].iter().take_while(|&&v| v == value).count();
+        i += run;
+
+        if value == 0 {
+            while run >= 11 {
+                let n = run.min(138);
+                runs.push((18, (n - 11) as u8));
+                run -= n;
+            }
+            if run >= 3 {
+                runs.push((17, (run - 3) as u8));
+                run = 0;
+            }
+        } else {
+            runs.push((value, 0));
+            run -= 1;
+            while run >= 3 {
+                let n = run.min(6);
+                runs.push((16, (n - 3) as u8));
+                run -= n;
+            }
+        }
+
+        runs.extend(std::iter::repeat_n((value, 0), run));
+    }
+
+    runs
+}
+
+/// Huffman code lengths limited to `limit` bits.
+///
+/// At least two symbols always get a code (a lone code is rejected by
+/// some decoders). Over-long trees are rebuilt from halved frequencies.
+fn code_lengths(freq: &[u32], limit: u8) -> Vec<u8> {
+    let mut freq = freq.to_vec();
+
+    for symbol in 0..2 {
+        if freq.iter().filter(|&&f| f > 0).count() < 2 && freq[symbol] == 0 {
+            freq[symbol] = 1;
+        }
+    }
+
+    loop {
+        let lengths = huffman_lengths(&freq);
+        if lengths.iter().all(|&l| l <= limit) {
+            return lengths;
+        }
+
+        for f in freq.iter_mut().filter(|f| **f > 0) {
+            *f = f.div_ceil(2);
+        }
+    }
+}
+
+/// Unlimited Huffman code lengths; ties broken by node order.
+fn huffman_lengths(freq: &[u32]) -> Vec<u8> {
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
+
+    let leaves: Vec<usize> = (0..freq.len()).filter(|&s| freq[s] > 0).collect();
+    let mut parent = vec![usize::MAX; 2 * leaves.len()];
+    let mut heap: BinaryHeap<Reverse<(u64, usize)>> = leaves
+        .iter()
+        .enumerate()
+        .map(|(node, &s)| Reverse((freq[s] as u64, node)))
+        .collect();
+
+    let mut next = leaves.len();
+    while heap.len() > 1 {
+        let Reverse((wa, a)) = heap.pop().unwrap_or(Reverse((0, 0)));
+        let Reverse((wb, b)) = heap.pop().unwrap_or(Reverse((0, 0)));
+        parent[a] = next;
+        parent[b] = next;
+        heap.push(Reverse((wa + wb, next)));
+        next += 1;
+    }
+
+    // Parents are created after their children: resolve depths top-down
+    let mut depth = vec![0u8; next];
+    for node in (0..next).rev() {
+        if parent[node] != usize::MAX {
+            depth[node] = depth[parent[node]] + 1;
+        }
+    }
+
+    let mut lengths = vec![0u8; freq.len()];
+    for (node, &s) in leaves.iter().enumerate() {
+        lengths[s] = depth[node];
+    }
+    lengths
+}
+
+/// Canonical Huffman codes for the given lengths (RFC 1951, 3.2.2).
+fn canonical_codes(lengths: &[u8]) -> Vec<u16> {
+    let mut count = [0u16; 16];
+    for &l in lengths.iter().filter(|&&l| l > 0) {
+        count[l as usize] += 1;
+    }
+
+    let mut next = [0u16; 16];
+    let mut code = 0u16;
+    for bits in 1..16 {
+        code = (code + count[bits - 1]) << 1;
+        next[bits] = code;
+    }
+
+    lengths
+        .iter()
+        .map(|&l| {
+            if l == 0 {
+                return 0;
+            }
+            let c = next[l as usize];
+            next[l as usize] += 1;
+            c
+        })
+        .collect()
+}
+
+/// LSB-first bit writer.
+#[derive(Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    buffer: u64,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u32, bits: u8) {
+        self.buffer |= (value as u64) << self.bits;
+        self.bits += bits;
+
+        while self.bits >= 8 {
+            self.out.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    /// Huffman codes are sent most significant bit first.
+    fn code(&mut self, code: u16, bits: u8) {
+        let reversed = code.reverse_bits() >> (16 - bits as u32);
+        self.write(reversed as u32, bits);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.out.push(self.buffer as u8);
+        }
+        self.out
+    }
+}
+
+// -------------------------------------------------------------------------
+// Checksums
+// -------------------------------------------------------------------------
+
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 { 0xedb8_8320 ^ (c >> 1) } else { c >> 1 };
+            k += 1;
+        }
+        table[n] = c;
+        n += 1;
+    }
+    table
+}
+
+pub(crate) fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |c, &b| {
+        CRC_TABLE[((c ^ b as u32) & 0xff) as usize] ^ (c >> 8)
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+
+    // 5552: the most bytes before `b` can overflow u32
+    for block in data.chunks(5552) {
+        for &byte in block {
+            a += byte as u32;
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// LSB-first bit reader over deflate data.
+    struct Bits<'a> {
+        data: &'a [u8],
+        pos: usize,
+    }
+
+    impl Bits<'_> {
+        fn read(&mut self, n: u8) -> u32 {
+            (0..n).fold(0, |v, i| {
+                let bit = (self.data[self.pos / 8] >> (self.pos % 8)) & 1;
+                self.pos += 1;
+                v | (bit as u32) << i
+            })
+        }
+    }
+
+    /// Canonical Huffman decoder (bit by bit, as in zlib's `puff`).
+    struct Decoder {
+        counts: [u16; 16],
+        symbols: Vec<u16>,
+    }
+
+    impl Decoder {
+        fn new(lengths: &[u8]) -> Self {
+            let mut counts = [0u16; 16];
+            lengths.iter().for_each(|&l| counts[l as usize] += 1);
+            counts[0] = 0;
+
+            let mut symbols: Vec<u16> = (0..lengths.len() as u16)
+                .filter(|&s| lengths[s as usize] > 0)
+                .collect();
+            symbols.sort_by_key(|&s| lengths[s as usize]);
+
+            Self { counts, symbols }
+        }
+
+        fn decode(&self, bits: &mut Bits) -> usize {
+            let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+
+            for &count in &self.counts[1..] {
+                code |= bits.read(1) as i32;
+                if code - (count as i32) < first {
+                    return self.symbols[(index + code - first) as usize] as usize;
+                }
+                index += count as i32;
+                first = (first + count as i32) << 1;
+                code <<= 1;
+            }
+
+            panic!("invalid Huffman code");
+        }
+    }
+
+    /// Independent RFC 1950 / 1951 decoder, checks the encoder's output.
+    fn inflate(zlib: &[u8]) -> Vec<u8> {
+        assert_eq!(zlib[0] & 0x0f, 8);
+        assert_eq!(u16::from_be_bytes([zlib[0], zlib[1]]) % 31, 0);
+
+        let mut bits = Bits {
+            data: &zlib[2..],
+            pos: 0,
+        };
+        let mut out = Vec::new();
+
+        loop {
+            let last = bits.read(1) == 1;
+
+            let (literals, distances) = match bits.read(2) {
+                0 => {
+                    bits.pos = bits.pos.div_ceil(8) * 8;
+                    let len = bits.read(16);
+                    assert_eq!(bits.read(16), !len & 0xffff);
+                    out.extend((0..len).map(|_| bits.read(8) as u8));
+
+                    if last {
+                        break;
+                    }
+                    continue;
+                }
+                1 => {
+                    let mut lengths = [8u8; 288];
+                    lengths[144..256].fill(9);
+                    lengths[256..280].fill(7);
+                    (Decoder::new(&lengths), Decoder::new(&[5; 30]))
+                }
+                2 => {
+                    let hlit = bits.read(5) as usize + 257;
+                    let hdist = bits.read(5) as usize + 1;
+                    let hclen = bits.read(4) as usize + 4;
+
+                    let mut cl = [0u8; 19];
+                    for &i in &CL_ORDER[..hclen] {
+                        cl[i] = bits.read(3) as u8;
+                    }
+                    let cl = Decoder::new(&cl);
+
+                    let mut lengths = Vec::new();
+                    while lengths.len() < hlit + hdist {
+                        match cl.decode(&mut bits) {
+                            sym @ 0..=15 => lengths.push(sym as u8),
+                            16 => {
+                                let prev = *lengths.last().unwrap();
+                                let n = 3 + bits.read(2);
+                                lengths.extend((0..n).map(|_| prev));
+                            }
+                            17 => lengths.extend((0..3 + bits.read(3)).map(|_| 0)),
+                            _ => lengths.extend((0..11 + bits.read(7)).map(|_| 0)),
+                        }
+                    }
+                    assert_eq!(lengths.len(), hlit + hdist);
+
+                    (Decoder::new(&lengths[..hlit]), Decoder::new(&lengths[hlit..]))
+                }
+                _ => panic!("reserved block type"),
+            };
+
+            loop {
+                match literals.decode(&mut bits) {
+                    sym @ 0..=255 => out.push(sym as u8),
+                    256 => break,
+                    sym => {
+                        let i = sym - 257;
+                        let length = LENGTH_BASE[i] as usize + bits.read(LENGTH_EXTRA[i]) as usize;
+                        let d = distances.decode(&mut bits);
+                        let distance = DIST_BASE[d] as usize + bits.read(DIST_EXTRA[d]) as usize;
+
+                        assert!(distance <= out.len() && distance <= WINDOW);
+                        for _ in 0..length {
+                            out.push(out[out.len() - distance]);
+                        }
+                    }
+                }
+            }
+
+            if last {
+                break;
+            }
+        }
+
+        let end = 2 + bits.pos.div_ceil(8);
+        assert_eq!(zlib[end..], adler32(&out).to_be_bytes());
+        out
+    }
+
+    /// Split a PNG into (type, data) chunks, checking every CRC.
+    fn chunks(png: &[u8]) -> Vec<([u8; 4], &[u8])> {
+        assert_eq!(png[..8], SIGNATURE);
+
+        let mut chunks = Vec::new();
+        let mut at = 8;
+        while at < png.len() {
+            let len = u32::from_be_bytes(png[at..at + 4].try_into().unwrap()) as usize;
+            let body = &png[at + 4..at + 8 + len];
+            let crc = u32::from_be_bytes(png[at + 8 + len..at + 12 + len].try_into().unwrap());
+
+            assert_eq!(crc32(body), crc);
+            chunks.push((body[..4].try_into().unwrap(), &body[4..]));
+            at += 12 + len;
+        }
+
+        chunks
+    }
+
+    #[test]
+    fn checksums_match_reference_values() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn encoding_is_deterministic() {
+        let mut image = ImageBuffer::new(64, 32);
+        for (i, px) in image.data.chunks_exact_mut(4).enumerate() {
+            px[0] = (i % 7 * 30) as u8;
+            px[1] = (i / 64 * 8) as u8;
+        }
+        let text = vec![("Source".to_string(), "Töne – 音.flac".to_string())];
+
+        let png = encode(&image, &text);
+        assert_eq!(png[..8], SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png, encode(&image, &text));
+    }
+
+    #[test]
+    fn deflate_round_trips() {
+        let mut seed = 7u32;
+        let mut random = |n: usize, alphabet: u32| -> Vec<u8> {
+            (0..n)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                    ((seed >> 16) % alphabet) as u8
+                })
+                .collect()
+        };
+
+        let inputs = [
+            Vec::new(),
+            b"a".to_vec(),
+            b"abcabcabcabcabcabcabcabcabcabcabcab".repeat(40),
+            vec![0u8; 300_000],
+            random(5000, 4),
+            // Mostly literals: several blocks of BLOCK_TOKENS
+            random(200_000, 256),
+            // Repeats further back than the window
+            random(40_000, 256).repeat(2),
+        ];
+
+        for input in inputs {
+            let compressed = zlib(&input);
+            assert_eq!(inflate(&compressed), input, "{} bytes", input.len());
+        }
+    }
+
+    #[test]
+    fn decoded_pixels_match_the_image() {
+        for (width, height, alpha) in [(1, 1, 255), (37, 23, 255), (64, 40, 128)] {
+            let mut image = ImageBuffer::new(width, height);
+            for (i, px) in image.data.chunks_exact_mut(4).enumerate() {
+                let (x, y) = (i % width, i / width);
+                px.copy_from_slice(&[(x * 7) as u8, (y * 11) as u8, (x * y) as u8, alpha]);
+            }
+
+            let png = encode(&image, &[]);
+            let chunks = chunks(&png);
+            let idat: Vec<u8> = chunks
+                .iter()
+                .filter(|(kind, _)| kind == b"IDAT")
+                .flat_map(|(_, data)| data.iter().copied())
+                .collect();
+
+            let channels = if alpha == 255 { 3 } else { 4 };
+            let stride = width * channels;
+            let filtered = inflate(&idat);
+            assert_eq!(filtered.len(), (stride + 1) * height);
+
+            // Undo the row filters
+            let mut prev = vec![0u8; stride];
+            for (y, line) in filtered.chunks_exact(stride + 1).enumerate() {
+                let mut row = line[1..].to_vec();
+                for i in 0..stride {
+                    let a = if i >= channels { row[i - channels] } else { 0 };
+                    let c = if i >= channels { prev[i - channels] } else { 0 };
+                    row[i] = row[i].wrapping_add(match line[0] {
+                        0 => 0,
+                        1 => a,
+                        2 => prev[i],
+                        3 => ((a as u16 + prev[i] as u16) / 2) as u8,
+                        4 => paeth(a, prev[i], c),
+                        f => panic!("filter type {}", f),
+                    });
+                }
+
+                for (x, px) in row.chunks_exact(channels).enumerate() {
+                    let at = (y * width + x) * 4;
+                    assert_eq!(px, &image.data[at..at + channels]);
+                }
+                prev = row;
+            }
+        }
+    }
+
+    #[test]
+    fn tiny_image_matches_golden_bytes() {
+        let mut image = ImageBuffer::new(2, 2);
+        image.data[..4].copy_from_slice(&[255, 0, 0, 255]);
+        image.data[12..].copy_from_slice(&[0, 0, 255, 255]);
+        let text = vec![("Software".to_string(), "spek-core".to_string())];
+
+        #[rustfmt::skip]
+        let golden: &[u8] = &[
+            0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a,
+            // IHDR: 2x2, 8 bit RGB
+            0, 0, 0, 13, b'I', b'H', b'D', b'R',
+            0, 0, 0, 2, 0, 0, 0, 2, 8, 2, 0, 0, 0,
+            0xfd, 0xd4, 0x9a, 0x73,
+            // tEXt
+            0, 0, 0, 18, b't', b'E', b'X', b't',
+            b'S', b'o', b'f', b't', b'w', b'a', b'r', b'e', 0,
+            b's', b'p', b'e', b'k', b'-', b'c', b'o', b'r', b'e',
+            0x8a, 0x7e, 0xd0, 0xbb,
+            // IDAT: zlib header, one fixed Huffman block, Adler-32
+            0, 0, 0, 21, b'I', b'D', b'A', b'T',
+            0x78, 0x9c,
+            0x45, 0xc1, 0x31, 0x01, 0x00, 0x00, 0x00, 0x40,
+            0x30, 0xfd, 0x4b, 0x73, 0xda, 0x90, 0x19,
+            0x0e, 0x00, 0x01, 0xff,
+            0xd0, 0x6d, 0x08, 0xf9,
+            // IEND
+            0, 0, 0, 0, b'I', b'E', b'N', b'D',
+            0xae, 0x42, 0x60, 0x82,
+        ];
+        assert_eq!(encode(&image, &text), golden);
+    }
+}
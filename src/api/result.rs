@@ -14,7 +14,7 @@ pub struct SpectrogramResult {
     /// Final rendered RGBA image buffer (with legend)
     pub image: ImageBuffer,
 
-    /// Duration of the input audio in seconds
+    /// Duration of the analyzed audio (the excerpt, if cropped) in seconds
     pub duration_seconds: f64,
 
     /// Sample rate of the input audio in Hz
@@ -22,4 +22,94 @@ pub struct SpectrogramResult {
 
     /// Number of channels in the input audio
     pub channels: u32,
+
+    /// dBFS shown as the bottom of the color scale
+    pub min_db: f32,
+
+    /// dBFS shown as the top of the color scale
+    pub max_db: f32,
+
+    /// Lossy-transcode / upsampling assessment
+    pub report: QualityReport,
+
+    /// Numeric spectrogram (only with `SpectrogramSettings::keep_data`)
+    pub data: Option<SpectrogramData>,
+}
+
+/// Numeric spectrogram behind the image.
+///
+/// Matrices are `[freq_bin][time_bin]`, bin 0 = lowest frequency.
+/// One time bin per analysis frame (`hop_size` apart), independent of
+/// the plot width; the image averages frames per column instead.
+#[derive(Debug, Clone)]
+pub struct SpectrogramData {
+    /// Lower edge of each bin (`k * rate / fft_size`), in Hz
+    ///
+    /// The plot draws bin `k` from this frequency up to the next one.
+    pub frequencies: Vec<f64>,
+
+    /// Center of each frame's window, in seconds from the start of the file
+    ///
+    /// Frame `n` covers samples `n * hop_size .. n * hop_size + fft_size`
+    /// of the excerpt; `times[n]` is its midpoint plus `time_start`.
+    pub times: Vec<f64>,
+
+    /// dBFS mapped to intensity 0.0
+    pub min_db: f32,
+
+    /// dBFS mapped to intensity 1.0
+    pub max_db: f32,
+
+    /// One entry per panel, top to bottom
+    pub channels: Vec<ChannelData>,
+}
+
+/// Numeric spectrogram of one panel.
+#[derive(Debug, Clone)]
+pub struct ChannelData {
+    /// Panel label (e.g. "L", "Side"), `None` for a single mix
+    pub label: Option<String>,
+
+    /// Calibrated dBFS, before range mapping and scaling
+    pub db: Vec<Vec<f32>>,
+
+    /// Scaled intensities (0.0..1.0), as fed to the palette
+    pub intensity: Vec<Vec<f32>>,
+}
+
+/// Spectral quality assessment of the analyzed audio.
+#[derive(Debug, Clone)]
+pub struct QualityReport {
+    pub verdict: Verdict,
+
+    /// Confidence of the verdict (0.0..1.0)
+    pub confidence: f32,
+
+    /// Median brick-wall cutoff in Hz, `None` for full bandwidth
+    pub cutoff_hz: Option<f32>,
+
+    /// Highest frequency with content per spectrogram column (`None` = silent)
+    pub bandwidth_hz: Vec<Option<f32>>,
+
+    /// Brick-wall cutoff per spectrogram column (`None` = no wall)
+    pub cutoff_over_time_hz: Vec<Option<f32>>,
+}
+
+/// Verdict of the quality assessment.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    /// Too little signal to judge
+    Inconclusive,
+
+    /// No encoder or resampler lowpass found
+    FullBandwidth,
+
+    /// Lowpass at a typical lossy encoder shelf (16 / 19.5 / 20.5 kHz)
+    LossyTranscode { shelf_hz: f32, encoder: String },
+
+    /// Band-limited to the Nyquist of a lower sample rate
+    Upsampled { source_rate: u32 },
+
+    /// Lowpass at another frequency
+    Lowpassed { cutoff_hz: f32 },
 }
@@ -5,6 +5,9 @@
 //!
 //! No UI-specific or platform-specific settings are allowed here.
 
+use std::path::PathBuf;
+use std::sync::Arc;
+
 /// Intensity scaling mode applied after dBFS mapping.
 #[derive(Debug, Copy, Clone)]
 pub enum ScaleMode {
@@ -21,16 +24,70 @@ pub enum WindowFunction {
     Hann,
     Hamming,
     Blackman,
+    Nuttall,
+    BlackmanHarris,
+    FlatTop,
+
+    /// Kaiser window with shape parameter beta (e.g. 8.6)
+    Kaiser(f32),
+
+    /// Gaussian window with relative standard deviation sigma (e.g. 0.4)
+    Gaussian(f32),
+
+    /// Tukey window with taper ratio alpha (0.0..=1.0)
+    Tukey(f32),
 }
 
-/// Channel processing mode.
+/// dBFS calibration mode.
+#[derive(Debug, Copy, Clone)]
+pub enum Normalization {
+    /// Peak bin of a full-scale sine reads 0 dBFS
+    Amplitude,
+
+    /// Summed power of a full-scale sine reads 0 dBFS
+    Energy,
+}
+
+/// dBFS range selection.
 #[derive(Debug, Copy, Clone)]
+pub enum DbRange {
+    /// Use `min_db` / `max_db` as given
+    Fixed,
+
+    /// Top at the loudest bin, span `max_db - min_db` kept
+    Peak,
+
+    /// Both ends at percentiles (0..100) of all bin levels
+    Percentile { low: f32, high: f32 },
+}
+
+/// One row of a user downmix matrix (one spectrogram panel).
+#[derive(Debug, Clone)]
+pub struct DownmixRow {
+    /// Panel label shown by the legend
+    pub label: Option<String>,
+
+    /// Weight per input channel, in file order
+    pub weights: Vec<f32>,
+}
+
+/// Channel processing mode.
+#[derive(Debug, Clone)]
 pub enum ChannelMode {
-    /// All channels combined into a single spectrogram
+    /// All channels averaged into a single spectrogram
     Combined,
 
-    /// One spectrogram per channel
+    /// One labeled spectrogram panel per channel, stacked top to bottom
     Split,
+
+    /// "Mid" (L+R)/2 and "Side" (L-R)/2 panels of the front pair
+    MidSide,
+
+    /// A single channel (0-based), labeled e.g. "R" or "LFE"
+    SelectChannel(usize),
+
+    /// User downmix matrix; weights must match the channel count
+    Matrix(Vec<DownmixRow>),
 }
 
 /// Numerical spectrogram generation settings.
@@ -56,21 +113,150 @@ pub struct SpectrogramSettings {
     /// Maximum dBFS ceiling (typically 0.0)
     pub max_db: f32,
 
+    /// dBFS range selection; the chosen range is reported in the result
+    pub range: DbRange,
+
     /// Intensity scaling mode
     pub scale: ScaleMode,
+
+    /// dBFS calibration mode
+    pub normalization: Normalization,
+
+    /// Analysis worker threads (0 = all cores); output does not depend on it
+    pub threads: usize,
+
+    /// Lowest frequency shown, in Hz (`None` = 0 Hz)
+    pub freq_min: Option<f32>,
+
+    /// Highest frequency shown, in Hz (`None` = Nyquist)
+    pub freq_max: Option<f32>,
+
+    /// Start of the analyzed excerpt, in seconds (`None` = file start)
+    pub time_start: Option<f64>,
+
+    /// End of the analyzed excerpt, in seconds (`None` = file end)
+    pub time_end: Option<f64>,
+
+    /// Return the numeric spectrogram in `SpectrogramResult::data`
+    /// (e.g. for `api::npy`), one time bin per frame.
+    ///
+    /// The frames are kept next to the plot grid, so memory grows with
+    /// the audio length. Image and report are unchanged.
+    pub keep_data: bool,
+}
+
+/// Vertical frequency axis scale.
+#[derive(Debug, Copy, Clone)]
+pub enum FrequencyScale {
+    /// 0 Hz .. Nyquist, evenly spaced
+    Linear,
+
+    /// 20 Hz .. Nyquist, equal space per octave
+    Log,
+
+    /// Mel (perceptual pitch)
+    Mel,
+
+    /// Bark (critical bands)
+    Bark,
+
+    /// ERB-rate (equivalent rectangular bandwidth)
+    Erb,
 }
 
 /// Final output image configuration.
 ///
-/// This controls the pixel dimensions of the rendered image.
-/// The legend is always included automatically.
+/// This controls the pixel dimensions of the spectrogram area.
+/// The legend is always included automatically:
+/// the final image grows by the legend margins.
 #[derive(Debug, Clone)]
 pub struct RenderSettings {
-    /// Output image width in pixels
+    /// Spectrogram area width in pixels
     pub width: usize,
 
-    /// Output image height in pixels
+    /// Spectrogram area height in pixels
     pub height: usize,
+
+    /// Frequency axis scale (spectrogram and legend)
+    pub freq_scale: FrequencyScale,
+
+    /// Mark a detected lowpass cutoff on the frequency axis
+    pub cutoff_marker: bool,
+
+    /// Spectrogram palette; the legend gradient follows it
+    pub palette: PaletteSource,
+
+    /// Color saturation of spectrogram and legend gradient
+    /// (0.0 = grayscale, 1.0 = palette unchanged, > 1.0 = more vivid)
+    pub saturation: f32,
+}
+
+/// Spectrogram palette source.
+#[derive(Debug, Clone)]
+pub enum PaletteSource {
+    /// Built-in palette by name (see `color::Palette`, e.g. "spek", "viridis")
+    Builtin(String),
+
+    /// Stops file: text, JSON or GIMP `.ggr`
+    File {
+        path: PathBuf,
+        interpolation: PaletteInterpolation,
+        lut_size: PaletteLutSize,
+    },
+
+    /// Stops in memory as (position 0.0..=1.0, RGBA), ascending
+    Stops {
+        stops: Vec<(f64, [u8; 4])>,
+        interpolation: PaletteInterpolation,
+        lut_size: PaletteLutSize,
+    },
+}
+
+/// Blending between user palette stops.
+#[derive(Debug, Copy, Clone)]
+pub enum PaletteInterpolation {
+    Srgb,
+    Oklab,
+}
+
+/// User palette lookup table size.
+#[derive(Debug, Copy, Clone)]
+pub enum PaletteLutSize {
+    Entries256,
+    Entries4096,
+}
+
+/// Legend font source.
+#[derive(Debug, Clone)]
+pub enum FontFamily {
+    /// Built-in bitmap font (always available)
+    Builtin,
+
+    /// TrueType / OpenType file (cargo feature `truetype`)
+    File(PathBuf),
+
+    /// TrueType / OpenType data in memory (cargo feature `truetype`)
+    Data(Arc<[u8]>),
+}
+
+/// Legend font weight.
+#[derive(Debug, Copy, Clone)]
+pub enum FontWeight {
+    Regular,
+    Bold,
+}
+
+/// Legend text configuration.
+#[derive(Debug, Clone)]
+pub struct LegendSettings {
+    /// Line height of all labels, in pixels
+    pub font_size: u32,
+
+    /// Font used for all labels
+    pub font_family: FontFamily,
+
+    /// Font weight used for all labels
+    pub font_weight: FontWeight,
 }
 
 /// Complete spek-core configuration.
@@ -83,4 +269,7 @@ pub struct SpekSettings {
 
     /// Render output settings
     pub render: RenderSettings,
+
+    /// Legend text settings
+    pub legend: LegendSettings,
 }
@@ -9,10 +9,14 @@
 //!
 //! Decodes audio into interleaved f32 PCM samples in range [-1.0, 1.0].
 
+use std::io::{ErrorKind, Read};
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Child, ChildStdout, Command, Stdio};
 
-use crate::audio::{AudioBuffer, AudioError, AudioMetadata, AudioSource};
+use crate::audio::{
+    cropped_len, read_to_end, AudioBuffer, AudioError, AudioMetadata, AudioSource, PcmStream,
+    StreamingAudioSource, TimeRange, STREAM_BLOCK_FRAMES,
+};
 
 /// FFmpeg-backed audio source.
 #[derive(Debug, Clone)]
@@ -29,7 +33,22 @@ impl FfmpegAudioSource {
 }
 
 impl AudioSource for FfmpegAudioSource {
-    fn load(&self) -> Result<AudioBuffer<'_>, AudioError> {
+    fn load(&self) -> Result<AudioBuffer, AudioError> {
+        read_to_end(self.open()?.as_mut())
+    }
+
+    fn load_range(&self, range: TimeRange) -> Result<AudioBuffer, AudioError> {
+        read_to_end(self.open_range(range)?.as_mut())
+    }
+}
+
+impl StreamingAudioSource for FfmpegAudioSource {
+    fn open(&self) -> Result<Box<dyn PcmStream + '_>, AudioError> {
+        self.open_range(TimeRange::FULL)
+    }
+
+    /// Only the range is decoded (`-ss` / `-t`).
+    fn open_range(&self, range: TimeRange) -> Result<Box<dyn PcmStream + '_>, AudioError> {
         // -------------------------------------------------------------
         // 1. Probe metadata using ffprobe
         // -------------------------------------------------------------
@@ -40,7 +59,7 @@ impl AudioSource for FfmpegAudioSource {
                 "-select_streams",
                 "a:0",
                 "-show_entries",
-                "stream=sample_rate,channels",
+                "stream=sample_rate,channels:format=duration",
                 "-of",
                 "default=nokey=1:noprint_wrappers=1",
             ])
@@ -63,22 +82,38 @@ impl AudioSource for FfmpegAudioSource {
         let channels: u16 = lines
             .next()
             .and_then(|v| v.parse().ok())
+            .filter(|&c| c > 0)
             .ok_or(AudioError::DecodeFailed)?;
 
+        // Container duration, may be "N/A" for raw streams
+        let duration: f64 = lines
+            .next()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0.0);
+
+        // Snap the range to whole frames
+        let (start, len) = range.frames(sample_rate)?;
+        let rate = sample_rate as f64;
+
         // -------------------------------------------------------------
-        // 2. Decode audio to f32 PCM via ffmpeg (MINIMAL + SAFE)
+        // 2. Start decoding audio to f32 PCM via ffmpeg (MINIMAL + SAFE)
         // -------------------------------------------------------------
-        let decode = Command::new("ffmpeg")
-            .args([
-                "-nostdin",
-                "-v",
-                "error",
-                "-vn",
-                "-sn",
-                "-dn",
-                "-i",
-            ])
-            .arg(&self.path)
+        let mut command = Command::new("ffmpeg");
+        command.args(["-nostdin", "-v", "error"]);
+
+        // Input seeking: ffmpeg decodes from the nearest seek point
+        // and drops samples up to `start`
+        if start > 0 {
+            command.args(["-ss", &(start as f64 / rate).to_string()]);
+        }
+
+        command.args(["-vn", "-sn", "-dn", "-i"]).arg(&self.path);
+
+        if let Some(len) = len {
+            command.args(["-t", &(len as f64 / rate).to_string()]);
+        }
+
+        let mut child = command
             .args([
                 "-f",
                 "f32le",
@@ -90,24 +125,92 @@ impl AudioSource for FfmpegAudioSource {
                 &sample_rate.to_string(),
                 "-",
             ])
-            .output()
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
             .map_err(|_| AudioError::IoError)?;
 
-        if !decode.status.success() {
-            return Err(AudioError::DecodeFailed);
-        }
+        let stdout = child.stdout.take().ok_or(AudioError::IoError)?;
 
         // -------------------------------------------------------------
-        // 3. Convert raw bytes → f32 samples (CLAMPED)
+        // 3. Assemble metadata & stream
         // -------------------------------------------------------------
-        let bytes = decode.stdout;
-        if bytes.len() % 4 != 0 {
-            return Err(AudioError::DecodeFailed);
+        let meta = AudioMetadata {
+            sample_rate,
+            channels,
+            total_samples: cropped_len((duration * rate).round() as u64, start, len),
+            bit_depth: None,
+            codec: None,
+            tags: Vec::new(),
+        };
+
+        Ok(Box::new(FfmpegStream {
+            raw: vec![0u8; STREAM_BLOCK_FRAMES * channels as usize * 4],
+            filled: 0,
+            child,
+            stdout,
+            meta,
+            finished: false,
+        }))
+    }
+}
+
+/// Incremental reader over ffmpeg's stdout.
+///
+/// The ffmpeg process is killed if the stream is dropped early.
+struct FfmpegStream {
+    child: Child,
+    stdout: ChildStdout,
+    meta: AudioMetadata,
+
+    /// Raw f32le bytes, plus a partial frame carried over
+    raw: Vec<u8>,
+    filled: usize,
+    finished: bool,
+}
+
+impl PcmStream for FfmpegStream {
+    fn meta(&self) -> &AudioMetadata {
+        &self.meta
+    }
+
+    fn read_block(&mut self, block: &mut Vec<f32>) -> Result<bool, AudioError> {
+        block.clear();
+
+        if self.finished {
+            return Ok(false);
+        }
+
+        let frame_bytes = self.meta.channels as usize * 4;
+
+        while self.filled < self.raw.len() {
+            match self.stdout.read(&mut self.raw[self.filled..]) {
+                Ok(0) => break,
+                Ok(n) => self.filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return Err(AudioError::IoError),
+            }
         }
 
-        let mut samples: Vec<f32> = Vec::with_capacity(bytes.len() / 4);
+        let complete = self.filled - self.filled % frame_bytes;
+
+        if complete == 0 {
+            // End of output: ffmpeg must have exited cleanly
+            self.finished = true;
+
+            let status = self.child.wait().map_err(|_| AudioError::IoError)?;
+            if !status.success() {
+                return Err(AudioError::DecodeFailed);
+            }
 
-        for chunk in bytes.chunks_exact(4) {
+            return Ok(false);
+        }
+
+        // -------------------------------------------------------------
+        // Convert raw bytes → f32 samples (CLAMPED)
+        // -------------------------------------------------------------
+        for chunk in self.raw[..complete].chunks_exact(4) {
             let mut v = f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
 
             // Critical safety:
@@ -117,25 +220,21 @@ impl AudioSource for FfmpegAudioSource {
                 v = v.clamp(-1.0, 1.0);
             }
 
-            samples.push(v);
+            block.push(v);
         }
 
-        let total_samples = (samples.len() / channels as usize) as u64;
+        self.raw.copy_within(complete..self.filled, 0);
+        self.filled -= complete;
 
-        // -------------------------------------------------------------
-        // 4. Assemble metadata & buffer
-        // -------------------------------------------------------------
-        let meta = AudioMetadata {
-            sample_rate,
-            channels,
-            total_samples,
-            bit_depth: None,
-        };
+        Ok(true)
+    }
+}
 
-        Ok(AudioBuffer {
-            // Intentional leak: AudioBuffer is immutable & lives for program lifetime
-            samples: Box::leak(samples.into_boxed_slice()),
-            meta,
-        })
+impl Drop for FfmpegStream {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
     }
 }
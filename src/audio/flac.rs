@@ -0,0 +1,1454 @@
+//! Native FLAC decoder for spek-core.
+//!
+//! This module provides a pure-Rust AudioSource implementation
+//! that needs NO external tools and keeps the real stream properties.
+//!
+//! Supported:
+//! - All subframe types: CONSTANT, VERBATIM, FIXED, LPC
+//! - Wasted bits, all stereo decorrelation modes
+//! - Rice / Rice2 residuals including escape partitions
+//! - Frame header CRC-8 and frame CRC-16 verification
+//! - STREAMINFO MD5 signature verification
+//! - VORBIS_COMMENT tags
+//! - SEEKTABLE seeking for time-range decoding
+//!
+//! Decodes audio into interleaved f32 PCM samples in range [-1.0, 1.0].
+
+use std::fs::File;
+use std::io::{ErrorKind, Read, Seek, SeekFrom};
+use std::path::PathBuf;
+
+use crate::audio::{
+    cropped_len, read_to_end, AudioBuffer, AudioError, AudioMetadata, AudioSource, PcmStream,
+    StreamingAudioSource, TimeRange,
+};
+
+const BLOCK_STREAMINFO: u8 = 0;
+const BLOCK_SEEKTABLE: u8 = 3;
+const BLOCK_VORBIS_COMMENT: u8 = 4;
+
+/// Sample number of an unused seek point.
+const SEEK_PLACEHOLDER: u64 = u64::MAX;
+
+/// Native FLAC audio source.
+#[derive(Debug, Clone)]
+pub struct FlacAudioSource {
+    /// Path to the input audio file
+    pub path: PathBuf,
+}
+
+impl FlacAudioSource {
+    /// Create a new FLAC audio source from a file path.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl AudioSource for FlacAudioSource {
+    fn load(&self) -> Result<AudioBuffer, AudioError> {
+        read_to_end(self.open()?.as_mut())
+    }
+
+    fn load_range(&self, range: TimeRange) -> Result<AudioBuffer, AudioError> {
+        read_to_end(self.open_range(range)?.as_mut())
+    }
+}
+
+impl StreamingAudioSource for FlacAudioSource {
+    fn open(&self) -> Result<Box<dyn PcmStream + '_>, AudioError> {
+        let file = File::open(&self.path).map_err(|_| AudioError::IoError)?;
+        Ok(Box::new(FlacStream::new(file)?))
+    }
+
+    /// Seeks via the SEEKTABLE, if present, then decodes up to the range.
+    fn open_range(&self, range: TimeRange) -> Result<Box<dyn PcmStream + '_>, AudioError> {
+        if range.is_full() {
+            return self.open();
+        }
+
+        let file = File::open(&self.path).map_err(|_| AudioError::IoError)?;
+
+        let mut stream = FlacStream::new(file)?;
+        stream.crop(range)?;
+
+        Ok(Box::new(stream))
+    }
+}
+
+/// Parsed STREAMINFO block.
+#[derive(Debug, Copy, Clone)]
+struct StreamInfo {
+    max_block_size: u16,
+    sample_rate: u32,
+    channels: u16,
+    bits_per_sample: u16,
+
+    /// Total samples per channel, 0 = unknown
+    total_samples: u64,
+
+    /// MD5 of the unencoded audio, all zero = not set
+    md5: [u8; 16],
+}
+
+/// One SEEKTABLE entry.
+#[derive(Debug, Copy, Clone)]
+struct SeekPoint {
+    /// First sample of the target frame
+    sample: u64,
+
+    /// Byte offset of the target frame from the first frame
+    offset: u64,
+}
+
+/// Incremental FLAC reader, one frame per block.
+///
+/// The MD5 signature is verified when the end of the stream is reached,
+/// unless the stream is cropped to a time range.
+struct FlacStream<R> {
+    bits: BitReader<R>,
+    info: StreamInfo,
+    meta: AudioMetadata,
+
+    /// Byte offset of the first frame in the file
+    first_frame: u64,
+    seek_table: Vec<SeekPoint>,
+
+    /// Requested sample range [start, end)
+    start: u64,
+    end: u64,
+    verify: bool,
+
+    md5: Md5,
+    channels: Vec<Vec<i64>>,
+    pcm_bytes: Vec<u8>,
+    decoded: u64,
+    finished: bool,
+}
+
+impl<R: Read> FlacStream<R> {
+    /// Parse the stream marker and all metadata blocks.
+    fn new(reader: R) -> Result<Self, AudioError> {
+        let mut bits = BitReader::new(reader);
+
+        if bits.read_bits(32)? != u32::from_be_bytes(*b"fLaC") {
+            return Err(AudioError::UnsupportedFormat);
+        }
+
+        let mut info: Option<StreamInfo> = None;
+        let mut tags = Vec::new();
+        let mut seek_table = Vec::new();
+        let mut first_frame = 4u64;
+
+        loop {
+            let last = bits.read_bits(1)? == 1;
+            let kind = bits.read_bits(7)? as u8;
+            let len = bits.read_bits(24)? as usize;
+
+            first_frame += 4 + len as u64;
+
+            match kind {
+                BLOCK_STREAMINFO => {
+                    info = Some(parse_stream_info(&bits.read_bytes(len)?)?);
+                }
+                BLOCK_SEEKTABLE => {
+                    seek_table = parse_seek_table(&bits.read_bytes(len)?);
+                }
+                BLOCK_VORBIS_COMMENT => {
+                    tags = parse_vorbis_comment(&bits.read_bytes(len)?);
+                }
+                _ => bits.skip_bytes(len)?,
+            }
+
+            if last {
+                break;
+            }
+        }
+
+        let info = info.ok_or(AudioError::DecodeFailed)?;
+
+        let meta = AudioMetadata {
+            sample_rate: info.sample_rate,
+            channels: info.channels,
+            total_samples: info.total_samples,
+            bit_depth: Some(info.bits_per_sample),
+            codec: Some("FLAC".to_string()),
+            tags,
+        };
+
+        Ok(Self {
+            bits,
+            info,
+            meta,
+            first_frame,
+            seek_table,
+            start: 0,
+            end: u64::MAX,
+            verify: true,
+            md5: Md5::new(),
+            channels: vec![Vec::new(); info.channels as usize],
+            pcm_bytes: Vec::new(),
+            decoded: 0,
+            finished: false,
+        })
+    }
+
+    /// Verify the MD5 signature once all frames are decoded.
+    fn finish(&mut self) -> Result<(), AudioError> {
+        self.finished = true;
+
+        if !self.verify {
+            return Ok(());
+        }
+
+        let md5 = std::mem::replace(&mut self.md5, Md5::new()).finish();
+        if self.info.md5 != [0u8; 16] && md5 != self.info.md5 {
+            return Err(AudioError::ChecksumMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read + Seek> FlacStream<R> {
+    /// Restrict the stream to `range` (before the first read).
+    ///
+    /// Jumps to the closest seek point before the range;
+    /// the remaining frames up to it are decoded and dropped.
+    fn crop(&mut self, range: TimeRange) -> Result<(), AudioError> {
+        let (start, len) = range.frames(self.info.sample_rate)?;
+
+        self.start = start;
+        self.end = len.map_or(u64::MAX, |len| start.saturating_add(len));
+        self.verify = false;
+        self.meta.total_samples = cropped_len(self.info.total_samples, start, len);
+
+        let point = self
+            .seek_table
+            .iter()
+            .take_while(|p| p.sample <= start)
+            .last();
+
+        if let Some(point) = point {
+            self.bits.seek(self.first_frame + point.offset)?;
+            self.decoded = point.sample;
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: Read> PcmStream for FlacStream<R> {
+    fn meta(&self) -> &AudioMetadata {
+        &self.meta
+    }
+
+    fn exact_length(&self) -> bool {
+        self.info.total_samples != 0
+    }
+
+    /// With a known total length, a stream cut short is an error.
+    fn read_block(&mut self, block: &mut Vec<f32>) -> Result<bool, AudioError> {
+        block.clear();
+
+        // Never past STREAMINFO's total, so a known length is exact
+        let end = match self.info.total_samples {
+            0 => self.end,
+            total => self.end.min(total),
+        };
+
+        loop {
+            if self.finished {
+                return Ok(false);
+            }
+
+            if self.decoded >= end {
+                self.finish()?;
+                return Ok(false);
+            }
+
+            if !self.bits.has_more()? {
+                if self.info.total_samples != 0 {
+                    return Err(AudioError::DecodeFailed);
+                }
+
+                self.finish()?;
+                return Ok(false);
+            }
+
+            let block_size = decode_frame(&mut self.bits, &self.info, &mut self.channels)?;
+
+            let first = self.decoded;
+            self.decoded += block_size as u64;
+
+            // Frame entirely before the range
+            if self.decoded <= self.start {
+                continue;
+            }
+
+            let from = self.start.saturating_sub(first) as usize;
+            let to = (end - first).min(block_size as u64) as usize;
+
+            // Interleave, convert, and feed the MD5 with signed LE samples
+            let scale = 1.0 / (1u64 << (self.info.bits_per_sample - 1)) as f64;
+            let md5_bytes = self.info.bits_per_sample.div_ceil(8) as usize;
+
+            self.pcm_bytes.clear();
+            for i in from..to {
+                for channel in self.channels.iter() {
+                    let v = channel[i];
+                    self.pcm_bytes.extend_from_slice(&v.to_le_bytes()[..md5_bytes]);
+                    block.push((v as f64 * scale).clamp(-1.0, 1.0) as f32);
+                }
+            }
+
+            if self.verify {
+                self.md5.update(&self.pcm_bytes);
+            }
+
+            return Ok(true);
+        }
+    }
+}
+
+/// Parse SEEKTABLE points, dropping placeholders.
+fn parse_seek_table(body: &[u8]) -> Vec<SeekPoint> {
+    body.chunks_exact(18)
+        .map(|p| SeekPoint {
+            sample: u64::from_be_bytes([p[0], p[1], p[2], p[3], p[4], p[5], p[6], p[7]]),
+            offset: u64::from_be_bytes([p[8], p[9], p[10], p[11], p[12], p[13], p[14], p[15]]),
+        })
+        .filter(|p| p.sample != SEEK_PLACEHOLDER)
+        .collect()
+}
+
+fn parse_stream_info(body: &[u8]) -> Result<StreamInfo, AudioError> {
+    if body.len() < 34 {
+        return Err(AudioError::DecodeFailed);
+    }
+
+    let packed = u64::from_be_bytes([
+        body[10], body[11], body[12], body[13], body[14], body[15], body[16], body[17],
+    ]);
+
+    let mut md5 = [0u8; 16];
+    md5.copy_from_slice(&body[18..34]);
+
+    let info = StreamInfo {
+        max_block_size: u16::from_be_bytes([body[2], body[3]]),
+        sample_rate: (packed >> 44) as u32,
+        channels: ((packed >> 41) & 0x7) as u16 + 1,
+        bits_per_sample: ((packed >> 36) & 0x1F) as u16 + 1,
+        total_samples: packed & 0xF_FFFF_FFFF,
+        md5,
+    };
+
+    if info.sample_rate == 0 || info.bits_per_sample < 4 {
+        return Err(AudioError::DecodeFailed);
+    }
+
+    Ok(info)
+}
+
+/// Parse VORBIS_COMMENT fields into (KEY, value) pairs.
+///
+/// Malformed entries are skipped, tags are informational only.
+fn parse_vorbis_comment(body: &[u8]) -> Vec<(String, String)> {
+    let mut tags = Vec::new();
+    let mut pos = 0;
+
+    let next = |pos: &mut usize| -> Option<&[u8]> {
+        let len = u32::from_le_bytes(body.get(*pos..*pos + 4)?.try_into().ok()?) as usize;
+        let data = body.get(*pos + 4..*pos + 4 + len)?;
+        *pos += 4 + len;
+        Some(data)
+    };
+
+    // Vendor string
+    if next(&mut pos).is_none() {
+        return tags;
+    }
+
+    let count = match body.get(pos..pos + 4) {
+        Some(b) => u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        None => return tags,
+    };
+    pos += 4;
+
+    for _ in 0..count {
+        let Some(entry) = next(&mut pos) else {
+            break;
+        };
+
+        let entry = String::from_utf8_lossy(entry);
+        if let Some((key, value)) = entry.split_once('=') {
+            tags.push((key.to_ascii_uppercase(), value.to_string()));
+        }
+    }
+
+    tags
+}
+
+/// Decode one frame into `block` (one Vec per channel).
+///
+/// Returns the frame's block size.
+fn decode_frame<R: Read>(
+    bits: &mut BitReader<R>,
+    info: &StreamInfo,
+    block: &mut [Vec<i64>],
+) -> Result<usize, AudioError> {
+    // -------------------------------------------------------------
+    // Frame header (CRC-8 protected)
+    // -------------------------------------------------------------
+    bits.reset_crc();
+
+    if bits.read_bits(15)? != 0x7FFC {
+        return Err(AudioError::DecodeFailed);
+    }
+    let _blocking_strategy = bits.read_bits(1)?;
+
+    let block_size_code = bits.read_bits(4)?;
+    let sample_rate_code = bits.read_bits(4)?;
+    let assignment = bits.read_bits(4)?;
+    let sample_size_code = bits.read_bits(3)?;
+
+    if bits.read_bits(1)? != 0 {
+        return Err(AudioError::DecodeFailed);
+    }
+
+    // UTF-8 style coded frame / sample number (value unused)
+    let lead = bits.read_bits(8)? as u8;
+    let extra = match lead.leading_ones() {
+        0 => 0,
+        n @ 2..=7 => n - 1,
+        _ => return Err(AudioError::DecodeFailed),
+    };
+    for _ in 0..extra {
+        if bits.read_bits(8)? & 0xC0 != 0x80 {
+            return Err(AudioError::DecodeFailed);
+        }
+    }
+
+    let block_size = match block_size_code {
+        0 => return Err(AudioError::DecodeFailed),
+        1 => 192,
+        n @ 2..=5 => 576 << (n - 2),
+        6 => bits.read_bits(8)? as usize + 1,
+        7 => bits.read_bits(16)? as usize + 1,
+        n => 256 << (n - 8),
+    };
+
+    match sample_rate_code {
+        12 => {
+            bits.read_bits(8)?;
+        }
+        13 | 14 => {
+            bits.read_bits(16)?;
+        }
+        15 => return Err(AudioError::DecodeFailed),
+        _ => {}
+    }
+
+    let bps = match sample_size_code {
+        0 => info.bits_per_sample as u32,
+        1 => 8,
+        2 => 12,
+        4 => 16,
+        5 => 20,
+        6 => 24,
+        7 => 32,
+        _ => return Err(AudioError::DecodeFailed),
+    };
+
+    let frame_channels = match assignment {
+        0..=7 => assignment as usize + 1,
+        8..=10 => 2,
+        _ => return Err(AudioError::DecodeFailed),
+    };
+
+    // Stream parameters must not change mid-stream
+    if frame_channels != block.len()
+        || bps != info.bits_per_sample as u32
+        || (info.max_block_size != 0 && block_size > info.max_block_size as usize)
+    {
+        return Err(AudioError::DecodeFailed);
+    }
+
+    let crc8 = bits.crc8();
+    if bits.read_bits(8)? as u8 != crc8 {
+        return Err(AudioError::ChecksumMismatch);
+    }
+
+    // -------------------------------------------------------------
+    // Subframes
+    // -------------------------------------------------------------
+    for (ch, samples) in block.iter_mut().enumerate() {
+        // The side channel carries one extra bit
+        let side = matches!((assignment, ch), (8, 1) | (9, 0) | (10, 1));
+        let channel_bps = bps + side as u32;
+
+        decode_subframe(bits, channel_bps, block_size, samples)?;
+    }
+
+    // -------------------------------------------------------------
+    // Stereo decorrelation
+    // -------------------------------------------------------------
+    if assignment >= 8 {
+        let (first, second) = block.split_at_mut(1);
+        let (a, b) = (&mut first[0], &mut second[0]);
+
+        for i in 0..block_size {
+            match assignment {
+                // left / side
+                8 => b[i] = a[i] - b[i],
+                // side / right
+                9 => a[i] += b[i],
+                // mid / side
+                _ => {
+                    let side = b[i];
+                    let mid = (a[i] << 1) | (side & 1);
+                    a[i] = (mid + side) >> 1;
+                    b[i] = (mid - side) >> 1;
+                }
+            }
+        }
+    }
+
+    // -------------------------------------------------------------
+    // Frame footer (CRC-16 protected)
+    // -------------------------------------------------------------
+    bits.align();
+
+    let crc16 = bits.crc16();
+    if bits.read_bits(16)? as u16 != crc16 {
+        return Err(AudioError::ChecksumMismatch);
+    }
+
+    Ok(block_size)
+}
+
+/// Decode one subframe into `out`.
+fn decode_subframe<R: Read>(
+    bits: &mut BitReader<R>,
+    bps: u32,
+    block_size: usize,
+    out: &mut Vec<i64>,
+) -> Result<(), AudioError> {
+    if bits.read_bits(1)? != 0 {
+        return Err(AudioError::DecodeFailed);
+    }
+
+    let kind = bits.read_bits(6)?;
+
+    let wasted = if bits.read_bits(1)? == 1 {
+        bits.read_unary()? + 1
+    } else {
+        0
+    };
+
+    if wasted >= bps {
+        return Err(AudioError::DecodeFailed);
+    }
+    let bps = bps - wasted;
+
+    out.clear();
+
+    match kind {
+        // CONSTANT
+        0 => {
+            let v = bits.read_signed(bps)?;
+            out.resize(block_size, v);
+        }
+
+        // VERBATIM
+        1 => {
+            for _ in 0..block_size {
+                out.push(bits.read_signed(bps)?);
+            }
+        }
+
+        // FIXED
+        8..=12 => {
+            let order = (kind - 8) as usize;
+            read_warmup(bits, bps, order, block_size, out)?;
+            read_residual(bits, order, block_size, out)?;
+            restore_fixed(order, out);
+        }
+
+        // LPC
+        32..=63 => {
+            let order = (kind - 31) as usize;
+            read_warmup(bits, bps, order, block_size, out)?;
+
+            let precision = bits.read_bits(4)?;
+            if precision == 0xF {
+                return Err(AudioError::DecodeFailed);
+            }
+
+            let shift = bits.read_signed(5)?;
+            if shift < 0 {
+                return Err(AudioError::DecodeFailed);
+            }
+
+            let mut coefs = [0i64; 32];
+            for c in coefs.iter_mut().take(order) {
+                *c = bits.read_signed(precision + 1)?;
+            }
+
+            read_residual(bits, order, block_size, out)?;
+            restore_lpc(&coefs[..order], shift as u32, out);
+        }
+
+        _ => return Err(AudioError::DecodeFailed),
+    }
+
+    if wasted > 0 {
+        for v in out.iter_mut() {
+            *v <<= wasted;
+        }
+    }
+
+    Ok(())
+}
+
+fn read_warmup<R: Read>(
+    bits: &mut BitReader<R>,
+    bps: u32,
+    order: usize,
+    block_size: usize,
+    out: &mut Vec<i64>,
+) -> Result<(), AudioError> {
+    if order > block_size {
+        return Err(AudioError::DecodeFailed);
+    }
+
+    for _ in 0..order {
+        out.push(bits.read_signed(bps)?);
+    }
+
+    Ok(())
+}
+
+/// Read a partitioned Rice residual, appending to `out`.
+fn read_residual<R: Read>(
+    bits: &mut BitReader<R>,
+    order: usize,
+    block_size: usize,
+    out: &mut Vec<i64>,
+) -> Result<(), AudioError> {
+    let (param_bits, escape) = match bits.read_bits(2)? {
+        0 => (4, 0xF),
+        1 => (5, 0x1F),
+        _ => return Err(AudioError::DecodeFailed),
+    };
+
+    let partition_order = bits.read_bits(4)?;
+    let partitions = 1usize << partition_order;
+    let partition_size = block_size >> partition_order;
+
+    if partition_size * partitions != block_size || partition_size < order {
+        return Err(AudioError::DecodeFailed);
+    }
+
+    for p in 0..partitions {
+        let count = if p == 0 {
+            partition_size - order
+        } else {
+            partition_size
+        };
+
+        let param = bits.read_bits(param_bits)?;
+
+        if param == escape {
+            let raw = bits.read_bits(5)?;
+            for _ in 0..count {
+                out.push(if raw == 0 { 0 } else { bits.read_signed(raw)? });
+            }
+        } else {
+            for _ in 0..count {
+                let q = bits.read_unary()? as u64;
+                let r = if param == 0 { 0 } else { bits.read_bits(param)? as u64 };
+                let u = (q << param) | r;
+                out.push((u >> 1) as i64 ^ -((u & 1) as i64));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Undo fixed polynomial prediction in place (residual -> signal).
+fn restore_fixed(order: usize, s: &mut [i64]) {
+    for i in order..s.len() {
+        let prediction = match order {
+            0 => 0,
+            1 => s[i - 1],
+            2 => 2 * s[i - 1] - s[i - 2],
+            3 => 3 * s[i - 1] - 3 * s[i - 2] + s[i - 3],
+            _ => 4 * s[i - 1] - 6 * s[i - 2] + 4 * s[i - 3] - s[i - 4],
+        };
+        s[i] += prediction;
+    }
+}
+
+/// Undo LPC prediction in place (residual -> signal).
+fn restore_lpc(coefs: &[i64], shift: u32, s: &mut [i64]) {
+    let order = coefs.len();
+
+    for i in order..s.len() {
+        let prediction: i64 = coefs
+            .iter()
+            .zip(s[i - order..i].iter().rev())
+            .map(|(c, v)| c * v)
+            .sum();
+        s[i] += prediction >> shift;
+    }
+}
+
+// -------------------------------------------------------------------------
+// Bit reader with running CRC-8 / CRC-16
+// -------------------------------------------------------------------------
+
+/// MSB-first bit reader.
+///
+/// Bytes are pulled on demand, so at every byte-aligned position
+/// the running CRCs cover exactly the bytes consumed so far.
+struct BitReader<R> {
+    inner: R,
+    buf: Box<[u8]>,
+    pos: usize,
+    end: usize,
+
+    acc: u64,
+    bits: u32,
+
+    crc8: u8,
+    crc16: u16,
+}
+
+impl<R: Read> BitReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            buf: vec![0u8; 1 << 16].into_boxed_slice(),
+            pos: 0,
+            end: 0,
+            acc: 0,
+            bits: 0,
+            crc8: 0,
+            crc16: 0,
+        }
+    }
+
+    /// Refill the byte buffer. Returns false at end of stream.
+    fn fill(&mut self) -> Result<bool, AudioError> {
+        if self.pos < self.end {
+            return Ok(true);
+        }
+
+        loop {
+            match self.inner.read(&mut self.buf) {
+                Ok(n) => {
+                    self.pos = 0;
+                    self.end = n;
+                    return Ok(n > 0);
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return Err(AudioError::IoError),
+            }
+        }
+    }
+
+    #[inline]
+    fn next_byte(&mut self) -> Result<u8, AudioError> {
+        if !self.fill()? {
+            return Err(AudioError::DecodeFailed);
+        }
+
+        let b = self.buf[self.pos];
+        self.pos += 1;
+
+        self.crc8 = CRC8_TABLE[(self.crc8 ^ b) as usize];
+        self.crc16 = (self.crc16 << 8) ^ CRC16_TABLE[((self.crc16 >> 8) as u8 ^ b) as usize];
+
+        Ok(b)
+    }
+
+    /// True if more input follows the current (byte-aligned) position.
+    fn has_more(&mut self) -> Result<bool, AudioError> {
+        Ok(self.bits > 0 || self.fill()?)
+    }
+
+    /// Read `n` (0..=32) bits as unsigned.
+    #[inline]
+    fn read_bits(&mut self, n: u32) -> Result<u32, AudioError> {
+        if n == 0 {
+            return Ok(0);
+        }
+
+        while self.bits < n {
+            self.acc = (self.acc << 8) | self.next_byte()? as u64;
+            self.bits += 8;
+        }
+
+        self.bits -= n;
+        Ok(((self.acc >> self.bits) & ((1u64 << n) - 1)) as u32)
+    }
+
+    /// Read `n` (1..=33) bits as two's complement.
+    #[inline]
+    fn read_signed(&mut self, n: u32) -> Result<i64, AudioError> {
+        let v = if n > 32 {
+            let hi = self.read_bits(n - 32)? as u64;
+            (hi << 32) | self.read_bits(32)? as u64
+        } else {
+            self.read_bits(n)? as u64
+        };
+
+        let shift = 64 - n;
+        Ok(((v << shift) as i64) >> shift)
+    }
+
+    /// Count zero bits up to and including the next one bit.
+    #[inline]
+    fn read_unary(&mut self) -> Result<u32, AudioError> {
+        let mut count = 0;
+
+        loop {
+            if self.bits == 0 {
+                self.acc = self.next_byte()? as u64;
+                self.bits = 8;
+            }
+
+            let window = self.acc & ((1u64 << self.bits) - 1);
+            if window == 0 {
+                count += self.bits;
+                self.bits = 0;
+                continue;
+            }
+
+            let top = 63 - window.leading_zeros();
+            count += self.bits - 1 - top;
+            self.bits = top;
+
+            return Ok(count);
+        }
+    }
+
+    /// Drop bits up to the next byte boundary.
+    fn align(&mut self) {
+        self.bits -= self.bits % 8;
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<Vec<u8>, AudioError> {
+        (0..len).map(|_| Ok(self.read_bits(8)? as u8)).collect()
+    }
+
+    fn skip_bytes(&mut self, len: usize) -> Result<(), AudioError> {
+        for _ in 0..len {
+            self.read_bits(8)?;
+        }
+        Ok(())
+    }
+
+    fn reset_crc(&mut self) {
+        self.crc8 = 0;
+        self.crc16 = 0;
+    }
+
+    fn crc8(&self) -> u8 {
+        self.crc8
+    }
+
+    fn crc16(&self) -> u16 {
+        self.crc16
+    }
+}
+
+impl<R: Read + Seek> BitReader<R> {
+    /// Continue reading at byte `offset` of the input.
+    ///
+    /// Must be called at a byte-aligned position.
+    fn seek(&mut self, offset: u64) -> Result<(), AudioError> {
+        self.inner
+            .seek(SeekFrom::Start(offset))
+            .map_err(|_| AudioError::IoError)?;
+
+        self.pos = 0;
+        self.end = 0;
+        self.acc = 0;
+        self.bits = 0;
+
+        Ok(())
+    }
+}
+
+/// CRC-8, polynomial x^8 + x^2 + x + 1 (0x07).
+const CRC8_TABLE: [u8; 256] = {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u8;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 0x80 != 0 { (c << 1) ^ 0x07 } else { c << 1 };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-16, polynomial x^16 + x^15 + x^2 + 1 (0x8005).
+const CRC16_TABLE: [u16; 256] = {
+    let mut table = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = (i as u16) << 8;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 0x8000 != 0 { (c << 1) ^ 0x8005 } else { c << 1 };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+// -------------------------------------------------------------------------
+// MD5 (RFC 1321), used for the STREAMINFO signature only
+// -------------------------------------------------------------------------
+
+struct Md5 {
+    state: [u32; 4],
+    buffer: [u8; 64],
+    buffered: usize,
+    length: u64,
+}
+
+impl Md5 {
+    fn new() -> Self {
+        Self {
+            state: [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476],
+            buffer: [0u8; 64],
+            buffered: 0,
+            length: 0,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        if self.buffered > 0 {
+            let n = (64 - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + n].copy_from_slice(&data[..n]);
+            self.buffered += n;
+            data = &data[n..];
+
+            if self.buffered < 64 {
+                return;
+            }
+
+            let block = self.buffer;
+            self.compress(&block);
+            self.buffered = 0;
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            self.compress(block.try_into().unwrap());
+        }
+
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    fn finish(mut self) -> [u8; 16] {
+        let bit_len = self.length.wrapping_mul(8);
+
+        self.update(&[0x80]);
+        while self.buffered != 56 {
+            self.update(&[0]);
+        }
+        self.update(&bit_len.to_le_bytes());
+
+        let mut out = [0u8; 16];
+        for (chunk, word) in out.chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        out
+    }
+
+    fn compress(&mut self, block: &[u8; 64]) {
+        const S: [u32; 64] = [
+            7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22,
+            5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20,
+            4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23,
+            6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+        ];
+
+        const K: [u32; 64] = {
+            // floor(abs(sin(i + 1)) * 2^32), precomputed
+            [
+                0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a,
+                0xa8304613, 0xfd469501, 0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be,
+                0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821, 0xf61e2562, 0xc040b340,
+                0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+                0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8,
+                0x676f02d9, 0x8d2a4c8a, 0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c,
+                0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70, 0x289b7ec6, 0xeaa127fa,
+                0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+                0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92,
+                0xffeff47d, 0x85845dd1, 0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1,
+                0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+            ]
+        };
+
+        let mut m = [0u32; 16];
+        for (word, chunk) in m.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+
+            let f = f
+                .wrapping_add(a)
+                .wrapping_add(K[i])
+                .wrapping_add(m[g]);
+
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(f.rotate_left(S[i]));
+        }
+
+        self.state[0] = self.state[0].wrapping_add(a);
+        self.state[1] = self.state[1].wrapping_add(b);
+        self.state[2] = self.state[2].wrapping_add(c);
+        self.state[3] = self.state[3].wrapping_add(d);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const RATE: u32 = 8000;
+    const BLOCK: usize = 32;
+
+    /// MSB-first bit writer, the counterpart of `BitReader`.
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        acc: u8,
+        bits: u32,
+    }
+
+    impl BitWriter {
+        /// Write the low `n` bits of `value` (two's complement for negatives).
+        fn put(&mut self, n: u32, value: i64) {
+            for i in (0..n).rev() {
+                self.acc = (self.acc << 1) | ((value >> i) & 1) as u8;
+                self.bits += 1;
+
+                if self.bits == 8 {
+                    self.bytes.push(self.acc);
+                    self.acc = 0;
+                    self.bits = 0;
+                }
+            }
+        }
+
+        fn put_unary(&mut self, zeros: u64) {
+            for _ in 0..zeros {
+                self.put(1, 0);
+            }
+            self.put(1, 1);
+        }
+
+        fn align(&mut self) {
+            while self.bits != 0 {
+                self.put(1, 0);
+            }
+        }
+    }
+
+    enum Subframe {
+        Constant,
+        Verbatim,
+        Fixed(usize),
+        Lpc { coefs: Vec<i64>, shift: u32 },
+    }
+
+    /// Encode one subframe of `samples`, all multiples of `1 << wasted`.
+    fn subframe(w: &mut BitWriter, kind: &Subframe, bps: u32, wasted: u32, samples: &[i64]) {
+        let s: Vec<i64> = samples.iter().map(|v| v >> wasted).collect();
+        let bps = bps - wasted;
+
+        w.put(1, 0);
+        w.put(
+            6,
+            match kind {
+                Subframe::Constant => 0,
+                Subframe::Verbatim => 1,
+                Subframe::Fixed(order) => 8 + *order as i64,
+                Subframe::Lpc { coefs, .. } => 31 + coefs.len() as i64,
+            },
+        );
+        if wasted > 0 {
+            w.put(1, 1);
+            w.put_unary(wasted as u64 - 1);
+        } else {
+            w.put(1, 0);
+        }
+
+        match kind {
+            Subframe::Constant => w.put(bps, s[0]),
+            Subframe::Verbatim => s.iter().for_each(|&v| w.put(bps, v)),
+            Subframe::Fixed(order) => {
+                s[..*order].iter().for_each(|&v| w.put(bps, v));
+
+                let predicted: Vec<i64> = (*order..s.len())
+                    .map(|i| match order {
+                        0 => 0,
+                        1 => s[i - 1],
+                        2 => 2 * s[i - 1] - s[i - 2],
+                        3 => 3 * s[i - 1] - 3 * s[i - 2] + s[i - 3],
+                        _ => 4 * s[i - 1] - 6 * s[i - 2] + 4 * s[i - 3] - s[i - 4],
+                    })
+                    .collect();
+                residual(w, *order, &s, &predicted);
+            }
+            Subframe::Lpc { coefs, shift } => {
+                let order = coefs.len();
+                s[..order].iter().for_each(|&v| w.put(bps, v));
+
+                w.put(4, 14); // 15 bit coefficients
+                w.put(5, *shift as i64);
+                coefs.iter().for_each(|&c| w.put(15, c));
+
+                let predicted: Vec<i64> = (order..s.len())
+                    .map(|i| {
+                        let sum: i64 = (0..order).map(|j| coefs[j] * s[i - 1 - j]).sum();
+                        sum >> shift
+                    })
+                    .collect();
+                residual(w, order, &s, &predicted);
+            }
+        }
+    }
+
+    /// Rice residual in two partitions, the second one escaped to raw bits.
+    fn residual(w: &mut BitWriter, order: usize, s: &[i64], predicted: &[i64]) {
+        let residual: Vec<i64> = s[order..].iter().zip(predicted).map(|(v, p)| v - p).collect();
+        let (rice, raw) = residual.split_at(s.len() / 2 - order);
+
+        w.put(2, 0);
+        w.put(4, 1);
+
+        let zigzag = |v: i64| ((v << 1) ^ (v >> 63)) as u64;
+        let param = (0..15u32)
+            .find(|&p| rice.iter().all(|&v| zigzag(v) >> p < 32))
+            .unwrap();
+
+        w.put(4, param as i64);
+        for &v in rice {
+            let u = zigzag(v);
+            w.put_unary(u >> param);
+            w.put(param, (u & ((1 << param) - 1)) as i64);
+        }
+
+        w.put(4, 0xF);
+        w.put(5, 20);
+        raw.iter().for_each(|&v| w.put(20, v));
+    }
+
+    /// Running CRC-8 / CRC-16 of `bytes`, as computed by `BitReader`.
+    fn checksums(bytes: &[u8]) -> (u8, u16) {
+        let mut bits = BitReader::new(Cursor::new(bytes));
+        bits.skip_bytes(bytes.len()).unwrap();
+        (bits.crc8(), bits.crc16())
+    }
+
+    /// One fixed-blocksize frame of `BLOCK` samples.
+    fn frame(number: u8, assignment: i64, subframes: impl FnOnce(&mut BitWriter)) -> Vec<u8> {
+        let mut w = BitWriter::default();
+
+        w.put(15, 0x7FFC);
+        w.put(1, 0);
+        w.put(4, 7); // 16 bit block size after the frame number
+        w.put(4, 0); // sample rate from STREAMINFO
+        w.put(4, assignment);
+        w.put(3, 0); // sample size from STREAMINFO
+        w.put(1, 0);
+        w.put(8, number as i64);
+        w.put(16, BLOCK as i64 - 1);
+        w.put(8, checksums(&w.bytes).0 as i64);
+
+        subframes(&mut w);
+
+        w.align();
+        w.put(16, checksums(&w.bytes).1 as i64);
+        w.bytes
+    }
+
+    /// `fLaC` marker, STREAMINFO, optional SEEKTABLE, then `frames`.
+    fn flac(channels: u16, md5: [u8; 16], seek_table: &[SeekPoint], frames: &[Vec<u8>]) -> Vec<u8> {
+        let total = (frames.len() * BLOCK) as u64;
+        let mut out = b"fLaC".to_vec();
+
+        let last = if seek_table.is_empty() { 0x80 } else { 0 };
+        out.extend_from_slice(&[last | BLOCK_STREAMINFO, 0, 0, 34]);
+        out.extend_from_slice(&(BLOCK as u16).to_be_bytes());
+        out.extend_from_slice(&(BLOCK as u16).to_be_bytes());
+        out.extend_from_slice(&[0; 6]);
+        let packed = (RATE as u64) << 44 | (channels as u64 - 1) << 41 | 15 << 36 | total;
+        out.extend_from_slice(&packed.to_be_bytes());
+        out.extend_from_slice(&md5);
+
+        if !seek_table.is_empty() {
+            out.push(0x80 | BLOCK_SEEKTABLE);
+            out.extend_from_slice(&((seek_table.len() * 18) as u32).to_be_bytes()[1..]);
+            for point in seek_table {
+                out.extend_from_slice(&point.sample.to_be_bytes());
+                out.extend_from_slice(&point.offset.to_be_bytes());
+                out.extend_from_slice(&(BLOCK as u16).to_be_bytes());
+            }
+        }
+
+        frames.iter().for_each(|f| out.extend_from_slice(f));
+        out
+    }
+
+    /// STREAMINFO signature of interleaved 16 bit samples.
+    fn signature(samples: &[i64]) -> [u8; 16] {
+        let mut md5 = Md5::new();
+        samples.iter().for_each(|v| md5.update(&v.to_le_bytes()[..2]));
+        md5.finish()
+    }
+
+    /// Decode to interleaved 16 bit integers.
+    fn decode(bytes: &[u8], range: TimeRange) -> Result<Vec<i64>, AudioError> {
+        let mut stream = FlacStream::new(Cursor::new(bytes))?;
+        if !range.is_full() {
+            stream.crop(range)?;
+        }
+
+        let buffer = read_to_end(&mut stream)?;
+        Ok(buffer.samples.iter().map(|&s| (s * 32768.0) as i64).collect())
+    }
+
+    fn noise(seed: i64) -> Vec<i64> {
+        (0..BLOCK as i64).map(|i| (i * i * seed + 7 * i) % 2001 - 1000).collect()
+    }
+
+    fn sine(amplitude: f64) -> Vec<i64> {
+        (0..BLOCK).map(|i| (amplitude * (i as f64 * 0.3).sin()).round() as i64).collect()
+    }
+
+    /// Mono stream of verbatim frames.
+    fn verbatim_frames(blocks: &[Vec<i64>]) -> Vec<Vec<u8>> {
+        blocks
+            .iter()
+            .enumerate()
+            .map(|(n, s)| frame(n as u8, 0, |w| subframe(w, &Subframe::Verbatim, 16, 0, s)))
+            .collect()
+    }
+
+    #[test]
+    fn checksums_match_reference_values() {
+        // CRC-8 (poly 0x07) and CRC-16/UMTS (poly 0x8005) check values
+        assert_eq!(checksums(b"123456789"), (0xF4, 0xFEE8));
+
+        // RFC 1321 test suite
+        let vectors: [(&[u8], &str); 7] = [
+            (b"", "d41d8cd98f00b204e9800998ecf8427e"),
+            (b"a", "0cc175b9c0f1b6a831c399e269772661"),
+            (b"abc", "900150983cd24fb0d6963f7d28e17f72"),
+            (b"message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+            (b"abcdefghijklmnopqrstuvwxyz", "c3fcd3d76192e4007dfb496cca67e13b"),
+            (
+                b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                "d174ab98d277d9f5a5611c2c9f419d9f",
+            ),
+            (
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "57edf4a22be3c955ac49da2e2107b67a",
+            ),
+        ];
+
+        for (input, expected) in vectors {
+            let hex = |md5: [u8; 16]| md5.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+
+            let mut md5 = Md5::new();
+            md5.update(input);
+            assert_eq!(hex(md5.finish()), expected);
+
+            // Byte-wise updates go through the partial block buffer
+            let mut md5 = Md5::new();
+            input.iter().for_each(|b| md5.update(&[*b]));
+            assert_eq!(hex(md5.finish()), expected);
+        }
+    }
+
+    #[test]
+    fn decodes_every_subframe_type() {
+        let constant = vec![-1234; BLOCK];
+        let noise = noise(13);
+        let sine = sine(12000.0);
+        let shifted: Vec<i64> = noise.iter().map(|v| v * 8).collect();
+
+        // 2 * cos(0.3) in 12 bit fixed point
+        let lpc = Subframe::Lpc {
+            coefs: vec![7826, -4096],
+            shift: 12,
+        };
+
+        let mut blocks = vec![
+            (Subframe::Constant, 0, &constant),
+            (Subframe::Verbatim, 0, &noise),
+            (lpc, 0, &sine),
+            (Subframe::Verbatim, 3, &shifted),
+        ];
+        for order in 0..=4 {
+            blocks.push((Subframe::Fixed(order), 0, &sine));
+        }
+
+        let frames: Vec<Vec<u8>> = blocks
+            .iter()
+            .enumerate()
+            .map(|(n, (kind, wasted, s))| frame(n as u8, 0, |w| subframe(w, kind, 16, *wasted, s)))
+            .collect();
+
+        let expected: Vec<i64> = blocks.iter().flat_map(|(_, _, s)| s.iter().copied()).collect();
+        let bytes = flac(1, signature(&expected), &[], &frames);
+
+        assert_eq!(decode(&bytes, TimeRange::FULL).unwrap(), expected);
+
+        // STREAMINFO promises more frames than the file holds
+        let cut = bytes.len() - frames.last().unwrap().len();
+        assert!(matches!(
+            decode(&bytes[..cut], TimeRange::FULL),
+            Err(AudioError::DecodeFailed)
+        ));
+    }
+
+    #[test]
+    fn decodes_stereo_decorrelation_modes() {
+        let left = noise(5);
+        let right = noise(11);
+
+        let side: Vec<i64> = left.iter().zip(&right).map(|(l, r)| l - r).collect();
+        let mid: Vec<i64> = left.iter().zip(&right).map(|(l, r)| (l + r) >> 1).collect();
+        assert!(side.iter().any(|s| s & 1 == 1));
+
+        let channels = |w: &mut BitWriter, a: (&[i64], u32), b: (&[i64], u32)| {
+            subframe(w, &Subframe::Verbatim, a.1, 0, a.0);
+            subframe(w, &Subframe::Fixed(1), b.1, 0, b.0);
+        };
+
+        let frames = [
+            frame(0, 1, |w| channels(w, (&left, 16), (&right, 16))),
+            frame(1, 8, |w| channels(w, (&left, 16), (&side, 17))),
+            frame(2, 9, |w| channels(w, (&side, 17), (&right, 16))),
+            frame(3, 10, |w| channels(w, (&mid, 16), (&side, 17))),
+        ];
+
+        let pairs: Vec<i64> = left.iter().zip(&right).flat_map(|(&l, &r)| [l, r]).collect();
+        let expected = pairs.repeat(frames.len());
+        let bytes = flac(2, signature(&expected), &[], &frames);
+
+        assert_eq!(decode(&bytes, TimeRange::FULL).unwrap(), expected);
+    }
+
+    #[test]
+    fn checksum_mismatches_are_reported() {
+        let samples = noise(3);
+        let frames = verbatim_frames(std::slice::from_ref(&samples));
+        let md5 = signature(&samples);
+
+        assert_eq!(decode(&flac(1, md5, &[], &frames), TimeRange::FULL).unwrap(), samples);
+        assert_eq!(decode(&flac(1, [0; 16], &[], &frames), TimeRange::FULL).unwrap(), samples);
+
+        // Wrong STREAMINFO signature
+        let mut wrong = md5;
+        wrong[7] ^= 1;
+        assert!(matches!(
+            decode(&flac(1, wrong, &[], &frames), TimeRange::FULL),
+            Err(AudioError::ChecksumMismatch)
+        ));
+
+        // Flipped bit in the verbatim payload (CRC-16) and in the header (CRC-8)
+        for at in [20, 4] {
+            let mut frames = frames.clone();
+            frames[0][at] ^= 0x10;
+            assert!(matches!(
+                decode(&flac(1, md5, &[], &frames), TimeRange::FULL),
+                Err(AudioError::ChecksumMismatch)
+            ));
+        }
+    }
+
+    #[test]
+    fn seek_table_crop_matches_full_decode() {
+        let blocks: Vec<Vec<i64>> = (0..6).map(|n| noise(3 + 2 * n)).collect();
+        let frames = verbatim_frames(&blocks);
+
+        let offsets: Vec<u64> = frames
+            .iter()
+            .scan(0u64, |at, f| {
+                let offset = *at;
+                *at += f.len() as u64;
+                Some(offset)
+            })
+            .collect();
+        let seek_table: Vec<SeekPoint> = [0, 2, 4]
+            .iter()
+            .map(|&n| SeekPoint {
+                sample: (n * BLOCK) as u64,
+                offset: offsets[n],
+            })
+            .chain(std::iter::once(SeekPoint {
+                sample: SEEK_PLACEHOLDER,
+                offset: 0,
+            }))
+            .collect();
+
+        let expected: Vec<i64> = blocks.concat();
+        let md5 = signature(&expected);
+        let plain = flac(1, md5, &[], &frames);
+        let mut seekable = flac(1, md5, &seek_table, &frames);
+
+        assert_eq!(decode(&plain, TimeRange::FULL).unwrap(), expected);
+        assert_eq!(decode(&seekable, TimeRange::FULL).unwrap(), expected);
+
+        // Samples 70..150 start in frame 2; the seek point skips frames 0 and 1
+        let range = TimeRange {
+            start: 70.0 / RATE as f64,
+            end: Some(150.0 / RATE as f64),
+        };
+        let frame_one = seekable.len() - frames.iter().skip(1).map(Vec::len).sum::<usize>();
+        seekable[frame_one] ^= 0xFF;
+        assert!(decode(&seekable, TimeRange::FULL).is_err());
+
+        assert_eq!(decode(&plain, range).unwrap(), &expected[70..150]);
+        assert_eq!(decode(&seekable, range).unwrap(), &expected[70..150]);
+
+        let open_end = TimeRange {
+            start: 100.0 / RATE as f64,
+            end: None,
+        };
+        assert_eq!(decode(&seekable, open_end).unwrap(), &expected[100..]);
+    }
+}
@@ -1,20 +1,20 @@
 //! Audio input abstraction for spek-core.
 //!
 //! In spek-core, "audio" means:
-//! - invoking an external backend (ffmpeg)
-//! - obtaining a rendered spectrogram image
-//! - collecting minimal audio metadata
+//! - opening an audio file or stream via a backend
+//! - collecting audio metadata
+//! - providing interleaved f32 PCM samples
 //!
-//! spek-core does NOT decode PCM,
-//! does NOT perform FFT,
-//! and does NOT do DSP of any kind.
+//! spek-core audio sources do NOT perform FFT,
+//! do NOT render and do NOT interpret the signal.
 
-use crate::render::ImageBuffer;
+pub mod ffmpeg;
+pub mod flac;
+pub mod wav;
 
-/// Audio metadata required for legend rendering.
+/// Audio metadata required for analysis and legend rendering.
 ///
-/// This metadata is informational only and
-/// mirrors what Spek / spek-rs exposes.
+/// This metadata mirrors what Spek / spek-rs exposes.
 #[derive(Debug, Clone)]
 pub struct AudioMetadata {
     /// Sample rate in Hz (e.g. 44100)
@@ -23,41 +23,307 @@ pub struct AudioMetadata {
     /// Number of channels (1 = mono, 2 = stereo, ...)
     pub channels: u16,
 
-    /// Total duration in seconds
-    pub duration_sec: f64,
+    /// Total number of samples per channel
+    pub total_samples: u64,
 
     /// Bit depth of the original source, if known
     pub bit_depth: Option<u16>,
+
+    /// Codec / container name (e.g. "FLAC"), if known
+    pub codec: Option<String>,
+
+    /// Embedded tags as (KEY, value) pairs, e.g. Vorbis comments
+    pub tags: Vec<(String, String)>,
 }
 
-/// Result of an audio source invocation.
+impl AudioMetadata {
+    /// Total duration in seconds.
+    pub fn duration_sec(&self) -> f64 {
+        if self.sample_rate == 0 {
+            return 0.0;
+        }
+        self.total_samples as f64 / self.sample_rate as f64
+    }
+}
+
+/// Decoded PCM audio.
 ///
-/// This is the ONLY data spek-core needs
-/// from an audio backend.
-#[derive(Debug)]
-pub struct SpectrogramSource {
-    /// Rendered spectrogram image (RGBA, opaque)
-    pub image: ImageBuffer,
+/// Samples are interleaved f32 in range [-1.0, 1.0]:
+/// samples[frame * channels + channel]
+#[derive(Debug, Clone)]
+pub struct AudioBuffer {
+    /// Interleaved PCM samples
+    pub samples: Vec<f32>,
 
-    /// Audio metadata for legend / UI
+    /// Audio metadata
     pub meta: AudioMetadata,
 }
 
+impl AudioBuffer {
+    /// Stream this buffer block by block.
+    ///
+    /// Lets in-memory audio run through the streaming pipeline.
+    pub fn stream(&self) -> BufferStream<'_> {
+        BufferStream {
+            samples: &self.samples,
+            meta: &self.meta,
+            pos: 0,
+        }
+    }
+}
+
 /// Abstract audio source.
 ///
 /// Implementations:
 /// - ffmpeg CLI backend (Linux, Colab)
+/// - native WAV / FLAC decoders
 /// - later: platform-specific wrappers
 ///
 /// IMPORTANT:
-/// Implementations MUST render the spectrogram themselves.
-/// spek-core never touches PCM or FFT data.
+/// Implementations MUST only decode.
+/// Analysis and rendering happen in the core pipeline.
 pub trait AudioSource {
-    /// Generate a spectrogram image and metadata.
-    fn load(&self) -> Result<SpectrogramSource, AudioError>;
+    /// Decode the complete audio into memory.
+    fn load(&self) -> Result<AudioBuffer, AudioError>;
+
+    /// Decode only `range` into memory.
+    ///
+    /// The default decodes everything and crops afterwards;
+    /// backends that can seek should override this.
+    fn load_range(&self, range: TimeRange) -> Result<AudioBuffer, AudioError> {
+        let audio = self.load()?;
+        let mut stream = RangeStream::new(Box::new(audio.stream()), range)?;
+        read_to_end(&mut stream)
+    }
+}
+
+/// Incremental PCM reader.
+///
+/// Yields interleaved f32 blocks in range [-1.0, 1.0].
+/// Blocks always contain whole frames (all channels).
+pub trait PcmStream {
+    /// Audio metadata, known before the first block.
+    ///
+    /// `total_samples` is the expected length, see `exact_length`.
+    fn meta(&self) -> &AudioMetadata;
+
+    /// True if the stream yields exactly `total_samples` frames.
+    ///
+    /// Estimates (container durations, unknown lengths) must return false;
+    /// consumers then take the length from the decoded audio instead.
+    fn exact_length(&self) -> bool {
+        false
+    }
+
+    /// Replace `block` with the next block of samples.
+    ///
+    /// Returns `Ok(false)` at end of stream.
+    fn read_block(&mut self, block: &mut Vec<f32>) -> Result<bool, AudioError>;
+}
+
+/// Audio source that can be decoded block by block.
+///
+/// Memory use of a streaming source is independent of the audio length.
+pub trait StreamingAudioSource {
+    /// Open a new PCM stream from the start of the audio.
+    fn open(&self) -> Result<Box<dyn PcmStream + '_>, AudioError>;
+
+    /// Open a PCM stream covering only `range`.
+    ///
+    /// The default decodes and drops everything before the range;
+    /// backends that can seek should override this.
+    fn open_range(&self, range: TimeRange) -> Result<Box<dyn PcmStream + '_>, AudioError> {
+        Ok(Box::new(RangeStream::new(self.open()?, range)?))
+    }
+}
+
+/// Excerpt of the audio, in seconds from the start of the file.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TimeRange {
+    /// First second to decode
+    pub start: f64,
+
+    /// End of the excerpt (exclusive), `None` = end of file
+    pub end: Option<f64>,
+}
+
+impl TimeRange {
+    /// The whole file.
+    pub const FULL: TimeRange = TimeRange {
+        start: 0.0,
+        end: None,
+    };
+
+    pub fn is_full(&self) -> bool {
+        *self == Self::FULL
+    }
+
+    /// First frame and frame count (`None` = to the end) at `sample_rate`.
+    ///
+    /// Returns `InvalidRange` for negative, non-finite or empty ranges.
+    pub fn frames(&self, sample_rate: u32) -> Result<(u64, Option<u64>), AudioError> {
+        let valid = self.start.is_finite()
+            && self.start >= 0.0
+            && self.end.is_none_or(|end| end.is_finite() && end > self.start);
+
+        if !valid {
+            return Err(AudioError::InvalidRange);
+        }
+
+        let rate = sample_rate as f64;
+        let start = (self.start * rate).round() as u64;
+        let len = self
+            .end
+            .map(|end| ((end * rate).round() as u64).saturating_sub(start));
+
+        Ok((start, len))
+    }
+}
+
+impl Default for TimeRange {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+/// `PcmStream` over an in-memory `AudioBuffer`.
+pub struct BufferStream<'a> {
+    samples: &'a [f32],
+    meta: &'a AudioMetadata,
+    pos: usize,
+}
+
+impl PcmStream for BufferStream<'_> {
+    fn meta(&self) -> &AudioMetadata {
+        self.meta
+    }
+
+    fn exact_length(&self) -> bool {
+        self.samples.len() as u64 == self.meta.total_samples * self.meta.channels.max(1) as u64
+    }
+
+    fn read_block(&mut self, block: &mut Vec<f32>) -> Result<bool, AudioError> {
+        let channels = (self.meta.channels as usize).max(1);
+        let len = (STREAM_BLOCK_FRAMES * channels).min(self.samples.len() - self.pos);
+
+        block.clear();
+        block.extend_from_slice(&self.samples[self.pos..self.pos + len]);
+        self.pos += len;
+
+        Ok(len > 0)
+    }
+}
+
+/// `PcmStream` adapter that drops frames outside a `TimeRange`.
+///
+/// Used by sources that cannot seek: leading frames are still decoded,
+/// but nothing after the range is read.
+pub struct RangeStream<'a> {
+    inner: Box<dyn PcmStream + 'a>,
+    meta: AudioMetadata,
+
+    /// Frames still to drop before the range
+    skip: u64,
+
+    /// Frames still to pass through, `None` = unlimited
+    remaining: Option<u64>,
+
+    /// `meta.total_samples` is exact
+    exact: bool,
 }
 
-/// Audio loading / rendering errors.
+impl<'a> RangeStream<'a> {
+    pub fn new(inner: Box<dyn PcmStream + 'a>, range: TimeRange) -> Result<Self, AudioError> {
+        let mut meta = inner.meta().clone();
+        let (skip, remaining) = range.frames(meta.sample_rate)?;
+
+        // A crop of an unknown length is only an upper bound
+        let exact = inner.exact_length() && meta.total_samples > 0;
+        meta.total_samples = cropped_len(meta.total_samples, skip, remaining);
+
+        Ok(Self {
+            inner,
+            meta,
+            skip,
+            remaining,
+            exact,
+        })
+    }
+}
+
+impl PcmStream for RangeStream<'_> {
+    fn meta(&self) -> &AudioMetadata {
+        &self.meta
+    }
+
+    fn exact_length(&self) -> bool {
+        self.exact
+    }
+
+    fn read_block(&mut self, block: &mut Vec<f32>) -> Result<bool, AudioError> {
+        let channels = (self.meta.channels as usize).max(1);
+
+        loop {
+            if self.remaining == Some(0) || !self.inner.read_block(block)? {
+                block.clear();
+                return Ok(false);
+            }
+
+            let frames = (block.len() / channels) as u64;
+
+            if self.skip >= frames {
+                self.skip -= frames;
+                continue;
+            }
+
+            block.drain(..self.skip as usize * channels);
+            self.skip = 0;
+
+            if let Some(remaining) = self.remaining.as_mut() {
+                let keep = (block.len() / channels).min(*remaining as usize);
+                block.truncate(keep * channels);
+                *remaining -= keep as u64;
+            }
+
+            return Ok(true);
+        }
+    }
+}
+
+/// Length of a crop of `total` frames (`total` 0 = unknown).
+pub(crate) fn cropped_len(total: u64, start: u64, len: Option<u64>) -> u64 {
+    match (total, len) {
+        (0, Some(len)) => len,
+        (0, None) => 0,
+        (total, len) => total.saturating_sub(start).min(len.unwrap_or(u64::MAX)),
+    }
+}
+
+/// Preferred number of frames per streamed block.
+pub(crate) const STREAM_BLOCK_FRAMES: usize = 16384;
+
+/// Drain a stream into an in-memory buffer.
+///
+/// `total_samples` is corrected to the decoded length.
+pub(crate) fn read_to_end(stream: &mut dyn PcmStream) -> Result<AudioBuffer, AudioError> {
+    let mut meta = stream.meta().clone();
+    let channels = (meta.channels as usize).max(1);
+
+    let mut samples: Vec<f32> = Vec::with_capacity(
+        (meta.total_samples * channels as u64).min(1 << 24) as usize,
+    );
+    let mut block = Vec::new();
+
+    while stream.read_block(&mut block)? {
+        samples.extend_from_slice(&block);
+    }
+
+    meta.total_samples = (samples.len() / channels) as u64;
+
+    Ok(AudioBuffer { samples, meta })
+}
+
+/// Audio loading / decoding errors.
 #[derive(Debug)]
 pub enum AudioError {
     /// Input format not supported by backend
@@ -69,6 +335,12 @@ pub enum AudioError {
     /// I/O failure
     IoError,
 
+    /// Stream integrity check (CRC / MD5) failed
+    ChecksumMismatch,
+
     /// Operation cancelled by caller
     Cancelled,
+
+    /// Requested time range is negative or empty
+    InvalidRange,
 }
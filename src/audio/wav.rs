@@ -0,0 +1,553 @@
+//! Native WAV / RF64 decoder for spek-core.
+//!
+//! This module provides a pure-Rust AudioSource implementation
+//! that needs NO external tools.
+//!
+//! Target:
+//! - CI containers without ffmpeg
+//! - Android / iPadOS
+//!
+//! Supported:
+//! - RIFF WAVE, RF64 and BW64 (files > 4 GB via `ds64` chunk)
+//! - Integer PCM, 8 / 16 / 24 / 32-bit
+//! - IEEE float, 32 / 64-bit
+//! - WAVE_FORMAT_EXTENSIBLE (incl. valid-bits reporting)
+//!
+//! Decodes audio into interleaved f32 PCM samples in range [-1.0, 1.0].
+
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read, Seek, SeekFrom, Take};
+use std::path::PathBuf;
+
+use crate::audio::{
+    cropped_len, read_to_end, AudioBuffer, AudioError, AudioMetadata, AudioSource, PcmStream,
+    StreamingAudioSource, TimeRange, STREAM_BLOCK_FRAMES,
+};
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Placeholder size used by RF64 / BW64 for chunks > 4 GB.
+const RF64_SIZE_MARKER: u32 = 0xFFFF_FFFF;
+
+/// Native WAV audio source.
+#[derive(Debug, Clone)]
+pub struct WavAudioSource {
+    /// Path to the input audio file
+    pub path: PathBuf,
+}
+
+impl WavAudioSource {
+    /// Create a new WAV audio source from a file path.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl AudioSource for WavAudioSource {
+    fn load(&self) -> Result<AudioBuffer, AudioError> {
+        read_to_end(self.open()?.as_mut())
+    }
+
+    fn load_range(&self, range: TimeRange) -> Result<AudioBuffer, AudioError> {
+        read_to_end(self.open_range(range)?.as_mut())
+    }
+}
+
+impl StreamingAudioSource for WavAudioSource {
+    fn open(&self) -> Result<Box<dyn PcmStream + '_>, AudioError> {
+        let file = File::open(&self.path).map_err(|_| AudioError::IoError)?;
+        Ok(Box::new(WavStream::new(BufReader::new(file))?))
+    }
+
+    /// Seeks straight to the first frame of the range.
+    fn open_range(&self, range: TimeRange) -> Result<Box<dyn PcmStream + '_>, AudioError> {
+        let file = File::open(&self.path).map_err(|_| AudioError::IoError)?;
+
+        let mut stream = WavStream::new(BufReader::new(file))?;
+        stream.crop(range)?;
+
+        Ok(Box::new(stream))
+    }
+}
+
+/// Sample encoding of the `data` chunk.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Encoding {
+    Pcm,
+    Float,
+}
+
+/// Parsed `fmt ` chunk.
+#[derive(Debug, Copy, Clone)]
+struct WavFormat {
+    encoding: Encoding,
+    channels: u16,
+    sample_rate: u32,
+    block_align: u16,
+
+    /// Bits per sample slot in the file
+    container_bits: u16,
+
+    /// Significant bits (WAVE_FORMAT_EXTENSIBLE), else container bits
+    valid_bits: u16,
+}
+
+/// Incremental WAV / RF64 reader positioned inside the `data` chunk.
+struct WavStream<R> {
+    data: Take<R>,
+    format: WavFormat,
+    meta: AudioMetadata,
+
+    /// Raw bytes of whole frames, plus a partial frame carried over
+    raw: Vec<u8>,
+    filled: usize,
+
+    /// `data` size is known; ending early is an error
+    sized: bool,
+}
+
+impl<R: Read + Seek> WavStream<R> {
+    /// Parse the header chunks and stop at the start of the audio data.
+    fn new(mut reader: R) -> Result<Self, AudioError> {
+        // -------------------------------------------------------------
+        // 1. RIFF / RF64 / BW64 header
+        // -------------------------------------------------------------
+        let mut header = [0u8; 12];
+        reader.read_exact(&mut header).map_err(map_io_error)?;
+
+        let rf64 = match &header[0..4] {
+            b"RIFF" => false,
+            b"RF64" | b"BW64" => true,
+            _ => return Err(AudioError::UnsupportedFormat),
+        };
+
+        if &header[8..12] != b"WAVE" {
+            return Err(AudioError::UnsupportedFormat);
+        }
+
+        // -------------------------------------------------------------
+        // 2. Walk chunks until `data`
+        // -------------------------------------------------------------
+        let mut ds64_data_size: Option<u64> = None;
+        let mut format: Option<WavFormat> = None;
+
+        loop {
+            let mut chunk = [0u8; 8];
+            reader.read_exact(&mut chunk).map_err(map_io_error)?;
+
+            let size = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+
+            match &chunk[0..4] {
+                b"ds64" if rf64 => {
+                    let body = read_chunk(&mut reader, size)?;
+                    if body.len() < 24 {
+                        return Err(AudioError::DecodeFailed);
+                    }
+                    ds64_data_size = Some(u64_le(&body[8..16]));
+                }
+
+                b"fmt " => {
+                    let body = read_chunk(&mut reader, size)?;
+                    format = Some(parse_format(&body)?);
+                }
+
+                b"data" => {
+                    let format = format.ok_or(AudioError::DecodeFailed)?;
+
+                    // A plain RIFF placeholder size means "until end of file"
+                    let (len, sized) = match (rf64, size) {
+                        (true, RF64_SIZE_MARKER) => {
+                            (ds64_data_size.ok_or(AudioError::DecodeFailed)?, true)
+                        }
+                        (false, RF64_SIZE_MARKER) => (u64::MAX, false),
+                        _ => (size as u64, true),
+                    };
+
+                    let frame_bytes = format.block_align as usize;
+
+                    // -------------------------------------------------
+                    // Assemble metadata
+                    // -------------------------------------------------
+                    let meta = AudioMetadata {
+                        sample_rate: format.sample_rate,
+                        channels: format.channels,
+                        total_samples: if sized { len / frame_bytes as u64 } else { 0 },
+                        bit_depth: Some(format.valid_bits),
+                        codec: Some("WAV".to_string()),
+                        tags: Vec::new(),
+                    };
+
+                    return Ok(Self {
+                        data: reader.take(len),
+                        format,
+                        meta,
+                        raw: vec![0u8; STREAM_BLOCK_FRAMES * frame_bytes],
+                        filled: 0,
+                        sized,
+                    });
+                }
+
+                _ => {
+                    let skip = size as i64 + (size & 1) as i64;
+                    reader
+                        .seek(SeekFrom::Current(skip))
+                        .map_err(|_| AudioError::IoError)?;
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read + Seek> WavStream<R> {
+    /// Restrict the stream to `range` (before the first read).
+    fn crop(&mut self, range: TimeRange) -> Result<(), AudioError> {
+        let (start, len) = range.frames(self.meta.sample_rate)?;
+        let frames = cropped_len(self.meta.total_samples, start, len);
+        let frame_bytes = self.format.block_align as u64;
+
+        // Frames are fixed-size: seek directly to the first one
+        let (skip, limit) = match self.sized {
+            true => (start.min(self.meta.total_samples), frames * frame_bytes),
+            false => (start, len.map_or(u64::MAX, |len| len * frame_bytes)),
+        };
+        self.data
+            .get_mut()
+            .seek(SeekFrom::Current((skip * frame_bytes) as i64))
+            .map_err(|_| AudioError::IoError)?;
+
+        self.data.set_limit(limit);
+        self.meta.total_samples = frames;
+
+        Ok(())
+    }
+}
+
+/// Read a small chunk body including its pad byte.
+fn read_chunk<R: Read>(reader: &mut R, size: u32) -> Result<Vec<u8>, AudioError> {
+    // Header chunks are tiny; anything large is corrupt.
+    if size > 1 << 16 {
+        return Err(AudioError::DecodeFailed);
+    }
+
+    let padded = size as usize + (size & 1) as usize;
+    let mut body = vec![0u8; padded];
+    reader.read_exact(&mut body).map_err(map_io_error)?;
+    body.truncate(size as usize);
+
+    Ok(body)
+}
+
+/// Parse a `fmt ` chunk body.
+fn parse_format(body: &[u8]) -> Result<WavFormat, AudioError> {
+    if body.len() < 16 {
+        return Err(AudioError::DecodeFailed);
+    }
+
+    let mut tag = u16_le(&body[0..2]);
+    let channels = u16_le(&body[2..4]);
+    let sample_rate = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+    let block_align = u16_le(&body[12..14]);
+    let container_bits = u16_le(&body[14..16]);
+    let mut valid_bits = container_bits;
+
+    if tag == WAVE_FORMAT_EXTENSIBLE {
+        // cbSize(2) validBits(2) channelMask(4) subFormat GUID(16)
+        if body.len() < 40 {
+            return Err(AudioError::DecodeFailed);
+        }
+
+        let bits = u16_le(&body[18..20]);
+        if bits != 0 {
+            valid_bits = bits;
+        }
+
+        // The first two GUID bytes carry the classic format tag
+        tag = u16_le(&body[24..26]);
+    }
+
+    let encoding = match tag {
+        WAVE_FORMAT_PCM => Encoding::Pcm,
+        WAVE_FORMAT_IEEE_FLOAT => Encoding::Float,
+        _ => return Err(AudioError::UnsupportedFormat),
+    };
+
+    let supported = match encoding {
+        Encoding::Pcm => matches!(container_bits, 8 | 16 | 24 | 32),
+        Encoding::Float => matches!(container_bits, 32 | 64),
+    };
+
+    if !supported {
+        return Err(AudioError::UnsupportedFormat);
+    }
+
+    if channels == 0
+        || sample_rate == 0
+        || valid_bits > container_bits
+        || block_align as usize != channels as usize * (container_bits / 8) as usize
+    {
+        return Err(AudioError::DecodeFailed);
+    }
+
+    Ok(WavFormat {
+        encoding,
+        channels,
+        sample_rate,
+        block_align,
+        container_bits,
+        valid_bits,
+    })
+}
+
+impl<R: Read> PcmStream for WavStream<R> {
+    fn meta(&self) -> &AudioMetadata {
+        &self.meta
+    }
+
+    fn exact_length(&self) -> bool {
+        self.sized
+    }
+
+    /// A `data` chunk cut short by the end of the file is an error.
+    fn read_block(&mut self, block: &mut Vec<f32>) -> Result<bool, AudioError> {
+        let frame_bytes = self.format.block_align as usize;
+        let sample_bytes = (self.format.container_bits / 8) as usize;
+
+        // Fill the raw buffer with as many bytes as available
+        while self.filled < self.raw.len() {
+            match self.data.read(&mut self.raw[self.filled..]) {
+                Ok(0) if self.sized && self.data.limit() > 0 => {
+                    return Err(AudioError::DecodeFailed)
+                }
+                Ok(0) => break,
+                Ok(n) => self.filled += n,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return Err(AudioError::IoError),
+            }
+        }
+
+        let complete = self.filled - self.filled % frame_bytes;
+
+        block.clear();
+        block.extend(
+            self.raw[..complete]
+                .chunks_exact(sample_bytes)
+                .map(|raw| convert_sample(raw, self.format.encoding)),
+        );
+
+        self.raw.copy_within(complete..self.filled, 0);
+        self.filled -= complete;
+
+        Ok(complete > 0)
+    }
+}
+
+/// Convert one little-endian sample slot to f32 (CLAMPED).
+#[inline]
+fn convert_sample(raw: &[u8], encoding: Encoding) -> f32 {
+    let v = match (encoding, raw.len()) {
+        (Encoding::Pcm, 1) => (raw[0] as f32 - 128.0) / 128.0,
+        (Encoding::Pcm, 2) => i16::from_le_bytes([raw[0], raw[1]]) as f32 / 32768.0,
+        (Encoding::Pcm, 3) => {
+            // Sign-extend 24-bit via the top of an i32
+            let v = i32::from_le_bytes([0, raw[0], raw[1], raw[2]]) >> 8;
+            v as f32 / 8_388_608.0
+        }
+        (Encoding::Pcm, 4) => {
+            let v = i32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]);
+            (v as f64 / 2_147_483_648.0) as f32
+        }
+        (Encoding::Float, 4) => f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]),
+        (Encoding::Float, 8) => f64::from_le_bytes([
+            raw[0], raw[1], raw[2], raw[3], raw[4], raw[5], raw[6], raw[7],
+        ]) as f32,
+        _ => 0.0,
+    };
+
+    // Critical safety:
+    if v.is_finite() {
+        v.clamp(-1.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+#[inline]
+fn u16_le(b: &[u8]) -> u16 {
+    u16::from_le_bytes([b[0], b[1]])
+}
+
+#[inline]
+fn u64_le(b: &[u8]) -> u64 {
+    u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+}
+
+/// Map I/O errors; a premature end of file means a broken header.
+fn map_io_error(err: std::io::Error) -> AudioError {
+    match err.kind() {
+        ErrorKind::UnexpectedEof => AudioError::DecodeFailed,
+        _ => AudioError::IoError,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Classic 16-byte `fmt ` body.
+    fn fmt(tag: u16, channels: u16, rate: u32, bits: u16) -> Vec<u8> {
+        let align = channels * bits / 8;
+        let mut body = Vec::new();
+        body.extend_from_slice(&tag.to_le_bytes());
+        body.extend_from_slice(&channels.to_le_bytes());
+        body.extend_from_slice(&rate.to_le_bytes());
+        body.extend_from_slice(&(rate * align as u32).to_le_bytes());
+        body.extend_from_slice(&align.to_le_bytes());
+        body.extend_from_slice(&bits.to_le_bytes());
+        body
+    }
+
+    /// 40-byte WAVE_FORMAT_EXTENSIBLE body.
+    fn fmt_extensible(tag: u16, channels: u16, rate: u32, bits: u16, valid: u16) -> Vec<u8> {
+        let mut body = fmt(WAVE_FORMAT_EXTENSIBLE, channels, rate, bits);
+        body.extend_from_slice(&22u16.to_le_bytes());
+        body.extend_from_slice(&valid.to_le_bytes());
+        body.extend_from_slice(&3u32.to_le_bytes());
+        body.extend_from_slice(&tag.to_le_bytes());
+        body.extend_from_slice(&[0; 14]);
+        body
+    }
+
+    fn chunk(out: &mut Vec<u8>, id: &[u8; 4], size: u32, body: &[u8]) {
+        out.extend_from_slice(id);
+        out.extend_from_slice(&size.to_le_bytes());
+        out.extend_from_slice(body);
+    }
+
+    fn wav(fmt: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        chunk(&mut out, b"RIFF", (4 + 16 + fmt.len() + data.len()) as u32, b"WAVE");
+        chunk(&mut out, b"fmt ", fmt.len() as u32, fmt);
+        chunk(&mut out, b"data", data.len() as u32, data);
+        out
+    }
+
+    fn open(bytes: Vec<u8>) -> Result<WavStream<Cursor<Vec<u8>>>, AudioError> {
+        WavStream::new(Cursor::new(bytes))
+    }
+
+    fn decode(bytes: Vec<u8>) -> Result<AudioBuffer, AudioError> {
+        read_to_end(&mut open(bytes)?)
+    }
+
+    #[test]
+    fn decodes_every_sample_format() {
+        let bytes = |values: &[&[u8]]| values.concat();
+        let cases: [(u16, u16, Vec<u8>); 6] = [
+            (WAVE_FORMAT_PCM, 8, vec![0, 128, 192]),
+            (
+                WAVE_FORMAT_PCM,
+                16,
+                bytes(&[&i16::MIN.to_le_bytes(), &0i16.to_le_bytes(), &16384i16.to_le_bytes()]),
+            ),
+            (WAVE_FORMAT_PCM, 24, vec![0x00, 0x00, 0x80, 0, 0, 0, 0x00, 0x00, 0x40]),
+            (
+                WAVE_FORMAT_PCM,
+                32,
+                bytes(&[&i32::MIN.to_le_bytes(), &0i32.to_le_bytes(), &(1i32 << 30).to_le_bytes()]),
+            ),
+            (
+                WAVE_FORMAT_IEEE_FLOAT,
+                32,
+                bytes(&[&(-1.0f32).to_le_bytes(), &0.0f32.to_le_bytes(), &0.5f32.to_le_bytes()]),
+            ),
+            (
+                WAVE_FORMAT_IEEE_FLOAT,
+                64,
+                bytes(&[&(-1.0f64).to_le_bytes(), &0.0f64.to_le_bytes(), &0.5f64.to_le_bytes()]),
+            ),
+        ];
+
+        for (tag, bits, data) in cases {
+            let audio = decode(wav(&fmt(tag, 1, 8000, bits), &data)).unwrap();
+
+            assert_eq!(audio.samples, [-1.0, 0.0, 0.5], "{} bit, tag {}", bits, tag);
+            assert_eq!(audio.meta.total_samples, 3);
+            assert_eq!(audio.meta.bit_depth, Some(bits));
+        }
+    }
+
+    #[test]
+    fn extensible_reports_valid_bits_and_decodes_the_container() {
+        // Stereo, 20 significant bits in 24-bit slots
+        let data = [0x00, 0x00, 0x40, 0x00, 0x00, 0xc0];
+        let audio = decode(wav(&fmt_extensible(WAVE_FORMAT_PCM, 2, 48000, 24, 20), &data)).unwrap();
+
+        assert_eq!(audio.samples, [0.5, -0.5]);
+        assert_eq!((audio.meta.channels, audio.meta.total_samples), (2, 1));
+        assert_eq!(audio.meta.bit_depth, Some(20));
+    }
+
+    #[test]
+    fn rf64_data_size_comes_from_ds64() {
+        let samples: Vec<u8> = [1000i16, 2000, 3000].iter().flat_map(|v| v.to_le_bytes()).collect();
+
+        let mut ds64 = Vec::new();
+        ds64.extend_from_slice(&0u64.to_le_bytes());
+        ds64.extend_from_slice(&(samples.len() as u64).to_le_bytes());
+        ds64.extend_from_slice(&3u64.to_le_bytes());
+        ds64.extend_from_slice(&0u32.to_le_bytes());
+
+        let format = fmt(WAVE_FORMAT_PCM, 1, 8000, 16);
+        let mut bytes = Vec::new();
+        chunk(&mut bytes, b"RF64", RF64_SIZE_MARKER, b"WAVE");
+        chunk(&mut bytes, b"ds64", ds64.len() as u32, &ds64);
+        chunk(&mut bytes, b"fmt ", format.len() as u32, &format);
+        chunk(&mut bytes, b"data", RF64_SIZE_MARKER, &samples);
+        // Trailing chunk must not be read as audio
+        chunk(&mut bytes, b"LIST", 4, b"INFO");
+
+        let audio = decode(bytes).unwrap();
+        assert_eq!(audio.meta.total_samples, 3);
+        assert_eq!(audio.samples, [1000.0 / 32768.0, 2000.0 / 32768.0, 3000.0 / 32768.0]);
+    }
+
+    #[test]
+    fn crop_seeks_to_the_range() {
+        // 10 Hz mono, sample i = i / 32768
+        let data: Vec<u8> = (0..20i16).flat_map(|v| v.to_le_bytes()).collect();
+        let mut stream = open(wav(&fmt(WAVE_FORMAT_PCM, 1, 10, 16), &data)).unwrap();
+
+        stream
+            .crop(TimeRange {
+                start: 0.5,
+                end: Some(1.2),
+            })
+            .unwrap();
+        let audio = read_to_end(&mut stream).unwrap();
+
+        let expected: Vec<f32> = (5..12).map(|v| v as f32 / 32768.0).collect();
+        assert_eq!(audio.samples, expected);
+        assert_eq!(audio.meta.total_samples, 7);
+    }
+
+    #[test]
+    fn truncated_files_are_errors() {
+        let data: Vec<u8> = (0..8i16).flat_map(|v| v.to_le_bytes()).collect();
+        let bytes = wav(&fmt(WAVE_FORMAT_PCM, 2, 8000, 16), &data);
+
+        // Every cut, in the header or in the data chunk
+        for len in 0..bytes.len() {
+            assert!(decode(bytes[..len].to_vec()).is_err(), "cut at {}", len);
+        }
+        assert!(decode(bytes).is_ok());
+
+        // Oversized header chunk
+        let mut huge = Vec::new();
+        chunk(&mut huge, b"RIFF", 0, b"WAVE");
+        chunk(&mut huge, b"fmt ", u32::MAX - 1, &[]);
+        assert!(matches!(decode(huge), Err(AudioError::DecodeFailed)));
+    }
+}
@@ -11,12 +11,14 @@ use std::env;
 use std::path::PathBuf;
 
 use spek_core::api::generate::generate_spectrogram;
+use spek_core::api::png;
 use spek_core::api::settings::{
-    ChannelMode, RenderSettings, ScaleMode, SpekSettings, SpectrogramSettings, WindowFunction,
+    ChannelMode, DbRange, FontFamily, FontWeight, FrequencyScale, LegendSettings,
+    Normalization, PaletteSource, RenderSettings, ScaleMode, SpekSettings, SpectrogramSettings,
+    WindowFunction,
 };
 use spek_core::analysis::fft::FftAnalyzer;
 use spek_core::audio::{AudioBuffer, AudioMetadata, AudioSource};
-use spek_core::color::spek::SpekColorMapper;
 use spek_core::render::basic::BasicRenderer;
 use spek_core::legend::simple::SimpleLegendRenderer;
 
@@ -41,6 +43,8 @@ impl AudioSource for DummyAudioSource {
                 channels: 1,
                 total_samples,
                 bit_depth: None,
+                codec: None,
+                tags: Vec::new(),
             },
         })
     }
@@ -61,8 +65,7 @@ fn main() {
     // -----------------------------------------------------------------
     let audio = DummyAudioSource;
     let analyzer = FftAnalyzer::new();
-    let color_mapper = SpekColorMapper::new();
-    let renderer = BasicRenderer::new(&color_mapper);
+    let renderer = BasicRenderer::new();
     let legend = SimpleLegendRenderer::new();
 
     // -----------------------------------------------------------------
@@ -76,11 +79,28 @@ fn main() {
             channels: ChannelMode::Combined,
             min_db: -120.0,
             max_db: 0.0,
+            range: DbRange::Fixed,
             scale: ScaleMode::Log,
+            normalization: Normalization::Amplitude,
+            threads: 0,
+            freq_min: None,
+            freq_max: None,
+            time_start: None,
+            time_end: None,
+            keep_data: false,
         },
         render: RenderSettings {
             width: 1024,
             height: 512,
+            freq_scale: FrequencyScale::Linear,
+            cutoff_marker: false,
+            palette: PaletteSource::Builtin("spek".to_string()),
+            saturation: 1.0,
+        },
+        legend: LegendSettings {
+            font_size: 14,
+            font_family: FontFamily::Builtin,
+            font_weight: FontWeight::Regular,
         },
     };
 
@@ -104,23 +124,10 @@ fn main() {
     // -----------------------------------------------------------------
     // Write PNG
     // -----------------------------------------------------------------
-    if let Err(e) = write_png(&output, &result.image) {
+    let text = png::metadata(&settings, None, &result);
+
+    if let Err(e) = std::fs::write(&output, png::encode(&result.image, &text)) {
         eprintln!("Failed to write PNG: {}", e);
         std::process::exit(3);
     }
 }
-
-/// Write RGBA buffer to PNG using image crate.
-fn write_png(
-    path: &PathBuf,
-    image: &spek_core::render::ImageBuffer,
-) -> Result<(), String> {
-    let img = image::RgbaImage::from_raw(
-        image.width as u32,
-        image.height as u32,
-        image.data.clone(),
-    )
-    .ok_or("Invalid image buffer")?;
-
-    img.save(path).map_err(|e| e.to_string())
-}
@@ -1,13 +1,19 @@
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 
-use spek_core::api::generate::generate_spectrogram;
+use spek_core::api::generate::generate_spectrogram_streaming;
+use spek_core::api::{npy, png};
+use spek_core::api::result::{QualityReport, Verdict};
 use spek_core::api::settings::*;
+use spek_core::audio::StreamingAudioSource;
 use spek_core::audio::ffmpeg::FfmpegAudioSource;
+use spek_core::audio::flac::FlacAudioSource;
+use spek_core::audio::wav::WavAudioSource;
 use spek_core::analysis::fft::FftAnalyzer;
-use spek_core::render::cpu::CpuRenderer;
-use spek_core::legend::cpu::CpuLegendRenderer;
+use spek_core::color::Palette;
+use spek_core::render::basic::BasicRenderer;
+use spek_core::legend::simple::SimpleLegendRenderer;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -26,7 +32,28 @@ fn main() {
     let mut width = 1024;
     let mut height = 512;
     let mut min_db = -120.0;
+    let mut max_db = 0.0;
+    let mut range = DbRange::Fixed;
     let mut scale = ScaleMode::Log;
+    let mut normalization = Normalization::Amplitude;
+    let mut window = WindowFunction::Hann;
+    let mut threads = 0;
+    let mut channels = ChannelMode::Combined;
+    let mut freq_scale = FrequencyScale::Linear;
+    let mut freq_min = None;
+    let mut freq_max = None;
+    let mut time_start = None;
+    let mut time_end = None;
+    let mut cutoff_marker = false;
+    let mut palette = "spek".to_string();
+    let mut palette_interpolation = PaletteInterpolation::Srgb;
+    let mut palette_lut = PaletteLutSize::Entries256;
+    let mut saturation = 1.0;
+    let mut font_size = 14;
+    let mut font_family = FontFamily::Builtin;
+    let mut font_weight = FontWeight::Regular;
+    let mut print_report = false;
+    let mut export_npy = None;
 
     // ---- parse flags ----------------------------------------------------
     let mut i = 3;
@@ -37,6 +64,8 @@ fn main() {
             "--width" => width = args[i + 1].parse().unwrap(),
             "--height" => height = args[i + 1].parse().unwrap(),
             "--min-db" => min_db = args[i + 1].parse().unwrap(),
+            "--max-db" => max_db = args[i + 1].parse().unwrap(),
+            "--range" => range = parse_range(&args[i + 1]),
             "--scale" => {
                 scale = match args[i + 1].as_str() {
                     "linear" => ScaleMode::Linear,
@@ -46,33 +75,122 @@ fn main() {
                     _ => panic!("Invalid scale mode"),
                 }
             }
+            "--norm" => {
+                normalization = match args[i + 1].as_str() {
+                    "amplitude" => Normalization::Amplitude,
+                    "energy" => Normalization::Energy,
+                    _ => panic!("Invalid normalization mode"),
+                }
+            }
+            "--window" => window = parse_window(&args[i + 1]),
+            "--channels" => channels = parse_channels(&args[i + 1]),
+            "--freq-scale" => {
+                freq_scale = match args[i + 1].as_str() {
+                    "linear" => FrequencyScale::Linear,
+                    "log" => FrequencyScale::Log,
+                    "mel" => FrequencyScale::Mel,
+                    "bark" => FrequencyScale::Bark,
+                    "erb" => FrequencyScale::Erb,
+                    _ => panic!("Invalid frequency scale"),
+                }
+            }
+            "--palette" => palette = args[i + 1].clone(),
+            "--palette-interp" => {
+                palette_interpolation = match args[i + 1].as_str() {
+                    "srgb" => PaletteInterpolation::Srgb,
+                    "oklab" => PaletteInterpolation::Oklab,
+                    _ => panic!("Invalid palette interpolation"),
+                }
+            }
+            "--palette-lut" => {
+                palette_lut = match args[i + 1].as_str() {
+                    "256" => PaletteLutSize::Entries256,
+                    "4096" => PaletteLutSize::Entries4096,
+                    _ => panic!("Invalid palette LUT size"),
+                }
+            }
+            "--saturation" => saturation = args[i + 1].parse().unwrap(),
+            "--threads" => threads = args[i + 1].parse().unwrap(),
+            "--freq-min" => freq_min = Some(args[i + 1].parse().unwrap()),
+            "--freq-max" => freq_max = Some(args[i + 1].parse().unwrap()),
+            "--start" => time_start = Some(args[i + 1].parse().unwrap()),
+            "--end" => time_end = Some(args[i + 1].parse().unwrap()),
+            "--export-npy" => export_npy = Some(PathBuf::from(&args[i + 1])),
+            "--font" => font_family = FontFamily::File(PathBuf::from(&args[i + 1])),
+            "--font-size" => font_size = args[i + 1].parse().unwrap(),
+            "--font-weight" => {
+                font_weight = match args[i + 1].as_str() {
+                    "regular" => FontWeight::Regular,
+                    "bold" => FontWeight::Bold,
+                    _ => panic!("Invalid font weight"),
+                }
+            }
+            // Switches without a value
+            "--cutoff-marker" | "--report" => {
+                match args[i].as_str() {
+                    "--cutoff-marker" => cutoff_marker = true,
+                    _ => print_report = true,
+                }
+                i += 1;
+                continue;
+            }
             _ => {}
         }
         i += 2;
     }
 
     // ---- construct settings --------------------------------------------
+    // Built-in names win, anything else is a stops file
+    let palette = match Palette::from_name(&palette) {
+        Some(_) => PaletteSource::Builtin(palette),
+        None => PaletteSource::File {
+            path: PathBuf::from(palette),
+            interpolation: palette_interpolation,
+            lut_size: palette_lut,
+        },
+    };
+
     let settings = SpekSettings {
         spectrogram: SpectrogramSettings {
             fft_size,
             hop_size,
-            window: WindowFunction::Hann,
-            channels: ChannelMode::Combined,
+            window,
+            channels,
             min_db,
-            max_db: 0.0,
+            max_db,
+            range,
             scale,
+            normalization,
+            threads,
+            freq_min,
+            freq_max,
+            time_start,
+            time_end,
+            keep_data: export_npy.is_some(),
+        },
+        render: RenderSettings {
+            width,
+            height,
+            freq_scale,
+            cutoff_marker,
+            palette,
+            saturation,
+        },
+        legend: LegendSettings {
+            font_size,
+            font_family,
+            font_weight,
         },
-        render: RenderSettings { width, height },
     };
 
     // ---- pipeline wiring ------------------------------------------------
-    let source = FfmpegAudioSource::new(input);
+    let source = open_source(input.clone());
     let analyzer = FftAnalyzer::new();
-    let renderer = CpuRenderer::new();
-    let legend = CpuLegendRenderer::new();
+    let renderer = BasicRenderer::new();
+    let legend = SimpleLegendRenderer::new();
 
-    let result = generate_spectrogram(
-        &source,
+    let result = generate_spectrogram_streaming(
+        source.as_ref(),
         &analyzer,
         &renderer,
         &legend,
@@ -80,9 +198,146 @@ fn main() {
     )
     .expect("Spectrogram generation failed");
 
+    // ---- quality report -------------------------------------------------
+    if print_report {
+        print_quality_report(&result.report);
+    }
+
     // ---- write PNG ------------------------------------------------------
-    std::fs::write(&output, result.image.data)
+    let file_name = input.file_name().map(|n| n.to_string_lossy().into_owned());
+    let text = png::metadata(&settings, file_name.as_deref(), &result);
+
+    std::fs::write(&output, png::encode(&result.image, &text))
         .expect("Failed to write output image");
+
+    // ---- numeric export -------------------------------------------------
+    if let (Some(path), Some(data)) = (export_npy, &result.data) {
+        write_npy(&path, &npy::arrays(data));
+    }
+}
+
+/// `.npz` path: one archive. Any other path: one `.npy` per array,
+/// named `<stem>_<array>.npy`.
+fn write_npy(path: &Path, arrays: &[(String, Vec<u8>)]) {
+    if path.extension().and_then(|e| e.to_str()) == Some("npz") {
+        std::fs::write(path, npy::npz(arrays)).expect("Failed to write NumPy export");
+        return;
+    }
+
+    let stem = path.with_extension("");
+    for (name, data) in arrays {
+        let file = format!("{}_{}.npy", stem.display(), name);
+        std::fs::write(file, data).expect("Failed to write NumPy export");
+    }
+}
+
+/// Parse `name` or `name:param`, e.g. `kaiser:8.6`.
+fn parse_window(arg: &str) -> WindowFunction {
+    let (name, param) = match arg.split_once(':') {
+        Some((name, p)) => (name, Some(p.parse::<f32>().expect("Invalid window parameter"))),
+        None => (arg, None),
+    };
+
+    match name {
+        "rect" | "rectangular" => WindowFunction::Rectangular,
+        "hann" => WindowFunction::Hann,
+        "hamming" => WindowFunction::Hamming,
+        "blackman" => WindowFunction::Blackman,
+        "nuttall" => WindowFunction::Nuttall,
+        "blackman-harris" => WindowFunction::BlackmanHarris,
+        "flattop" => WindowFunction::FlatTop,
+        "kaiser" => WindowFunction::Kaiser(param.unwrap_or(8.6)),
+        "gaussian" => WindowFunction::Gaussian(param.unwrap_or(0.4)),
+        "tukey" => WindowFunction::Tukey(param.unwrap_or(0.5)),
+        _ => panic!("Invalid window function"),
+    }
+}
+
+/// Parse `combined`, `split`, `midside`, `select:N`
+/// or `matrix:w,w;w,w` (one `;`-separated row per panel).
+fn parse_channels(arg: &str) -> ChannelMode {
+    let (name, param) = match arg.split_once(':') {
+        Some((name, p)) => (name, Some(p)),
+        None => (arg, None),
+    };
+
+    match (name, param) {
+        ("combined", None) => ChannelMode::Combined,
+        ("split", None) => ChannelMode::Split,
+        ("midside", None) => ChannelMode::MidSide,
+        ("select", Some(ch)) => ChannelMode::SelectChannel(ch.parse().expect("Invalid channel")),
+        ("matrix", Some(rows)) => ChannelMode::Matrix(
+            rows.split(';')
+                .enumerate()
+                .map(|(i, row)| DownmixRow {
+                    label: Some(format!("Mix {}", i + 1)),
+                    weights: row
+                        .split(',')
+                        .map(|w| w.trim().parse().expect("Invalid matrix weight"))
+                        .collect(),
+                })
+                .collect(),
+        ),
+        _ => panic!("Invalid channel mode"),
+    }
+}
+
+/// Parse `fixed`, `peak` or `percentile[:low:high]`.
+fn parse_range(arg: &str) -> DbRange {
+    let mut parts = arg.split(':');
+
+    match parts.next() {
+        Some("fixed") => DbRange::Fixed,
+        Some("peak") => DbRange::Peak,
+        Some("percentile") => {
+            let mut next = |default: f32| {
+                parts
+                    .next()
+                    .map(|p| p.parse::<f32>().expect("Invalid percentile"))
+                    .unwrap_or(default)
+            };
+            let low = next(1.0);
+            let high = next(99.9);
+            DbRange::Percentile { low, high }
+        }
+        _ => panic!("Invalid range mode"),
+    }
+}
+
+/// One verdict line plus the detected cutoff, if any.
+fn print_quality_report(report: &QualityReport) {
+    let verdict = match &report.verdict {
+        Verdict::Inconclusive => "inconclusive".to_string(),
+        Verdict::FullBandwidth => "full bandwidth".to_string(),
+        Verdict::LossyTranscode { shelf_hz, encoder } => {
+            format!("lossy transcode ({} shelf at {:.0} Hz)", encoder, shelf_hz)
+        }
+        Verdict::Upsampled { source_rate } => format!("upsampled from {} Hz", source_rate),
+        Verdict::Lowpassed { cutoff_hz } => format!("lowpassed at {:.0} Hz", cutoff_hz),
+    };
+
+    println!("verdict:    {}", verdict);
+    println!("confidence: {:.0} %", report.confidence * 100.0);
+
+    if let Some(cutoff) = report.cutoff_hz {
+        println!("cutoff:     {:.0} Hz", cutoff);
+    }
+}
+
+/// Pick a native decoder by file extension, ffmpeg otherwise.
+fn open_source(input: PathBuf) -> Box<dyn StreamingAudioSource> {
+    let ext = input
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+
+    match ext.as_deref() {
+        Some("wav") | Some("wave") | Some("rf64") | Some("bw64") => {
+            Box::new(WavAudioSource::new(input))
+        }
+        Some("flac") => Box::new(FlacAudioSource::new(input)),
+        _ => Box::new(FfmpegAudioSource::new(input)),
+    }
 }
 
 fn print_help() {
@@ -90,12 +345,43 @@ fn print_help() {
         "Usage: spek-core <input_audio> <output_png> [options]
 
 Options:
-  --width <px>        Output width (default: 1024)
-  --height <px>       Output height (default: 512)
+  --width <px>        Spectrogram width, legend excluded (default: 1024)
+  --height <px>       Spectrogram height, legend excluded (default: 512)
   --fft <size>        FFT size (default: 2048)
   --hop <size>        Hop size (default: 512)
   --min-db <value>    Min dBFS (default: -120)
+  --max-db <value>    Max dBFS (default: 0)
+  --range <mode>      fixed|peak|percentile[:low:high]
+                      (default: fixed, percentile 1:99.9)
   --scale <mode>      linear|sqrt|cbrt|log
+  --norm <mode>       amplitude|energy (default: amplitude)
+  --window <name>     rect|hann|hamming|blackman|nuttall|blackman-harris|
+                      flattop|kaiser[:beta]|gaussian[:sigma]|tukey[:alpha]
+                      (default: hann)
+  --channels <mode>   combined|split|midside|select:<n>|matrix:<w,w;w,w>
+                      (default: combined)
+  --freq-scale <s>    linear|log|mel|bark|erb (default: linear)
+  --palette <name>    spek|sox|audacity|viridis|magma|inferno|plasma|
+                      cividis|grayscale|grayscale-inverted, or a stops file
+                      (.txt / .json / GIMP .ggr) (default: spek)
+  --palette-interp <i> srgb|oklab, stops files only (default: srgb)
+  --palette-lut <n>   256|4096, stops files only (default: 256)
+  --saturation <f>    Color saturation, 0 = grayscale (default: 1)
+  --threads <n>       Analysis threads, 0 = all cores (default: 0)
+  --freq-min <hz>     Lowest frequency shown (default: 0)
+  --freq-max <hz>     Highest frequency shown (default: Nyquist)
+  --start <sec>       Start of the analyzed excerpt (default: file start)
+  --end <sec>         End of the analyzed excerpt (default: file end)
+  --font <file>       TTF / OTF legend font (build feature `truetype`)
+                      (default: built-in bitmap font)
+  --font-size <px>    Legend line height (default: 14)
+  --font-weight <w>   regular|bold (default: regular)
+  --report            Print the transcode / upsampling verdict
+  --cutoff-marker     Mark a detected lowpass cutoff on the frequency axis
+  --export-npy <path> Also write the numeric spectrogram (float32 dBFS and
+                      intensities, axes) as NumPy arrays: one .npz archive, or
+                      <stem>_<array>.npy files for any other extension;
+                      one time bin per FFT frame, not per plot column
   --help"
     );
 }
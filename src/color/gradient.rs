@@ -0,0 +1,48 @@
+//! Piecewise-linear gradient palettes.
+//!
+//! A gradient is a list of color stops on the intensity axis.
+//! Colors between two stops are interpolated linearly in sRGB,
+//! in f64, rounded once: the output is identical on every platform.
+
+use crate::color::{clamp_intensity, ColorMapper};
+
+/// Palette defined by sRGB color stops.
+///
+/// Stops are (position 0.0..=1.0, RGB8), sorted by position,
+/// the first at 0.0 and the last at 1.0.
+#[derive(Debug, Copy, Clone)]
+pub struct GradientColorMapper {
+    stops: &'static [(f64, [u8; 3])],
+}
+
+impl GradientColorMapper {
+    pub const fn new(stops: &'static [(f64, [u8; 3])]) -> Self {
+        Self { stops }
+    }
+}
+
+impl ColorMapper for GradientColorMapper {
+    fn map(&self, intensity: f32) -> [u8; 4] {
+        let v = clamp_intensity(intensity);
+
+        // First stop at or above v (the last stop sits at 1.0)
+        let upper = self
+            .stops
+            .iter()
+            .position(|&(pos, _)| pos >= v)
+            .unwrap_or(self.stops.len() - 1);
+
+        if upper == 0 {
+            let [r, g, b] = self.stops[0].1;
+            return [r, g, b, 255];
+        }
+
+        let (p0, c0) = self.stops[upper - 1];
+        let (p1, c1) = self.stops[upper];
+        let t = (v - p0) / (p1 - p0);
+
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t + 0.5) as u8;
+
+        [lerp(c0[0], c1[0]), lerp(c0[1], c1[1]), lerp(c0[2], c1[2]), 255]
+    }
+}
@@ -0,0 +1,32 @@
+//! Grayscale palettes.
+//!
+//! Plain luminance ramps, for print and monochrome displays.
+
+use crate::color::{clamp_intensity, ColorMapper};
+
+/// Black (silence) -> white (full scale), or inverted.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct GrayColorMapper {
+    inverted: bool,
+}
+
+impl GrayColorMapper {
+    pub fn new() -> Self {
+        Self { inverted: false }
+    }
+
+    /// White (silence) -> black (full scale), e.g. for paper.
+    pub fn inverted() -> Self {
+        Self { inverted: true }
+    }
+}
+
+impl ColorMapper for GrayColorMapper {
+    fn map(&self, intensity: f32) -> [u8; 4] {
+        let v = clamp_intensity(intensity);
+        let v = if self.inverted { 1.0 - v } else { v };
+
+        let level = (v * 255.0 + 0.5) as u8;
+        [level, level, level, 255]
+    }
+}
@@ -0,0 +1,43 @@
+//! Intensity-to-color mapping for spek-core.
+//!
+//! This module is a pure transfer function:
+//! number -> color
+//!
+//! It knows NO time, NO frequency, NO pixel positions and NO text.
+
+pub mod gradient;
+pub mod gray;
+pub mod palette;
+pub mod saturation;
+pub mod sox;
+pub mod spek;
+pub mod stops;
+pub mod user;
+
+pub use palette::Palette;
+pub use saturation::SaturatedColorMapper;
+pub use user::{Interpolation, LutSize, PaletteError, Stop, UserPalette};
+
+/// Color mapper interface.
+///
+/// Maps a normalized intensity to an opaque RGBA color.
+///
+/// Implementations MUST:
+/// - accept any `f32` (out-of-range and non-finite values are clamped)
+/// - be deterministic and platform-independent
+pub trait ColorMapper {
+    /// Map intensity in 0.0..1.0 to RGBA8.
+    fn map(&self, intensity: f32) -> [u8; 4];
+}
+
+/// Clamp an intensity into 0.0..1.0.
+///
+/// Non-finite values are treated as silence.
+#[inline]
+pub(crate) fn clamp_intensity(v: f32) -> f64 {
+    if v.is_finite() {
+        (v as f64).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,192 @@
+//! Built-in palette registry.
+//!
+//! Every palette has a stable lowercase name,
+//! used by `RenderSettings` and the CLI.
+//!
+//! Viridis, Magma, Inferno, Plasma and Cividis are perceptually uniform
+//! and readable in grayscale print and with color vision deficiencies.
+//! They are reproduced from ten evenly spaced samples of the reference maps
+//! (as published by viridisLite), interpolated linearly in sRGB.
+
+use crate::color::gradient::GradientColorMapper;
+use crate::color::gray::GrayColorMapper;
+use crate::color::sox::SoxColorMapper;
+use crate::color::spek::SpekColorMapper;
+use crate::color::ColorMapper;
+
+/// Built-in palettes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Palette {
+    /// Spek default (reference look)
+    Spek,
+
+    /// SoX `spectrogram` default
+    Sox,
+
+    /// Classic Audacity spectrogram colors
+    Audacity,
+
+    Viridis,
+    Magma,
+    Inferno,
+    Plasma,
+    Cividis,
+
+    /// Black -> white
+    Grayscale,
+
+    /// White -> black
+    InvertedGrayscale,
+}
+
+impl Palette {
+    /// All built-in palettes, in documentation order.
+    pub const ALL: [Palette; 10] = [
+        Palette::Spek,
+        Palette::Sox,
+        Palette::Audacity,
+        Palette::Viridis,
+        Palette::Magma,
+        Palette::Inferno,
+        Palette::Plasma,
+        Palette::Cividis,
+        Palette::Grayscale,
+        Palette::InvertedGrayscale,
+    ];
+
+    /// Stable lowercase name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Palette::Spek => "spek",
+            Palette::Sox => "sox",
+            Palette::Audacity => "audacity",
+            Palette::Viridis => "viridis",
+            Palette::Magma => "magma",
+            Palette::Inferno => "inferno",
+            Palette::Plasma => "plasma",
+            Palette::Cividis => "cividis",
+            Palette::Grayscale => "grayscale",
+            Palette::InvertedGrayscale => "grayscale-inverted",
+        }
+    }
+
+    /// Look up a palette by name (ASCII case-insensitive).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Color mapper drawing this palette.
+    pub fn mapper(self) -> Box<dyn ColorMapper> {
+        match self {
+            Palette::Spek => Box::new(SpekColorMapper::new()),
+            Palette::Sox => Box::new(SoxColorMapper::new()),
+            Palette::Audacity => Box::new(GradientColorMapper::new(&AUDACITY)),
+            Palette::Viridis => Box::new(GradientColorMapper::new(&VIRIDIS)),
+            Palette::Magma => Box::new(GradientColorMapper::new(&MAGMA)),
+            Palette::Inferno => Box::new(GradientColorMapper::new(&INFERNO)),
+            Palette::Plasma => Box::new(GradientColorMapper::new(&PLASMA)),
+            Palette::Cividis => Box::new(GradientColorMapper::new(&CIVIDIS)),
+            Palette::Grayscale => Box::new(GrayColorMapper::new()),
+            Palette::InvertedGrayscale => Box::new(GrayColorMapper::inverted()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------
+// Stop tables
+// -------------------------------------------------------------------------
+
+/// Audacity "classic" gradient: gray -> light blue -> violet -> red -> white
+const AUDACITY: [(f64, [u8; 3]); 5] = [
+    (0.00, [0xbf, 0xbf, 0xbf]),
+    (0.25, [0x4d, 0x99, 0xff]),
+    (0.50, [0xe6, 0x1a, 0xe6]),
+    (0.75, [0xff, 0x00, 0x00]),
+    (1.00, [0xff, 0xff, 0xff]),
+];
+
+const VIRIDIS: [(f64, [u8; 3]); 10] = [
+    (0.0, [0x44, 0x01, 0x54]),
+    (1.0 / 9.0, [0x48, 0x28, 0x78]),
+    (2.0 / 9.0, [0x3e, 0x4a, 0x89]),
+    (3.0 / 9.0, [0x31, 0x68, 0x8e]),
+    (4.0 / 9.0, [0x26, 0x82, 0x8e]),
+    (5.0 / 9.0, [0x1f, 0x9e, 0x89]),
+    (6.0 / 9.0, [0x35, 0xb7, 0x79]),
+    (7.0 / 9.0, [0x6d, 0xcd, 0x59]),
+    (8.0 / 9.0, [0xb4, 0xde, 0x2c]),
+    (1.0, [0xfd, 0xe7, 0x25]),
+];
+
+const MAGMA: [(f64, [u8; 3]); 10] = [
+    (0.0, [0x00, 0x00, 0x04]),
+    (1.0 / 9.0, [0x18, 0x0f, 0x3e]),
+    (2.0 / 9.0, [0x45, 0x10, 0x77]),
+    (3.0 / 9.0, [0x72, 0x1f, 0x81]),
+    (4.0 / 9.0, [0x9f, 0x2f, 0x7f]),
+    (5.0 / 9.0, [0xcd, 0x40, 0x71]),
+    (6.0 / 9.0, [0xf1, 0x60, 0x5d]),
+    (7.0 / 9.0, [0xfd, 0x95, 0x67]),
+    (8.0 / 9.0, [0xfe, 0xc9, 0x8d]),
+    (1.0, [0xfc, 0xfd, 0xbf]),
+];
+
+const INFERNO: [(f64, [u8; 3]); 10] = [
+    (0.0, [0x00, 0x00, 0x04]),
+    (1.0 / 9.0, [0x1b, 0x0c, 0x42]),
+    (2.0 / 9.0, [0x4b, 0x0c, 0x6b]),
+    (3.0 / 9.0, [0x78, 0x1c, 0x6d]),
+    (4.0 / 9.0, [0xa5, 0x2c, 0x60]),
+    (5.0 / 9.0, [0xcf, 0x44, 0x46]),
+    (6.0 / 9.0, [0xed, 0x69, 0x25]),
+    (7.0 / 9.0, [0xfb, 0x9a, 0x06]),
+    (8.0 / 9.0, [0xf7, 0xd1, 0x3d]),
+    (1.0, [0xfc, 0xff, 0xa4]),
+];
+
+const PLASMA: [(f64, [u8; 3]); 10] = [
+    (0.0, [0x0d, 0x08, 0x87]),
+    (1.0 / 9.0, [0x47, 0x03, 0x9f]),
+    (2.0 / 9.0, [0x73, 0x01, 0xa8]),
+    (3.0 / 9.0, [0x9c, 0x17, 0x9e]),
+    (4.0 / 9.0, [0xbd, 0x37, 0x86]),
+    (5.0 / 9.0, [0xd8, 0x57, 0x6b]),
+    (6.0 / 9.0, [0xed, 0x79, 0x53]),
+    (7.0 / 9.0, [0xfa, 0x9e, 0x3b]),
+    (8.0 / 9.0, [0xfd, 0xc9, 0x26]),
+    (1.0, [0xf0, 0xf9, 0x21]),
+];
+
+const CIVIDIS: [(f64, [u8; 3]); 10] = [
+    (0.0, [0x00, 0x20, 0x4d]),
+    (1.0 / 9.0, [0x00, 0x33, 0x6f]),
+    (2.0 / 9.0, [0x39, 0x48, 0x6b]),
+    (3.0 / 9.0, [0x57, 0x5c, 0x6d]),
+    (4.0 / 9.0, [0x70, 0x71, 0x73]),
+    (5.0 / 9.0, [0x8a, 0x87, 0x79]),
+    (6.0 / 9.0, [0xa6, 0x9d, 0x75]),
+    (7.0 / 9.0, [0xc4, 0xb5, 0x6c]),
+    (8.0 / 9.0, [0xe4, 0xcf, 0x5b]),
+    (1.0, [0xff, 0xea, 0x46]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip_and_ends_match_tables() {
+        for palette in Palette::ALL {
+            assert_eq!(Palette::from_name(palette.name()), Some(palette));
+        }
+        assert_eq!(Palette::from_name("Viridis"), Some(Palette::Viridis));
+        assert_eq!(Palette::from_name("jet"), None);
+
+        let viridis = Palette::Viridis.mapper();
+        assert_eq!(viridis.map(0.0), [0x44, 0x01, 0x54, 255]);
+        assert_eq!(viridis.map(1.0), [0xfd, 0xe7, 0x25, 255]);
+        assert_eq!(Palette::InvertedGrayscale.mapper().map(0.0), [255, 255, 255, 255]);
+    }
+}
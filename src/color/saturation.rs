@@ -0,0 +1,73 @@
+//! Saturation control on top of any palette.
+//!
+//! Every color is blended with its own luma (Rec. 709 weights):
+//! 0.0 = grayscale, 1.0 = palette unchanged, above 1.0 = more vivid.
+//!
+//! Arithmetic is done in f64 and rounded once,
+//! so the output is identical on every platform.
+
+use crate::color::ColorMapper;
+
+/// Palette with adjusted saturation.
+///
+/// Spectrogram and legend gradient both draw through this mapper,
+/// so a level has the same color in both.
+pub struct SaturatedColorMapper {
+    palette: Box<dyn ColorMapper>,
+    saturation: f64,
+}
+
+impl SaturatedColorMapper {
+    /// Wrap `palette`; `saturation` must be finite and >= 0.0.
+    pub fn new(palette: Box<dyn ColorMapper>, saturation: f32) -> Self {
+        Self {
+            palette,
+            saturation: saturation as f64,
+        }
+    }
+}
+
+impl ColorMapper for SaturatedColorMapper {
+    fn map(&self, intensity: f32) -> [u8; 4] {
+        let [r, g, b, a] = self.palette.map(intensity);
+
+        let luma = 0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64;
+        let adjust = |c: u8| (luma + (c as f64 - luma) * self.saturation).clamp(0.0, 255.0) + 0.5;
+
+        [adjust(r) as u8, adjust(g) as u8, adjust(b) as u8, a]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Palette;
+
+    struct Red;
+
+    impl ColorMapper for Red {
+        fn map(&self, _: f32) -> [u8; 4] {
+            [255, 0, 0, 255]
+        }
+    }
+
+    #[test]
+    fn saturation_blends_towards_luma() {
+        let saturated = |s: f32| SaturatedColorMapper::new(Palette::Viridis.mapper(), s);
+        let viridis = Palette::Viridis.mapper();
+
+        for i in 0..=20 {
+            let v = i as f32 / 20.0;
+            let [r, g, b, a] = saturated(0.0).map(v);
+
+            assert_eq!(saturated(1.0).map(v), viridis.map(v));
+            assert!(r == g && g == b && a == 255);
+        }
+
+        // Pure red: luma 54.213, doubled distance clamps to the ends
+        let red = SaturatedColorMapper::new(Box::new(Red), 2.0);
+        assert_eq!(red.map(1.0), [255, 0, 0, 255]);
+        let red = SaturatedColorMapper::new(Box::new(Red), 0.5);
+        assert_eq!(red.map(1.0), [155, 27, 27, 255]);
+    }
+}
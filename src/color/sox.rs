@@ -0,0 +1,57 @@
+//! SoX-style palette.
+//!
+//! Port of the default palette of `sox ... spectrogram`:
+//! black -> dark blue -> purple -> red -> orange -> yellow -> white
+//!
+//! The de-facto standard look in audio forensics.
+
+use std::f64::consts::PI;
+
+use crate::color::{clamp_intensity, ColorMapper};
+
+/// SoX spectrogram palette.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SoxColorMapper;
+
+impl SoxColorMapper {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ColorMapper for SoxColorMapper {
+    fn map(&self, intensity: f32) -> [u8; 4] {
+        let x = clamp_intensity(intensity);
+
+        let r = if x < 0.13 {
+            0.0
+        } else if x < 0.73 {
+            ((x - 0.13) / 0.60 * PI / 2.0).sin()
+        } else {
+            1.0
+        };
+
+        let g = if x < 0.60 {
+            0.0
+        } else if x < 0.91 {
+            ((x - 0.60) / 0.31 * PI / 2.0).sin()
+        } else {
+            1.0
+        };
+
+        let b = if x < 0.60 {
+            0.5 * (x / 0.60 * PI).sin()
+        } else if x < 0.78 {
+            0.0
+        } else {
+            (x - 0.78) / 0.22
+        };
+
+        [
+            (r * 255.0 + 0.5) as u8,
+            (g * 255.0 + 0.5) as u8,
+            (b * 255.0 + 0.5) as u8,
+            255,
+        ]
+    }
+}
@@ -0,0 +1,51 @@
+//! Reference Spek palette.
+//!
+//! Port of the default "spectrum" palette of Spek
+//! (a modified version of Dan Bruton's wavelength-to-RGB algorithm):
+//! black -> violet -> blue -> cyan -> green -> yellow -> red
+//!
+//! Arithmetic is done in f64 with the same rounding as Spek,
+//! so the output is bit-identical on every platform.
+
+use crate::color::{clamp_intensity, ColorMapper};
+
+/// Spek's default spectrogram palette.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SpekColorMapper;
+
+impl SpekColorMapper {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ColorMapper for SpekColorMapper {
+    fn map(&self, intensity: f32) -> [u8; 4] {
+        let level = clamp_intensity(intensity) * 0.6625;
+
+        let (r, g, b) = if level < 0.15 {
+            ((0.15 - level) / (0.15 + 0.075), 0.0, 1.0)
+        } else if level < 0.275 {
+            (0.0, (level - 0.15) / (0.275 - 0.15), 1.0)
+        } else if level < 0.325 {
+            (0.0, 1.0, (0.325 - level) / (0.325 - 0.275))
+        } else if level < 0.5 {
+            ((level - 0.325) / (0.5 - 0.325), 1.0, 0.0)
+        } else if level < 0.6625 {
+            (1.0, (0.6625 - level) / (0.6625 - 0.5), 0.0)
+        } else {
+            // Top of the scale (intensity == 1.0)
+            (1.0, 0.0, 0.0)
+        };
+
+        // Intensity correction: fade in from black
+        let cf = if level < 0.1 { level / 0.1 } else { 1.0 } * 255.0;
+
+        [
+            (r * cf + 0.5) as u8,
+            (g * cf + 0.5) as u8,
+            (b * cf + 0.5) as u8,
+            255,
+        ]
+    }
+}
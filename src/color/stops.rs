@@ -0,0 +1,547 @@
+//! Palette stop file formats.
+//!
+//! Text (one stop per line):
+//!
+//! ```text
+//! # position  color
+//! 0.0   #000000
+//! 0.5   255 0 0
+//! 1.0   #ffffff80
+//! ```
+//!
+//! JSON (a `stops` array, or the bare array):
+//!
+//! ```json
+//! { "stops": [ { "position": 0.0, "color": "#440154" },
+//!              { "position": 1.0, "color": [253, 231, 37, 255] } ] }
+//! ```
+//!
+//! GIMP gradients (`.ggr`): RGB segments with any blending function.
+//! Linear segments become exact stops, curved ones are sampled.
+//!
+//! Parsing only: validation happens in `UserPalette::from_stops`.
+
+use std::f64::consts::PI;
+use std::path::Path;
+
+use crate::color::user::{PaletteError, Stop};
+
+/// Stop file format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StopFormat {
+    Text,
+    Json,
+    Ggr,
+}
+
+impl StopFormat {
+    /// Format from the file header, then the extension; text otherwise.
+    pub fn detect(path: &Path, text: &str) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+
+        if text.starts_with("GIMP Gradient") {
+            return StopFormat::Ggr;
+        }
+
+        match extension.as_deref() {
+            Some("ggr") => StopFormat::Ggr,
+            Some("json") => StopFormat::Json,
+            _ => StopFormat::Text,
+        }
+    }
+}
+
+/// Parse stops in the given format.
+pub fn parse(text: &str, format: StopFormat) -> Result<Vec<Stop>, PaletteError> {
+    match format {
+        StopFormat::Text => parse_text(text),
+        StopFormat::Json => parse_json(text),
+        StopFormat::Ggr => parse_ggr(text),
+    }
+}
+
+// -------------------------------------------------------------------------
+// Text
+// -------------------------------------------------------------------------
+
+fn parse_text(text: &str) -> Result<Vec<Stop>, PaletteError> {
+    let mut stops = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let syntax = PaletteError::Syntax { line: index + 1 };
+        let line = line.trim();
+
+        // Stops start with their position, so "#" at the start is a comment
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let position = fields
+            .next()
+            .and_then(|p| p.parse::<f64>().ok())
+            .ok_or(syntax)?;
+
+        let fields: Vec<&str> = fields.collect();
+        let color = match fields.as_slice() {
+            [hex] => parse_hex(hex),
+            [r, g, b] => parse_channels(&[r, g, b, "255"]),
+            [r, g, b, a] => parse_channels(&[r, g, b, a]),
+            _ => None,
+        }
+        .ok_or(PaletteError::Syntax { line: index + 1 })?;
+
+        stops.push(Stop { position, color });
+    }
+
+    Ok(stops)
+}
+
+/// `#rrggbb` or `#rrggbbaa`.
+fn parse_hex(hex: &str) -> Option<[u8; 4]> {
+    let digits = hex.strip_prefix('#')?;
+    if !matches!(digits.len(), 6 | 8) || !digits.is_ascii() {
+        return None;
+    }
+
+    let byte = |i: usize| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok();
+    let alpha = if digits.len() == 8 { byte(6)? } else { 255 };
+
+    Some([byte(0)?, byte(2)?, byte(4)?, alpha])
+}
+
+/// Four decimal channels 0..=255.
+fn parse_channels(channels: &[&str; 4]) -> Option<[u8; 4]> {
+    let mut color = [0u8; 4];
+    for (c, s) in color.iter_mut().zip(channels) {
+        *c = s.parse().ok()?;
+    }
+    Some(color)
+}
+
+// -------------------------------------------------------------------------
+// JSON
+// -------------------------------------------------------------------------
+
+fn parse_json(text: &str) -> Result<Vec<Stop>, PaletteError> {
+    let mut parser = JsonParser { text, pos: 0 };
+
+    let root = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos != text.len() {
+        return Err(parser.error());
+    }
+
+    let stops = match &root {
+        Json::Array(items) => items,
+        Json::Object(_) => match root.get("stops") {
+            Some(Json::Array(items)) => items,
+            _ => return Err(PaletteError::Syntax { line: 1 }),
+        },
+        _ => return Err(PaletteError::Syntax { line: 1 }),
+    };
+
+    stops
+        .iter()
+        .map(|(line, stop)| {
+            let syntax = PaletteError::Syntax { line: *line };
+
+            let (position, color) = match stop {
+                Json::Object(_) => (stop.get("position"), stop.get("color")),
+                Json::Array(pair) if pair.len() == 2 => {
+                    (pair.first().map(|(_, v)| v), pair.get(1).map(|(_, v)| v))
+                }
+                _ => (None, None),
+            };
+
+            let position = match position {
+                Some(Json::Number(p)) => *p,
+                _ => return Err(syntax),
+            };
+
+            let color = match color {
+                Some(Json::String(hex)) => parse_hex(hex),
+                Some(Json::Array(channels)) => json_channels(channels),
+                _ => None,
+            }
+            .ok_or(syntax)?;
+
+            Ok(Stop { position, color })
+        })
+        .collect()
+}
+
+/// `[r, g, b]` or `[r, g, b, a]`, integers 0..=255.
+fn json_channels(channels: &[(usize, Json)]) -> Option<[u8; 4]> {
+    if !matches!(channels.len(), 3 | 4) {
+        return None;
+    }
+
+    let mut color = [0, 0, 0, 255];
+    for (c, (_, value)) in color.iter_mut().zip(channels) {
+        match value {
+            Json::Number(v) if v.fract() == 0.0 && (0.0..=255.0).contains(v) => *c = *v as u8,
+            _ => return None,
+        }
+    }
+    Some(color)
+}
+
+/// Minimal JSON document model (no dependency for a few stops).
+#[derive(Debug)]
+enum Json {
+    Null,
+    Bool,
+    Number(f64),
+    String(String),
+    /// Items with their 1-based line, for error messages
+    Array(Vec<(usize, Json)>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Member of an object.
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// Recursive descent JSON parser over a UTF-8 string.
+struct JsonParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn value(&mut self) -> Result<Json, PaletteError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Json::String),
+            Some('t') => self.literal("true", Json::Bool),
+            Some('f') => self.literal("false", Json::Bool),
+            Some('n') => self.literal("null", Json::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, PaletteError> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+
+        self.skip_whitespace();
+        if self.eat('}') {
+            return Ok(Json::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            members.push((key, self.value()?));
+
+            self.skip_whitespace();
+            if self.eat('}') {
+                return Ok(Json::Object(members));
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, PaletteError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+
+        self.skip_whitespace();
+        if self.eat(']') {
+            return Ok(Json::Array(items));
+        }
+
+        loop {
+            self.skip_whitespace();
+            items.push((self.line(), self.value()?));
+
+            self.skip_whitespace();
+            if self.eat(']') {
+                return Ok(Json::Array(items));
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn string(&mut self) -> Result<String, PaletteError> {
+        self.expect('"')?;
+        let mut out = String::new();
+
+        loop {
+            let c = self.next().ok_or_else(|| self.error())?;
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let escaped = match self.next().ok_or_else(|| self.error())? {
+                        '"' => '"',
+                        '\\' => '\\',
+                        '/' => '/',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => {
+                            let hex = self.text.get(self.pos..self.pos + 4).ok_or_else(|| self.error())?;
+                            let code = u32::from_str_radix(hex, 16).map_err(|_| self.error())?;
+                            self.pos += 4;
+                            // Surrogate pairs never occur in stop files; keep them visible
+                            char::from_u32(code).unwrap_or('\u{fffd}')
+                        }
+                        _ => return Err(self.error()),
+                    };
+                    out.push(escaped);
+                }
+                c if (c as u32) < 0x20 => return Err(self.error()),
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, PaletteError> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_digit() || "+-.eE".contains(c)) {
+            self.pos += 1;
+        }
+
+        self.text[start..self.pos]
+            .parse()
+            .map(Json::Number)
+            .map_err(|_| self.error())
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> Result<Json, PaletteError> {
+        if self.text[self.pos..].starts_with(word) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), PaletteError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    /// 1-based line of the current position.
+    fn line(&self) -> usize {
+        self.text[..self.pos].matches('\n').count() + 1
+    }
+
+    /// Syntax error at the current line.
+    fn error(&self) -> PaletteError {
+        PaletteError::Syntax { line: self.line() }
+    }
+}
+
+// -------------------------------------------------------------------------
+// GIMP gradient (.ggr)
+// -------------------------------------------------------------------------
+
+/// Samples per curved (non-linear) segment.
+const SEGMENT_SAMPLES: usize = 16;
+
+fn parse_ggr(text: &str) -> Result<Vec<Stop>, PaletteError> {
+    let mut lines = text.lines().enumerate().peekable();
+
+    // Header
+    match lines.next() {
+        Some((_, header)) if header.trim() == "GIMP Gradient" => {}
+        _ => return Err(PaletteError::Syntax { line: 1 }),
+    }
+    lines.next_if(|(_, line)| line.starts_with("Name:"));
+
+    let (index, count) = lines.next().ok_or(PaletteError::Syntax { line: 2 })?;
+    let count: usize = count
+        .trim()
+        .parse()
+        .map_err(|_| PaletteError::Syntax { line: index + 1 })?;
+
+    let mut stops = Vec::new();
+
+    for _ in 0..count {
+        let (index, line) = lines.next().ok_or(PaletteError::Syntax { line: index + 2 })?;
+        let syntax = PaletteError::Syntax { line: index + 1 };
+
+        let values: Vec<f64> = line
+            .split_whitespace()
+            .map(|v| v.parse::<f64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| syntax)?;
+
+        // left mid right, RGBA left, RGBA right, blending, coloring
+        // [, left / right endpoint color type]
+        if values.len() != 13 && values.len() != 15 {
+            return Err(PaletteError::Syntax { line: index + 1 });
+        }
+
+        // Only RGB coloring with fixed endpoint colors maps onto stops
+        if values[12..].iter().any(|&v| v != 0.0) {
+            return Err(PaletteError::Unsupported);
+        }
+
+        let (left, middle, right) = (values[0], values[1], values[2]);
+        let c0 = [values[3], values[4], values[5], values[6]];
+        let c1 = [values[7], values[8], values[9], values[10]];
+        let blend = values[11] as u32;
+
+        segment_stops(&mut stops, [left, middle, right], c0, c1, blend)?;
+    }
+
+    Ok(stops)
+}
+
+/// Append the stops of one segment.
+///
+/// `c0` / `c1` are RGBA in 0.0..=1.0.
+fn segment_stops(
+    stops: &mut Vec<Stop>,
+    [left, middle, right]: [f64; 3],
+    c0: [f64; 4],
+    c1: [f64; 4],
+    blend: u32,
+) -> Result<(), PaletteError> {
+    let color = |f: f64| {
+        let mut rgba = [0u8; 4];
+        for (i, c) in rgba.iter_mut().enumerate() {
+            *c = ((c0[i] + (c1[i] - c0[i]) * f).clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+        }
+        rgba
+    };
+
+    let width = right - left;
+    let mid = if width > 0.0 { (middle - left) / width } else { 0.5 };
+
+    let factor: fn(f64, f64) -> f64 = match blend {
+        0 => linear_factor,
+        1 => |mid, pos| if mid < 1e-10 { 1.0 } else { pos.powf(0.5f64.ln() / mid.ln()) },
+        2 => |mid, pos| ((-PI / 2.0 + PI * linear_factor(mid, pos)).sin() + 1.0) / 2.0,
+        3 => |mid, pos| {
+            let p = linear_factor(mid, pos) - 1.0;
+            (1.0 - p * p).sqrt()
+        },
+        4 => |mid, pos| {
+            let p = linear_factor(mid, pos);
+            1.0 - (1.0 - p * p).sqrt()
+        },
+        // Step: hard edge at the midpoint
+        5 => {
+            stops.push(Stop { position: left, color: color(0.0) });
+            stops.push(Stop { position: middle, color: color(0.0) });
+            stops.push(Stop { position: middle, color: color(1.0) });
+            stops.push(Stop { position: right, color: color(1.0) });
+            return Ok(());
+        }
+        _ => return Err(PaletteError::Unsupported),
+    };
+
+    // Linear is piecewise linear through the midpoint: three exact stops
+    let samples: Vec<f64> = match blend {
+        0 => vec![0.0, mid, 1.0],
+        _ => (0..=SEGMENT_SAMPLES)
+            .map(|i| i as f64 / SEGMENT_SAMPLES as f64)
+            .collect(),
+    };
+
+    for pos in samples {
+        stops.push(Stop {
+            position: left + pos * width,
+            color: color(factor(mid, pos)),
+        });
+    }
+
+    Ok(())
+}
+
+/// GIMP's linear blend with a movable midpoint (0.5 at `mid`).
+fn linear_factor(mid: f64, pos: f64) -> f64 {
+    if pos <= mid {
+        if mid < 1e-10 {
+            0.0
+        } else {
+            0.5 * pos / mid
+        }
+    } else if 1.0 - mid < 1e-10 {
+        1.0
+    } else {
+        0.5 + 0.5 * (pos - mid) / (1.0 - mid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_parse_to_the_same_stops() {
+        let expected = vec![
+            Stop { position: 0.0, color: [0, 0, 0, 255] },
+            Stop { position: 0.5, color: [128, 128, 128, 255] },
+            Stop { position: 1.0, color: [255, 255, 255, 255] },
+        ];
+
+        let text = "# gray\n0.0 #000000\n0.5 128 128 128\n1.0 #ffffffff\n";
+        assert_eq!(parse(text, StopFormat::Text), Ok(expected.clone()));
+
+        let json = r##"{"stops": [
+            {"position": 0.0, "color": "#000000"},
+            {"position": 0.5, "color": [128, 128, 128]},
+            [1.0, "#ffffff"]
+        ]}"##;
+        assert_eq!(parse(json, StopFormat::Json), Ok(expected.clone()));
+
+        let ggr = "GIMP Gradient\nName: gray\n1\n\
+                   0 0.5 1 0 0 0 1 1 1 1 1 0 0\n";
+        assert_eq!(parse(ggr, StopFormat::Ggr), Ok(expected));
+
+        assert_eq!(
+            parse("0.0 #000000\n0.5 red\n", StopFormat::Text),
+            Err(PaletteError::Syntax { line: 2 })
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! User-defined palettes.
+//!
+//! A palette is a list of (position, RGBA) stops, interpolated
+//! linearly in sRGB or in OKLab and baked into a lookup table once.
+//! Mapping is then a single table read: fast, and identical everywhere.
+//!
+//! Stops come from code or from a file, see `color::stops`.
+
+use std::path::Path;
+
+use crate::color::stops::{self, StopFormat};
+use crate::color::{clamp_intensity, ColorMapper};
+
+/// One color stop.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Stop {
+    /// Position on the intensity axis, 0.0..=1.0
+    pub position: f64,
+
+    /// sRGB color with alpha
+    pub color: [u8; 4],
+}
+
+/// Color space in which neighbouring stops are blended.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Straight sRGB channel blend (what most tools do)
+    #[default]
+    Srgb,
+
+    /// OKLab: perceptually even steps, no muddy midpoints
+    Oklab,
+}
+
+/// Lookup table resolution.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum LutSize {
+    /// 256 entries (one per 8-bit level)
+    #[default]
+    Small,
+
+    /// 4096 entries (smooth gradients on tall images)
+    Large,
+}
+
+impl LutSize {
+    /// Number of table entries.
+    pub fn entries(self) -> usize {
+        match self {
+            LutSize::Small => 256,
+            LutSize::Large => 4096,
+        }
+    }
+}
+
+/// Palette loading / validation errors.
+#[derive(Debug, PartialEq)]
+pub enum PaletteError {
+    /// Palette file could not be read
+    IoError,
+
+    /// Malformed file, with the 1-based line of the first problem
+    Syntax { line: usize },
+
+    /// Fewer than two stops
+    TooFewStops,
+
+    /// Stop position not finite or outside 0.0..=1.0
+    PositionOutOfRange,
+
+    /// Stop positions not in ascending order
+    Unsorted,
+
+    /// File feature without a palette equivalent (e.g. HSV gradient segments)
+    Unsupported,
+}
+
+/// Palette baked from color stops.
+///
+/// Intensities below the first stop take its color,
+/// intensities above the last stop take the last color.
+/// Two stops at the same position form a hard edge.
+#[derive(Debug, Clone)]
+pub struct UserPalette {
+    lut: Vec<[u8; 4]>,
+}
+
+impl UserPalette {
+    /// Build a palette from stops sorted by position.
+    pub fn from_stops(
+        stops: &[Stop],
+        interpolation: Interpolation,
+        lut_size: LutSize,
+    ) -> Result<Self, PaletteError> {
+        validate(stops)?;
+
+        let n = lut_size.entries();
+        let lut = (0..n)
+            .map(|i| color_at(stops, i as f64 / (n - 1) as f64, interpolation))
+            .collect();
+
+        Ok(Self { lut })
+    }
+
+    /// Load a palette from a stops file (text, JSON or GIMP `.ggr`).
+    ///
+    /// The format is picked from the extension and the file header.
+    pub fn from_path(
+        path: impl AsRef<Path>,
+        interpolation: Interpolation,
+        lut_size: LutSize,
+    ) -> Result<Self, PaletteError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|_| PaletteError::IoError)?;
+
+        let stops = stops::parse(&text, StopFormat::detect(path, &text))?;
+        Self::from_stops(&stops, interpolation, lut_size)
+    }
+}
+
+impl ColorMapper for UserPalette {
+    fn map(&self, intensity: f32) -> [u8; 4] {
+        let last = self.lut.len() - 1;
+        let index = (clamp_intensity(intensity) * last as f64 + 0.5) as usize;
+
+        self.lut[index.min(last)]
+    }
+}
+
+/// Check stop count, range and order.
+fn validate(stops: &[Stop]) -> Result<(), PaletteError> {
+    if stops.len() < 2 {
+        return Err(PaletteError::TooFewStops);
+    }
+
+    if stops
+        .iter()
+        .any(|s| !(0.0..=1.0).contains(&s.position))
+    {
+        return Err(PaletteError::PositionOutOfRange);
+    }
+
+    if stops.windows(2).any(|w| w[1].position < w[0].position) {
+        return Err(PaletteError::Unsorted);
+    }
+
+    Ok(())
+}
+
+/// Palette color at intensity `x`.
+fn color_at(stops: &[Stop], x: f64, interpolation: Interpolation) -> [u8; 4] {
+    // First stop strictly above x; at a hard edge the upper color wins
+    let upper = match stops.iter().position(|s| s.position > x) {
+        Some(0) => return stops[0].color,
+        Some(i) => i,
+        None => return stops[stops.len() - 1].color,
+    };
+
+    let (a, b) = (stops[upper - 1], stops[upper]);
+    let t = (x - a.position) / (b.position - a.position);
+
+    let alpha = lerp(a.color[3] as f64, b.color[3] as f64, t);
+
+    let [r, g, b] = match interpolation {
+        Interpolation::Srgb => {
+            let channel = |i: usize| lerp(a.color[i] as f64, b.color[i] as f64, t);
+            [channel(0), channel(1), channel(2)]
+        }
+        Interpolation::Oklab => {
+            let (lab_a, lab_b) = (oklab(a.color), oklab(b.color));
+            let mixed = [
+                lerp(lab_a[0], lab_b[0], t),
+                lerp(lab_a[1], lab_b[1], t),
+                lerp(lab_a[2], lab_b[2], t),
+            ];
+            srgb(mixed)
+        }
+    };
+
+    [to_u8(r), to_u8(g), to_u8(b), to_u8(alpha)]
+}
+
+#[inline]
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+/// Round a 0..255 channel value.
+#[inline]
+fn to_u8(v: f64) -> u8 {
+    (v.clamp(0.0, 255.0) + 0.5) as u8
+}
+
+// -------------------------------------------------------------------------
+// OKLab (Björn Ottosson, 2020)
+// -------------------------------------------------------------------------
+
+/// sRGB8 -> OKLab.
+fn oklab(c: [u8; 4]) -> [f64; 3] {
+    let linear = |v: u8| {
+        let v = v as f64 / 255.0;
+        if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let (r, g, b) = (linear(c[0]), linear(c[1]), linear(c[2]));
+
+    let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+    [
+        0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+        1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+        0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+    ]
+}
+
+/// OKLab -> sRGB channels in 0..255 (not yet rounded or clamped).
+fn srgb(lab: [f64; 3]) -> [f64; 3] {
+    let [l, a, b] = lab;
+
+    let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+
+    let encode = |v: f64| {
+        let v = v.clamp(0.0, 1.0);
+        let v = if v <= 0.0031308 {
+            12.92 * v
+        } else {
+            1.055 * v.powf(1.0 / 2.4) - 0.055
+        };
+        v * 255.0
+    };
+
+    [
+        encode(4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_),
+        encode(-1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_),
+        encode(-0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stop(position: f64, color: [u8; 4]) -> Stop {
+        Stop { position, color }
+    }
+
+    #[test]
+    fn lut_hits_stops_in_both_color_spaces() {
+        let stops = [
+            stop(0.0, [0, 0, 0, 255]),
+            stop(0.5, [255, 0, 0, 255]),
+            stop(1.0, [255, 255, 255, 255]),
+        ];
+
+        for interpolation in [Interpolation::Srgb, Interpolation::Oklab] {
+            let palette = UserPalette::from_stops(&stops, interpolation, LutSize::Large).unwrap();
+            assert_eq!(palette.map(0.0), [0, 0, 0, 255]);
+            assert_eq!(palette.map(0.5), [255, 0, 0, 255]);
+            assert_eq!(palette.map(1.0), [255, 255, 255, 255]);
+        }
+    }
+
+    #[test]
+    fn invalid_stops_are_rejected() {
+        let black = [0, 0, 0, 255];
+        let build = |stops: &[Stop]| {
+            UserPalette::from_stops(stops, Interpolation::Srgb, LutSize::Small).err()
+        };
+
+        assert_eq!(build(&[stop(0.0, black)]), Some(PaletteError::TooFewStops));
+        assert_eq!(
+            build(&[stop(0.0, black), stop(f64::NAN, black)]),
+            Some(PaletteError::PositionOutOfRange)
+        );
+        assert_eq!(
+            build(&[stop(0.6, black), stop(0.4, black)]),
+            Some(PaletteError::Unsorted)
+        );
+    }
+}
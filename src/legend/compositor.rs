@@ -4,6 +4,7 @@
 //! This module does NOT generate legend content.
 //! It only executes drawing commands deterministically.
 
+use crate::legend::font::FontRasterizer;
 use crate::legend::LegendCommand;
 use crate::render::ImageBuffer;
 
@@ -14,20 +15,25 @@ pub struct LegendCompositor;
 
 impl LegendCompositor {
     /// Apply legend drawing commands onto an image buffer.
+    ///
+    /// Lines and text use one solid `color`.
+    /// The dBFS gradient needs a `ColorMapper` and is left to
+    /// `overlay::apply_legend_overlay`.
     pub fn apply(
         image: &mut ImageBuffer,
         commands: &[LegendCommand],
         color: [u8; 4],
+        font: &dyn FontRasterizer,
     ) {
         for cmd in commands {
             match cmd {
                 LegendCommand::Line { x1, y1, x2, y2 } => {
                     Self::draw_line(image, *x1, *y1, *x2, *y2, color);
                 }
-                LegendCommand::Text { .. } => {
-                    // Text rendering is delegated to a font rasterizer later.
-                    // This compositor only reserves the command.
+                LegendCommand::Text { x, y, content, size } => {
+                    font.draw(image, *x, *y, content, *size, color);
                 }
+                LegendCommand::DbfsGradient { .. } => {}
             }
         }
     }
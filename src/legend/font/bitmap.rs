@@ -0,0 +1,355 @@
+//! Built-in bitmap font.
+//!
+//! 5×9 pixel glyphs (7 px cap height + 2 px descender) in a 6×12 cell,
+//! with 2 rows above the capitals for accents.
+//!
+//! Covers printable ASCII and Latin-1 (U+00A0..U+00FF),
+//! including "·" and "µ". Other characters are drawn as "?".
+//!
+//! Glyphs are scaled by whole pixels only:
+//! no anti-aliasing, no floating point, crisp at every size.
+//! Bold doubles every stroke one pixel to the right.
+
+use crate::legend::font::{FontRasterizer, FontWeight};
+use crate::render::ImageBuffer;
+
+/// Horizontal advance per character, unscaled
+pub const CELL_WIDTH: usize = 6;
+
+/// Line height, unscaled
+pub const CELL_HEIGHT: usize = 12;
+
+/// Built-in deterministic bitmap font.
+///
+/// `size` selects the largest whole-pixel scale whose line height
+/// fits into `size` pixels (minimum 1): 12 px, 24 px, 36 px …
+#[derive(Debug, Default)]
+pub struct BitmapFont {
+    weight: FontWeight,
+}
+
+impl BitmapFont {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_weight(weight: FontWeight) -> Self {
+        Self { weight }
+    }
+
+    /// Pixel scale for a requested line height.
+    pub fn scale(size: u32) -> u32 {
+        (size / CELL_HEIGHT as u32).max(1)
+    }
+
+    /// Horizontal advance per character, unscaled.
+    fn advance(&self) -> usize {
+        match self.weight {
+            FontWeight::Regular => CELL_WIDTH,
+            FontWeight::Bold => CELL_WIDTH + 1,
+        }
+    }
+}
+
+impl FontRasterizer for BitmapFont {
+    fn measure(&self, text: &str, size: u32) -> (u32, u32) {
+        let scale = Self::scale(size);
+        let chars = text.chars().count() as u32;
+
+        (chars * self.advance() as u32 * scale, CELL_HEIGHT as u32 * scale)
+    }
+
+    fn draw(
+        &self,
+        image: &mut ImageBuffer,
+        x: u32,
+        y: u32,
+        text: &str,
+        size: u32,
+        color: [u8; 4],
+    ) {
+        let scale = Self::scale(size) as usize;
+        let (x, y) = (x as usize, y as usize);
+
+        let bold = self.weight == FontWeight::Bold;
+
+        for (i, c) in text.chars().enumerate() {
+            let left = x + i * self.advance() * scale;
+
+            for (row, &bits) in glyph(c).iter().enumerate() {
+                // 6 columns, bit 5 leftmost;
+                // bold ORs in the row shifted one column right
+                let bits = (bits as u32) << 1 | if bold { bits as u32 } else { 0 };
+
+                for col in 0..6 {
+                    if bits & (0x20 >> col) == 0 {
+                        continue;
+                    }
+
+                    for dy in 0..scale {
+                        for dx in 0..scale {
+                            put_pixel(
+                                image,
+                                left + col * scale + dx,
+                                y + row * scale + dy,
+                                color,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Glyph rows for `c`, top to bottom.
+fn glyph(c: char) -> &'static [u8; CELL_HEIGHT] {
+    match c as u32 {
+        code @ 0x20..=0x7E => &ASCII[(code - 0x20) as usize],
+        code @ 0xA0..=0xFF => &LATIN1[(code - 0xA0) as usize],
+        _ => &ASCII[('?' as u32 - 0x20) as usize],
+    }
+}
+
+#[inline]
+fn put_pixel(image: &mut ImageBuffer, x: usize, y: usize, color: [u8; 4]) {
+    if x >= image.width || y >= image.height {
+        return;
+    }
+
+    let idx = (y * image.width + x) * 4;
+    image.data[idx..idx + 4].copy_from_slice(&color);
+}
+
+// -------------------------------------------------------------------------
+// Glyph tables
+//
+// One row per byte, bit 4 = leftmost column.
+// Rows 0-1: accents, rows 2-8: capitals, rows 9-10: descenders.
+// -------------------------------------------------------------------------
+
+const ASCII: [[u8; CELL_HEIGHT]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0020  
+    [0x00, 0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04, 0x00, 0x00, 0x00], // U+0021 !
+    [0x00, 0x00, 0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0022 "
+    [0x00, 0x00, 0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A, 0x00, 0x00, 0x00], // U+0023 #
+    [0x00, 0x00, 0x04, 0x0F, 0x14, 0x0E, 0x05, 0x1E, 0x04, 0x00, 0x00, 0x00], // U+0024 $
+    [0x00, 0x00, 0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03, 0x00, 0x00, 0x00], // U+0025 %
+    [0x00, 0x00, 0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D, 0x00, 0x00, 0x00], // U+0026 &
+    [0x00, 0x00, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0027 '
+    [0x00, 0x00, 0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02, 0x00, 0x00, 0x00], // U+0028 (
+    [0x00, 0x00, 0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08, 0x00, 0x00, 0x00], // U+0029 )
+    [0x00, 0x00, 0x00, 0x04, 0x15, 0x0E, 0x15, 0x04, 0x00, 0x00, 0x00, 0x00], // U+002A *
+    [0x00, 0x00, 0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00, 0x00, 0x00, 0x00], // U+002B +
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x04, 0x08, 0x00, 0x00], // U+002C ,
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+002D -
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00], // U+002E .
+    [0x00, 0x00, 0x01, 0x02, 0x02, 0x04, 0x08, 0x08, 0x10, 0x00, 0x00, 0x00], // U+002F /
+    [0x00, 0x00, 0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+0030 0
+    [0x00, 0x00, 0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00], // U+0031 1
+    [0x00, 0x00, 0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F, 0x00, 0x00, 0x00], // U+0032 2
+    [0x00, 0x00, 0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+0033 3
+    [0x00, 0x00, 0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02, 0x00, 0x00, 0x00], // U+0034 4
+    [0x00, 0x00, 0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+0035 5
+    [0x00, 0x00, 0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+0036 6
+    [0x00, 0x00, 0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00], // U+0037 7
+    [0x00, 0x00, 0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+0038 8
+    [0x00, 0x00, 0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C, 0x00, 0x00, 0x00], // U+0039 9
+    [0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00], // U+003A :
+    [0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x04, 0x04, 0x08, 0x00, 0x00], // U+003B ;
+    [0x00, 0x00, 0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02, 0x00, 0x00, 0x00], // U+003C <
+    [0x00, 0x00, 0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00, 0x00, 0x00, 0x00], // U+003D =
+    [0x00, 0x00, 0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08, 0x00, 0x00, 0x00], // U+003E >
+    [0x00, 0x00, 0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04, 0x00, 0x00, 0x00], // U+003F ?
+    [0x00, 0x00, 0x0E, 0x11, 0x17, 0x15, 0x17, 0x10, 0x0F, 0x00, 0x00, 0x00], // U+0040 @
+    [0x00, 0x00, 0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11, 0x00, 0x00, 0x00], // U+0041 A
+    [0x00, 0x00, 0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E, 0x00, 0x00, 0x00], // U+0042 B
+    [0x00, 0x00, 0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+0043 C
+    [0x00, 0x00, 0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C, 0x00, 0x00, 0x00], // U+0044 D
+    [0x00, 0x00, 0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F, 0x00, 0x00, 0x00], // U+0045 E
+    [0x00, 0x00, 0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10, 0x00, 0x00, 0x00], // U+0046 F
+    [0x00, 0x00, 0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F, 0x00, 0x00, 0x00], // U+0047 G
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11, 0x00, 0x00, 0x00], // U+0048 H
+    [0x00, 0x00, 0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00], // U+0049 I
+    [0x00, 0x00, 0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C, 0x00, 0x00, 0x00], // U+004A J
+    [0x00, 0x00, 0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11, 0x00, 0x00, 0x00], // U+004B K
+    [0x00, 0x00, 0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F, 0x00, 0x00, 0x00], // U+004C L
+    [0x00, 0x00, 0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11, 0x00, 0x00, 0x00], // U+004D M
+    [0x00, 0x00, 0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11, 0x00, 0x00, 0x00], // U+004E N
+    [0x00, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+004F O
+    [0x00, 0x00, 0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10, 0x00, 0x00, 0x00], // U+0050 P
+    [0x00, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D, 0x00, 0x00, 0x00], // U+0051 Q
+    [0x00, 0x00, 0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11, 0x00, 0x00, 0x00], // U+0052 R
+    [0x00, 0x00, 0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E, 0x00, 0x00, 0x00], // U+0053 S
+    [0x00, 0x00, 0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00], // U+0054 T
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+0055 U
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04, 0x00, 0x00, 0x00], // U+0056 V
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A, 0x00, 0x00, 0x00], // U+0057 W
+    [0x00, 0x00, 0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11, 0x00, 0x00, 0x00], // U+0058 X
+    [0x00, 0x00, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00], // U+0059 Y
+    [0x00, 0x00, 0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F, 0x00, 0x00, 0x00], // U+005A Z
+    [0x00, 0x00, 0x0E, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0E, 0x00, 0x00, 0x00], // U+005B [
+    [0x00, 0x00, 0x10, 0x08, 0x08, 0x04, 0x02, 0x02, 0x01, 0x00, 0x00, 0x00], // U+005C \
+    [0x00, 0x00, 0x0E, 0x02, 0x02, 0x02, 0x02, 0x02, 0x0E, 0x00, 0x00, 0x00], // U+005D ]
+    [0x00, 0x00, 0x04, 0x0A, 0x11, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+005E ^
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F, 0x00, 0x00], // U+005F _
+    [0x00, 0x00, 0x08, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+0060 `
+    [0x00, 0x00, 0x00, 0x00, 0x0E, 0x01, 0x0F, 0x11, 0x0F, 0x00, 0x00, 0x00], // U+0061 a
+    [0x00, 0x00, 0x10, 0x10, 0x1E, 0x11, 0x11, 0x11, 0x1E, 0x00, 0x00, 0x00], // U+0062 b
+    [0x00, 0x00, 0x00, 0x00, 0x0E, 0x10, 0x10, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+0063 c
+    [0x00, 0x00, 0x01, 0x01, 0x0F, 0x11, 0x11, 0x11, 0x0F, 0x00, 0x00, 0x00], // U+0064 d
+    [0x00, 0x00, 0x00, 0x00, 0x0E, 0x11, 0x1F, 0x10, 0x0E, 0x00, 0x00, 0x00], // U+0065 e
+    [0x00, 0x00, 0x06, 0x09, 0x08, 0x1C, 0x08, 0x08, 0x08, 0x00, 0x00, 0x00], // U+0066 f
+    [0x00, 0x00, 0x00, 0x00, 0x0F, 0x11, 0x11, 0x11, 0x0F, 0x01, 0x0E, 0x00], // U+0067 g
+    [0x00, 0x00, 0x10, 0x10, 0x1E, 0x11, 0x11, 0x11, 0x11, 0x00, 0x00, 0x00], // U+0068 h
+    [0x00, 0x00, 0x04, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00], // U+0069 i
+    [0x00, 0x00, 0x02, 0x00, 0x06, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C, 0x00], // U+006A j
+    [0x00, 0x00, 0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12, 0x00, 0x00, 0x00], // U+006B k
+    [0x00, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00], // U+006C l
+    [0x00, 0x00, 0x00, 0x00, 0x1A, 0x15, 0x15, 0x15, 0x15, 0x00, 0x00, 0x00], // U+006D m
+    [0x00, 0x00, 0x00, 0x00, 0x1E, 0x11, 0x11, 0x11, 0x11, 0x00, 0x00, 0x00], // U+006E n
+    [0x00, 0x00, 0x00, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+006F o
+    [0x00, 0x00, 0x00, 0x00, 0x1E, 0x11, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x00], // U+0070 p
+    [0x00, 0x00, 0x00, 0x00, 0x0F, 0x11, 0x11, 0x11, 0x0F, 0x01, 0x01, 0x00], // U+0071 q
+    [0x00, 0x00, 0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10, 0x00, 0x00, 0x00], // U+0072 r
+    [0x00, 0x00, 0x00, 0x00, 0x0F, 0x10, 0x0E, 0x01, 0x1E, 0x00, 0x00, 0x00], // U+0073 s
+    [0x00, 0x00, 0x08, 0x08, 0x1C, 0x08, 0x08, 0x09, 0x06, 0x00, 0x00, 0x00], // U+0074 t
+    [0x00, 0x00, 0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0D, 0x00, 0x00, 0x00], // U+0075 u
+    [0x00, 0x00, 0x00, 0x00, 0x11, 0x11, 0x11, 0x0A, 0x04, 0x00, 0x00, 0x00], // U+0076 v
+    [0x00, 0x00, 0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0A, 0x00, 0x00, 0x00], // U+0077 w
+    [0x00, 0x00, 0x00, 0x00, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x00, 0x00, 0x00], // U+0078 x
+    [0x00, 0x00, 0x00, 0x00, 0x11, 0x11, 0x11, 0x11, 0x0F, 0x01, 0x0E, 0x00], // U+0079 y
+    [0x00, 0x00, 0x00, 0x00, 0x1F, 0x02, 0x04, 0x08, 0x1F, 0x00, 0x00, 0x00], // U+007A z
+    [0x00, 0x00, 0x02, 0x04, 0x04, 0x08, 0x04, 0x04, 0x02, 0x00, 0x00, 0x00], // U+007B {
+    [0x00, 0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00], // U+007C |
+    [0x00, 0x00, 0x08, 0x04, 0x04, 0x02, 0x04, 0x04, 0x08, 0x00, 0x00, 0x00], // U+007D }
+    [0x00, 0x00, 0x00, 0x00, 0x09, 0x15, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00], // U+007E ~
+];
+
+const LATIN1: [[u8; CELL_HEIGHT]; 96] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+00A0 NBSP
+    [0x00, 0x00, 0x04, 0x00, 0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00], // U+00A1 ¡
+    [0x00, 0x00, 0x04, 0x0F, 0x14, 0x14, 0x14, 0x0F, 0x04, 0x00, 0x00, 0x00], // U+00A2 ¢
+    [0x00, 0x00, 0x06, 0x09, 0x08, 0x1C, 0x08, 0x09, 0x16, 0x00, 0x00, 0x00], // U+00A3 £
+    [0x00, 0x00, 0x00, 0x11, 0x0E, 0x0A, 0x0E, 0x11, 0x00, 0x00, 0x00, 0x00], // U+00A4 ¤
+    [0x00, 0x00, 0x11, 0x0A, 0x04, 0x1F, 0x04, 0x1F, 0x04, 0x00, 0x00, 0x00], // U+00A5 ¥
+    [0x00, 0x00, 0x04, 0x04, 0x04, 0x00, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00], // U+00A6 ¦
+    [0x00, 0x00, 0x0E, 0x10, 0x0C, 0x12, 0x0C, 0x02, 0x1C, 0x00, 0x00, 0x00], // U+00A7 §
+    [0x00, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+00A8 ¨
+    [0x00, 0x00, 0x0E, 0x11, 0x15, 0x19, 0x15, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00A9 ©
+    [0x00, 0x00, 0x0C, 0x02, 0x0E, 0x12, 0x0E, 0x00, 0x1E, 0x00, 0x00, 0x00], // U+00AA ª
+    [0x00, 0x00, 0x00, 0x05, 0x0A, 0x14, 0x0A, 0x05, 0x00, 0x00, 0x00, 0x00], // U+00AB «
+    [0x00, 0x00, 0x00, 0x00, 0x1F, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00], // U+00AC ¬
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+00AD SHY
+    [0x00, 0x00, 0x0E, 0x11, 0x1D, 0x1B, 0x1B, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00AE ®
+    [0x00, 0x00, 0x1F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+00AF ¯
+    [0x00, 0x00, 0x0C, 0x12, 0x12, 0x0C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+00B0 °
+    [0x00, 0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00, 0x1F, 0x00, 0x00, 0x00], // U+00B1 ±
+    [0x00, 0x00, 0x0C, 0x02, 0x04, 0x08, 0x0E, 0x00, 0x00, 0x00, 0x00, 0x00], // U+00B2 ²
+    [0x00, 0x00, 0x1C, 0x02, 0x0C, 0x02, 0x1C, 0x00, 0x00, 0x00, 0x00, 0x00], // U+00B3 ³
+    [0x00, 0x00, 0x02, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+00B4 ´
+    [0x00, 0x00, 0x00, 0x00, 0x11, 0x11, 0x11, 0x19, 0x16, 0x10, 0x10, 0x00], // U+00B5 µ
+    [0x00, 0x00, 0x0F, 0x1D, 0x1D, 0x0D, 0x05, 0x05, 0x05, 0x00, 0x00, 0x00], // U+00B6 ¶
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // U+00B7 ·
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x0C, 0x00], // U+00B8 ¸
+    [0x00, 0x00, 0x04, 0x0C, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00, 0x00, 0x00], // U+00B9 ¹
+    [0x00, 0x00, 0x0C, 0x12, 0x12, 0x0C, 0x00, 0x00, 0x1E, 0x00, 0x00, 0x00], // U+00BA º
+    [0x00, 0x00, 0x00, 0x14, 0x0A, 0x05, 0x0A, 0x14, 0x00, 0x00, 0x00, 0x00], // U+00BB »
+    [0x00, 0x00, 0x11, 0x12, 0x14, 0x0A, 0x16, 0x1F, 0x02, 0x00, 0x00, 0x00], // U+00BC ¼
+    [0x00, 0x00, 0x11, 0x12, 0x14, 0x0B, 0x11, 0x02, 0x07, 0x00, 0x00, 0x00], // U+00BD ½
+    [0x00, 0x00, 0x19, 0x0A, 0x18, 0x0A, 0x16, 0x1F, 0x02, 0x00, 0x00, 0x00], // U+00BE ¾
+    [0x00, 0x00, 0x04, 0x00, 0x04, 0x08, 0x10, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00BF ¿
+    [0x08, 0x04, 0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11, 0x00, 0x00, 0x00], // U+00C0 À
+    [0x02, 0x04, 0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11, 0x00, 0x00, 0x00], // U+00C1 Á
+    [0x04, 0x0A, 0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11, 0x00, 0x00, 0x00], // U+00C2 Â
+    [0x0D, 0x12, 0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11, 0x00, 0x00, 0x00], // U+00C3 Ã
+    [0x0A, 0x00, 0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11, 0x00, 0x00, 0x00], // U+00C4 Ä
+    [0x04, 0x0A, 0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11, 0x00, 0x00, 0x00], // U+00C5 Å
+    [0x00, 0x00, 0x0F, 0x14, 0x14, 0x1F, 0x14, 0x14, 0x17, 0x00, 0x00, 0x00], // U+00C6 Æ
+    [0x00, 0x00, 0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E, 0x04, 0x0C, 0x00], // U+00C7 Ç
+    [0x08, 0x04, 0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F, 0x00, 0x00, 0x00], // U+00C8 È
+    [0x02, 0x04, 0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F, 0x00, 0x00, 0x00], // U+00C9 É
+    [0x04, 0x0A, 0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F, 0x00, 0x00, 0x00], // U+00CA Ê
+    [0x0A, 0x00, 0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F, 0x00, 0x00, 0x00], // U+00CB Ë
+    [0x08, 0x04, 0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00], // U+00CC Ì
+    [0x02, 0x04, 0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00], // U+00CD Í
+    [0x04, 0x0A, 0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00], // U+00CE Î
+    [0x0A, 0x00, 0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00], // U+00CF Ï
+    [0x00, 0x00, 0x1C, 0x12, 0x11, 0x1D, 0x11, 0x12, 0x1C, 0x00, 0x00, 0x00], // U+00D0 Ð
+    [0x0D, 0x12, 0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11, 0x00, 0x00, 0x00], // U+00D1 Ñ
+    [0x08, 0x04, 0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00D2 Ò
+    [0x02, 0x04, 0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00D3 Ó
+    [0x04, 0x0A, 0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00D4 Ô
+    [0x0D, 0x12, 0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00D5 Õ
+    [0x0A, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00D6 Ö
+    [0x00, 0x00, 0x00, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x00, 0x00, 0x00, 0x00], // U+00D7 ×
+    [0x00, 0x00, 0x0F, 0x13, 0x15, 0x15, 0x15, 0x19, 0x1E, 0x00, 0x00, 0x00], // U+00D8 Ø
+    [0x08, 0x04, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00D9 Ù
+    [0x02, 0x04, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00DA Ú
+    [0x04, 0x0A, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00DB Û
+    [0x0A, 0x00, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00DC Ü
+    [0x02, 0x04, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x00], // U+00DD Ý
+    [0x00, 0x00, 0x10, 0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x00, 0x00, 0x00], // U+00DE Þ
+    [0x00, 0x00, 0x0C, 0x12, 0x12, 0x14, 0x12, 0x11, 0x16, 0x00, 0x00, 0x00], // U+00DF ß
+    [0x00, 0x00, 0x08, 0x04, 0x0E, 0x01, 0x0F, 0x11, 0x0F, 0x00, 0x00, 0x00], // U+00E0 à
+    [0x00, 0x00, 0x02, 0x04, 0x0E, 0x01, 0x0F, 0x11, 0x0F, 0x00, 0x00, 0x00], // U+00E1 á
+    [0x00, 0x00, 0x04, 0x0A, 0x0E, 0x01, 0x0F, 0x11, 0x0F, 0x00, 0x00, 0x00], // U+00E2 â
+    [0x00, 0x00, 0x0D, 0x12, 0x0E, 0x01, 0x0F, 0x11, 0x0F, 0x00, 0x00, 0x00], // U+00E3 ã
+    [0x00, 0x00, 0x0A, 0x00, 0x0E, 0x01, 0x0F, 0x11, 0x0F, 0x00, 0x00, 0x00], // U+00E4 ä
+    [0x00, 0x00, 0x04, 0x0A, 0x0E, 0x01, 0x0F, 0x11, 0x0F, 0x00, 0x00, 0x00], // U+00E5 å
+    [0x00, 0x00, 0x00, 0x00, 0x1A, 0x05, 0x0F, 0x14, 0x0B, 0x00, 0x00, 0x00], // U+00E6 æ
+    [0x00, 0x00, 0x00, 0x00, 0x0E, 0x10, 0x10, 0x11, 0x0E, 0x04, 0x0C, 0x00], // U+00E7 ç
+    [0x00, 0x00, 0x08, 0x04, 0x0E, 0x11, 0x1F, 0x10, 0x0E, 0x00, 0x00, 0x00], // U+00E8 è
+    [0x00, 0x00, 0x02, 0x04, 0x0E, 0x11, 0x1F, 0x10, 0x0E, 0x00, 0x00, 0x00], // U+00E9 é
+    [0x00, 0x00, 0x04, 0x0A, 0x0E, 0x11, 0x1F, 0x10, 0x0E, 0x00, 0x00, 0x00], // U+00EA ê
+    [0x00, 0x00, 0x0A, 0x00, 0x0E, 0x11, 0x1F, 0x10, 0x0E, 0x00, 0x00, 0x00], // U+00EB ë
+    [0x00, 0x00, 0x08, 0x04, 0x0C, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00], // U+00EC ì
+    [0x00, 0x00, 0x02, 0x04, 0x0C, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00], // U+00ED í
+    [0x00, 0x00, 0x04, 0x0A, 0x0C, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00], // U+00EE î
+    [0x00, 0x00, 0x0A, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x0E, 0x00, 0x00, 0x00], // U+00EF ï
+    [0x00, 0x00, 0x0A, 0x04, 0x0A, 0x01, 0x0F, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00F0 ð
+    [0x00, 0x00, 0x0D, 0x12, 0x1E, 0x11, 0x11, 0x11, 0x11, 0x00, 0x00, 0x00], // U+00F1 ñ
+    [0x00, 0x00, 0x08, 0x04, 0x0E, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00F2 ò
+    [0x00, 0x00, 0x02, 0x04, 0x0E, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00F3 ó
+    [0x00, 0x00, 0x04, 0x0A, 0x0E, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00F4 ô
+    [0x00, 0x00, 0x0D, 0x12, 0x0E, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00F5 õ
+    [0x00, 0x00, 0x0A, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x0E, 0x00, 0x00, 0x00], // U+00F6 ö
+    [0x00, 0x00, 0x00, 0x04, 0x00, 0x1F, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00], // U+00F7 ÷
+    [0x00, 0x00, 0x00, 0x00, 0x0F, 0x13, 0x15, 0x19, 0x1E, 0x00, 0x00, 0x00], // U+00F8 ø
+    [0x00, 0x00, 0x08, 0x04, 0x11, 0x11, 0x11, 0x13, 0x0D, 0x00, 0x00, 0x00], // U+00F9 ù
+    [0x00, 0x00, 0x02, 0x04, 0x11, 0x11, 0x11, 0x13, 0x0D, 0x00, 0x00, 0x00], // U+00FA ú
+    [0x00, 0x00, 0x04, 0x0A, 0x11, 0x11, 0x11, 0x13, 0x0D, 0x00, 0x00, 0x00], // U+00FB û
+    [0x00, 0x00, 0x0A, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0D, 0x00, 0x00, 0x00], // U+00FC ü
+    [0x00, 0x00, 0x02, 0x04, 0x11, 0x11, 0x11, 0x11, 0x0F, 0x01, 0x0E, 0x00], // U+00FD ý
+    [0x00, 0x00, 0x10, 0x10, 0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x00, 0x00], // U+00FE þ
+    [0x00, 0x00, 0x0A, 0x00, 0x11, 0x11, 0x11, 0x11, 0x0F, 0x01, 0x0E, 0x00], // U+00FF ÿ
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn covers_ascii_and_latin1() {
+        let blank = ['\u{20}', '\u{A0}'];
+
+        for c in (0x21u8..=0x7E).chain(0xA1..=0xFF).map(char::from) {
+            if !blank.contains(&c) {
+                assert!(glyph(c).iter().any(|&row| row != 0), "empty glyph {:?}", c);
+            }
+        }
+
+        assert_eq!(glyph('\u{2603}'), glyph('?'));
+    }
+
+    #[test]
+    fn scales_by_whole_pixels() {
+        let font = BitmapFont::new();
+        let mut image = ImageBuffer::new(64, 32);
+
+        font.draw(&mut image, 0, 0, "µ·", 24, [255, 255, 255, 255]);
+
+        assert_eq!(font.measure("µ·", 24), (24, 24));
+        assert!(image.data.chunks_exact(4).all(|px| px[0] == 0 || px[0] == 255));
+    }
+}
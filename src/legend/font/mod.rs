@@ -0,0 +1,61 @@
+//! Text rasterization for legend labels.
+//!
+//! Legend commands carry plain strings.
+//! A `FontRasterizer` turns them into pixels during the overlay step.
+//!
+//! The built-in `BitmapFont` needs no font files and no dependencies,
+//! so legend text is pixel-identical on every platform.
+//! `TrueTypeFont` (cargo feature `truetype`) draws anti-aliased text
+//! from a user-supplied TTF / OTF file.
+
+pub mod bitmap;
+#[cfg(feature = "truetype")]
+pub mod truetype;
+
+use crate::render::ImageBuffer;
+
+pub use bitmap::BitmapFont;
+#[cfg(feature = "truetype")]
+pub use truetype::TrueTypeFont;
+
+/// Font weight.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum FontWeight {
+    #[default]
+    Regular,
+    Bold,
+}
+
+/// Font loading errors.
+#[derive(Debug)]
+pub enum FontError {
+    /// Font file could not be read
+    IoError,
+
+    /// Data is not a usable TrueType / OpenType font
+    InvalidFont,
+}
+
+/// Text rasterizer interface.
+///
+/// `size` is the line height in pixels (`LegendSettings::font_size`).
+///
+/// Implementations MUST be deterministic:
+/// same text + same size -> same pixels.
+pub trait FontRasterizer {
+    /// Width and height in pixels of `text` drawn at `size`.
+    fn measure(&self, text: &str, size: u32) -> (u32, u32);
+
+    /// Draw `text` with the top-left corner of its line box at (x, y).
+    ///
+    /// Pixels outside the image are clipped.
+    fn draw(
+        &self,
+        image: &mut ImageBuffer,
+        x: u32,
+        y: u32,
+        text: &str,
+        size: u32,
+        color: [u8; 4],
+    );
+}
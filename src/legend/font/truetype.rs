@@ -0,0 +1,489 @@
+//! TrueType / OpenType legend fonts (cargo feature `truetype`).
+//!
+//! Outlines are rasterized in software by `ab_glyph` into 8-bit coverage
+//! and blended with integer math. No system fonts, no hinting,
+//! no platform rasterizer: the same font file gives the same pixels everywhere.
+//!
+//! Kerning comes from the GPOS `kern` feature, or the legacy `kern` table
+//! for fonts without one. GPOS pairs are copied out once at load time.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use ab_glyph::{point, Font, FontVec, Glyph, GlyphId, PxScale, ScaleFont, VariableFont};
+use ttf_parser::gpos::{PairAdjustment, PositioningSubtable};
+use ttf_parser::{Face, Tag};
+
+use crate::legend::font::{FontError, FontRasterizer, FontWeight};
+use crate::render::ImageBuffer;
+
+/// Anti-aliased TrueType / OpenType font.
+///
+/// `size` is the line height in pixels (ascent + descent).
+pub struct TrueTypeFont {
+    font: FontVec,
+
+    /// GPOS `kern` subtables in lookup order, `None` without a GPOS
+    /// `kern` feature (the legacy table applies then)
+    gpos_kerning: Option<Vec<PairKerning>>,
+
+    /// Weight class of the (default) face
+    weight: u16,
+
+    /// Synthetic bold: static font without a bold face
+    embolden: bool,
+}
+
+impl TrueTypeFont {
+    /// Load a font from TTF / OTF data (first face of a collection).
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, FontError> {
+        let (gpos_kerning, weight) = {
+            let face = Face::parse(&data, 0).map_err(|_| FontError::InvalidFont)?;
+            let glyphs = mapped_glyphs(&face);
+
+            let kerning: Vec<PairKerning> = kern_lookups(&face)
+                .map(|pair| PairKerning::new(pair, &glyphs))
+                .collect();
+
+            ((!kerning.is_empty()).then_some(kerning), face.weight().to_number())
+        };
+
+        let font = FontVec::try_from_vec(data).map_err(|_| FontError::InvalidFont)?;
+
+        Ok(Self {
+            font,
+            gpos_kerning,
+            weight,
+            embolden: false,
+        })
+    }
+
+    /// Load a font from a TTF / OTF file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, FontError> {
+        let data = std::fs::read(path).map_err(|_| FontError::IoError)?;
+        Self::from_bytes(data)
+    }
+
+    /// Select a weight.
+    ///
+    /// Variable fonts use their `wght` axis (400 / 700).
+    /// Static regular faces are emboldened by one pixel for `Bold`.
+    pub fn with_weight(mut self, weight: FontWeight) -> Self {
+        let wght = match weight {
+            FontWeight::Regular => 400.0,
+            FontWeight::Bold => 700.0,
+        };
+
+        if !self.font.set_variation(b"wght", wght) {
+            self.embolden = weight == FontWeight::Bold && self.weight < 600;
+        }
+
+        self
+    }
+
+    /// Glyphs of `text` positioned on one line, and the pen advance.
+    ///
+    /// Glyph origins are relative to the top-left corner of the line box.
+    fn layout(&self, text: &str, size: u32) -> (Vec<Glyph>, f32) {
+        let scale = PxScale::from(size as f32);
+        let scaled = self.font.as_scaled(scale);
+
+        let mut glyphs = Vec::new();
+        let mut caret = 0.0f32;
+        let mut prev: Option<GlyphId> = None;
+
+        for c in text.chars() {
+            // Missing glyphs are drawn as "?" (same as the bitmap font)
+            let id = match scaled.glyph_id(c) {
+                GlyphId(0) => scaled.glyph_id('?'),
+                id => id,
+            };
+
+            if let Some(prev) = prev {
+                caret += match &self.gpos_kerning {
+                    Some(kerning) => {
+                        let adjust = kerning.iter().find_map(|pair| pair.get(prev.0, id.0));
+                        adjust.unwrap_or(0) as f32 * scaled.h_scale_factor()
+                    }
+                    None => scaled.kern(prev, id),
+                };
+            }
+
+            glyphs.push(id.with_scale_and_position(scale, point(caret, scaled.ascent())));
+            caret += scaled.h_advance(id);
+            prev = Some(id);
+        }
+
+        (glyphs, caret)
+    }
+}
+
+impl FontRasterizer for TrueTypeFont {
+    fn measure(&self, text: &str, size: u32) -> (u32, u32) {
+        let (_, advance) = self.layout(text, size);
+        (advance.ceil() as u32 + self.embolden as u32, size)
+    }
+
+    fn draw(
+        &self,
+        image: &mut ImageBuffer,
+        x: u32,
+        y: u32,
+        text: &str,
+        size: u32,
+        color: [u8; 4],
+    ) {
+        let (glyphs, _) = self.layout(text, size);
+        let outlines: Vec<_> = glyphs
+            .into_iter()
+            .filter_map(|g| self.font.outline_glyph(g))
+            .collect();
+
+        if outlines.is_empty() {
+            return;
+        }
+
+        // -----------------------------------------------------------------
+        // Coverage mask over the union of all glyph bounds
+        // -----------------------------------------------------------------
+        let left = outlines.iter().map(|o| o.px_bounds().min.x as i64).min().unwrap_or(0);
+        let top = outlines.iter().map(|o| o.px_bounds().min.y as i64).min().unwrap_or(0);
+        let right = outlines.iter().map(|o| o.px_bounds().max.x as i64).max().unwrap_or(0);
+        let bottom = outlines.iter().map(|o| o.px_bounds().max.y as i64).max().unwrap_or(0);
+
+        let width = (right - left) as usize + self.embolden as usize;
+        let height = (bottom - top) as usize;
+        let mut mask = vec![0u8; width * height];
+
+        for outline in &outlines {
+            let bounds = outline.px_bounds();
+            let ox = (bounds.min.x as i64 - left) as usize;
+            let oy = (bounds.min.y as i64 - top) as usize;
+
+            outline.draw(|gx, gy, coverage| {
+                let idx = (oy + gy as usize) * width + ox + gx as usize;
+                let value = (coverage.min(1.0) * 255.0).round() as u8;
+                mask[idx] = mask[idx].max(value);
+            });
+        }
+
+        // Synthetic bold: smear one pixel to the right
+        if self.embolden {
+            for row in mask.chunks_exact_mut(width) {
+                for i in (1..width).rev() {
+                    row[i] = row[i].max(row[i - 1]);
+                }
+            }
+        }
+
+        // -----------------------------------------------------------------
+        // Blend (integer "over" operator)
+        // -----------------------------------------------------------------
+        for (my, row) in mask.chunks_exact(width).enumerate() {
+            let py = y as i64 + top + my as i64;
+            if py < 0 || py >= image.height as i64 {
+                continue;
+            }
+
+            for (mx, &coverage) in row.iter().enumerate() {
+                let px = x as i64 + left + mx as i64;
+                if coverage == 0 || px < 0 || px >= image.width as i64 {
+                    continue;
+                }
+
+                let idx = (py as usize * image.width + px as usize) * 4;
+                blend(&mut image.data[idx..idx + 4], color, coverage);
+            }
+        }
+    }
+}
+
+/// Blend `color` over `dst` with `coverage` (0..=255).
+#[inline]
+fn blend(dst: &mut [u8], color: [u8; 4], coverage: u8) {
+    let a = coverage as u32 * color[3] as u32 / 255;
+
+    for i in 0..3 {
+        dst[i] = ((color[i] as u32 * a + dst[i] as u32 * (255 - a) + 127) / 255) as u8;
+    }
+    dst[3] = (a + dst[3] as u32 * (255 - a) / 255) as u8;
+}
+
+/// Pair positioning subtables of every GPOS `kern` feature.
+fn kern_lookups<'a>(face: &Face<'a>) -> impl Iterator<Item = PairAdjustment<'a>> {
+    let gpos = face.tables().gpos;
+    let kern = Tag::from_bytes(b"kern");
+
+    gpos.into_iter().flat_map(move |gpos| {
+        gpos.features
+            .into_iter()
+            .filter(move |feature| feature.tag == kern)
+            .flat_map(|feature| feature.lookup_indices)
+            .filter_map(move |index| gpos.lookups.get(index))
+            .flat_map(|lookup| lookup.subtables.into_iter::<PositioningSubtable>())
+            .filter_map(|subtable| match subtable {
+                PositioningSubtable::Pair(pair) => Some(pair),
+                _ => None,
+            })
+    })
+}
+
+/// Every glyph text can map to: Unicode cmap targets and `.notdef`.
+fn mapped_glyphs(face: &Face) -> Vec<ttf_parser::GlyphId> {
+    let mut glyphs = BTreeSet::from([0]);
+
+    let cmap = face.tables().cmap.into_iter().flat_map(|cmap| cmap.subtables);
+    for subtable in cmap.filter(|subtable| subtable.is_unicode()) {
+        subtable.codepoints(|c| glyphs.extend(subtable.glyph_index(c).map(|id| id.0)));
+    }
+
+    glyphs.into_iter().map(ttf_parser::GlyphId).collect()
+}
+
+/// One GPOS pair adjustment subtable, copied out of the font data.
+///
+/// Horizontal advance adjustments only, in font units.
+enum PairKerning {
+    /// Format 1: individual glyph pairs
+    Glyphs(HashMap<(u16, u16), i16>),
+
+    /// Format 2: class pairs; `first` holds the classes of covered glyphs,
+    /// `second` every non-zero second class
+    Classes {
+        first: HashMap<u16, u16>,
+        second: HashMap<u16, u16>,
+        columns: usize,
+        matrix: Vec<Option<i16>>,
+    },
+}
+
+impl PairKerning {
+    /// Copy the adjustments between `glyphs`, all glyphs a layout can meet.
+    fn new(pair: PairAdjustment, glyphs: &[ttf_parser::GlyphId]) -> Self {
+        let coverage = pair.coverage();
+        let covered = glyphs
+            .iter()
+            .filter_map(|&glyph| Some((glyph, coverage.get(glyph)?)));
+
+        match pair {
+            PairAdjustment::Format1 { sets, .. } => {
+                let mut pairs = HashMap::new();
+
+                for (first, index) in covered {
+                    let Some(set) = sets.get(index) else { continue };
+
+                    for &second in glyphs {
+                        if let Some((record, _)) = set.get(second) {
+                            pairs.insert((first.0, second.0), record.x_advance);
+                        }
+                    }
+                }
+
+                Self::Glyphs(pairs)
+            }
+
+            PairAdjustment::Format2 { classes, matrix, .. } => {
+                let first: HashMap<u16, u16> = covered
+                    .map(|(glyph, _)| (glyph.0, classes.0.get(glyph)))
+                    .collect();
+                let second: HashMap<u16, u16> = glyphs
+                    .iter()
+                    .map(|&glyph| (glyph.0, classes.1.get(glyph)))
+                    .filter(|&(_, class)| class != 0)
+                    .collect();
+
+                let rows = first.values().max().map_or(0, |&class| class as usize + 1);
+                let columns = second.values().max().map_or(0, |&class| class as usize) + 1;
+
+                let matrix = (0..rows * columns)
+                    .map(|i| {
+                        let classes = ((i / columns) as u16, (i % columns) as u16);
+                        matrix.get(classes).map(|(record, _)| record.x_advance)
+                    })
+                    .collect();
+
+                Self::Classes {
+                    first,
+                    second,
+                    columns,
+                    matrix,
+                }
+            }
+        }
+    }
+
+    /// Adjustment between two glyphs, `None` if this subtable has none.
+    fn get(&self, first: u16, second: u16) -> Option<i16> {
+        match self {
+            Self::Glyphs(pairs) => pairs.get(&(first, second)).copied(),
+            Self::Classes {
+                first: rows,
+                second: columns_of,
+                columns,
+                matrix,
+            } => {
+                let row = *rows.get(&first)? as usize;
+                let column = columns_of.get(&second).copied().unwrap_or(0) as usize;
+                matrix[row * columns + column]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Big-endian 16-bit words.
+    fn words(values: &[i32]) -> Vec<u8> {
+        values.iter().flat_map(|&v| (v as u16).to_be_bytes()).collect()
+    }
+
+    /// GPOS table with one `kern` feature running `pair_pos`.
+    fn gpos(pair_pos: &[u8]) -> Vec<u8> {
+        let mut table = words(&[1, 0, 10, 30, 44]);
+
+        // ScriptList: DFLT, default LangSys with feature 0
+        table.extend(words(&[1]));
+        table.extend(b"DFLT");
+        table.extend(words(&[8, 4, 0, 0, 0xFFFF, 1, 0]));
+
+        // FeatureList: kern -> lookup 0
+        table.extend(words(&[1]));
+        table.extend(b"kern");
+        table.extend(words(&[8, 0, 1, 0]));
+
+        // LookupList: one pair adjustment lookup
+        table.extend(words(&[1, 4, 2, 0, 1, 8]));
+        table.extend(pair_pos);
+        table
+    }
+
+    /// Tiny font: "A" and "V" as triangles, 600 units wide, 1000 per em.
+    fn font(gpos: Option<Vec<u8>>) -> Vec<u8> {
+        let glyph = |points: [(i32, i32); 3]| {
+            let mut glyph = words(&[1, 0, 0, 600, 700, 2, 0]);
+            glyph.extend([1, 1, 1]);
+
+            let mut last = (0, 0);
+            let deltas: Vec<(i32, i32)> = points
+                .iter()
+                .map(|&(x, y)| {
+                    let delta = (x - last.0, y - last.1);
+                    last = (x, y);
+                    delta
+                })
+                .collect();
+            glyph.extend(words(&deltas.iter().map(|d| d.0).collect::<Vec<_>>()));
+            glyph.extend(words(&deltas.iter().map(|d| d.1).collect::<Vec<_>>()));
+            glyph.push(0);
+            glyph
+        };
+
+        let mut glyf = glyph([(0, 0), (300, 700), (600, 0)]);
+        glyf.extend(glyph([(0, 700), (600, 700), (300, 0)]));
+
+        let mut cmap = words(&[0, 1, 3, 10, 0, 12, 12, 0, 0, 40, 0, 0, 0, 2]);
+        cmap.extend(words(&[0, 65, 0, 65, 0, 1, 0, 86, 0, 86, 0, 2]));
+
+        let mut head = words(&[1, 0, 0, 0, 0, 0, 0x5F0F, 0x3CF5, 0, 1000]);
+        head.extend([0; 16]);
+        head.extend(words(&[0, 0, 600, 700, 0, 8, 2, 0, 0]));
+
+        let mut hhea = words(&[1, 0, 800, -200, 0, 600, 0, 0, 600, 1, 0, 0]);
+        hhea.extend(words(&[0, 0, 0, 0, 0, 3]));
+
+        let mut tables = vec![
+            (b"cmap", cmap),
+            (b"glyf", glyf),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", words(&[600, 0, 600, 0, 600, 0])),
+            (b"loca", words(&[0, 0, 15, 30])),
+            (b"maxp", words(&[0, 0x5000, 3])),
+        ];
+        if let Some(gpos) = gpos {
+            tables.insert(0, (b"GPOS", gpos));
+        }
+
+        let mut data = words(&[1, 0, tables.len() as i32, 0, 0, 0]);
+        let mut offset = 12 + 16 * tables.len();
+
+        for (tag, table) in &tables {
+            data.extend(*tag);
+            data.extend([0; 4]);
+            data.extend((offset as u32).to_be_bytes());
+            data.extend((table.len() as u32).to_be_bytes());
+            offset += table.len().next_multiple_of(4);
+        }
+        for (_, table) in tables {
+            data.resize(data.len() + table.len().next_multiple_of(4), 0);
+            let end = data.len() - table.len().next_multiple_of(4);
+            data[end..end + table.len()].copy_from_slice(&table);
+        }
+
+        data
+    }
+
+    /// Both pair adjustment formats, "AV" moved together by 200 units.
+    fn kerned_fonts() -> Vec<TrueTypeFont> {
+        // Format 1: coverage {A}, pair set {V: -200}
+        let mut glyph_pairs = words(&[1, 18, 4, 0, 1, 12, 1, 2, -200]);
+        glyph_pairs.extend(words(&[1, 1, 1]));
+
+        // Format 2: class 1 = {A} x class 1 = {V}
+        let mut class_pairs = words(&[2, 24, 4, 0, 30, 38, 2, 2, 0, 0, 0, -200]);
+        class_pairs.extend(words(&[1, 1, 1, 1, 1, 1, 1, 1, 2, 1, 1]));
+
+        [glyph_pairs, class_pairs]
+            .into_iter()
+            .map(|pair_pos| TrueTypeFont::from_bytes(font(Some(gpos(&pair_pos)))).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn rejects_invalid_data() {
+        let result = TrueTypeFont::from_bytes(b"not a font".to_vec());
+        assert!(matches!(result, Err(FontError::InvalidFont)));
+    }
+
+    #[test]
+    fn gpos_pairs_are_kerned() {
+        // 20 px line height = 1000 units: 600 units advance 12 px
+        for font in kerned_fonts() {
+            assert_eq!(font.measure("A", 20), (12, 20));
+            assert_eq!(font.measure("AV", 20), (20, 20));
+            assert_eq!(font.measure("VA", 20), (24, 20));
+            assert_eq!(font.measure("AVAV", 20), (40, 20));
+        }
+
+        let plain = TrueTypeFont::from_bytes(font(None)).unwrap();
+        assert_eq!(plain.measure("AV", 20), (24, 20));
+    }
+
+    #[test]
+    fn draws_deterministic_anti_aliased_glyphs() {
+        let draw = |font: &TrueTypeFont| {
+            let mut image = ImageBuffer::new(32, 24);
+            font.draw(&mut image, 2, 2, "AV", 20, [255, 255, 255, 255]);
+            image
+        };
+
+        for font in kerned_fonts() {
+            let image = draw(&font);
+            assert_eq!(image.data, draw(&font).data);
+
+            // Solid interior, partial coverage along the slanted edges
+            let red: Vec<u8> = image.data.iter().step_by(4).copied().collect();
+            assert!(red.contains(&255));
+            assert!(red.iter().any(|&v| v > 0 && v < 255));
+
+            // Kerned: "V" ends 4 px before its unkerned position
+            let columns: Vec<usize> = (0..image.width)
+                .filter(|x| (0..image.height).any(|y| red[y * image.width + x] > 0))
+                .collect();
+            assert_eq!(columns.first(), Some(&2));
+            assert_eq!(columns.last(), Some(&21));
+        }
+    }
+}
@@ -5,12 +5,20 @@
 //!
 //! The legend is ALWAYS rendered and never optional.
 
+use crate::analysis::IntensityScale;
 use crate::audio::AudioMetadata;
+use crate::legend::font::FontRasterizer;
+use crate::render::FrequencyScale;
 
+pub mod compositor;
+pub mod font;
 pub mod overlay;
 pub mod simple;
+pub mod ticks;
 
 /// Legend layout margins (in pixels).
+///
+/// Space around the spectrogram area, computed by `LegendRenderer::layout`.
 #[derive(Debug, Copy, Clone)]
 pub struct LegendMargins {
     pub left: u32,
@@ -26,16 +34,16 @@ pub struct LegendMargins {
 /// There is NO disable flag by design.
 #[derive(Debug, Clone)]
 pub struct LegendSettings {
-    /// Font size in pixels (logical size, renderer-defined)
+    /// Font size in pixels (line height, see `font::FontRasterizer`)
     pub font_size: u32,
 
-    /// Number of frequency ticks
+    /// Upper bound for frequency ticks per axis height
     pub freq_ticks: usize,
 
-    /// Number of time ticks
+    /// Upper bound for time ticks across the plot width
     pub time_ticks: usize,
 
-    /// Number of dB ticks
+    /// Upper bound for dB ticks
     pub db_ticks: usize,
 }
 
@@ -56,15 +64,39 @@ pub struct LegendContext {
     /// Audio metadata (sample rate, channels, bit depth)
     pub audio: AudioMetadata,
 
-    /// Total duration in seconds
+    /// Total duration in seconds (of the excerpt, if cropped)
     pub duration_sec: f64,
 
+    /// Absolute time of the left edge, in seconds (excerpt start)
+    pub time_offset: f64,
+
     /// Minimum dBFS shown (e.g. -120.0)
     pub min_db: f32,
 
     /// Maximum dBFS shown (usually 0.0)
     pub max_db: f32,
 
+    /// Intensity scale of the spectrogram (shapes the dBFS gradient)
+    pub scale: IntensityScale,
+
+    /// Spectrogram panels, top to bottom, with optional labels.
+    ///
+    /// Panel geometry matches the renderer:
+    /// panel `i` spans rows `i * h / n .. (i + 1) * h / n`.
+    pub panels: Vec<Option<String>>,
+
+    /// Frequency at the bottom of every panel, in Hz
+    pub min_freq: f64,
+
+    /// Frequency at the top of every panel (Nyquist unless cropped), in Hz
+    pub max_freq: f64,
+
+    /// Frequency axis scale, identical to the renderer's
+    pub freq_scale: FrequencyScale,
+
+    /// Detected lowpass cutoff to mark on the frequency axis, in Hz
+    pub cutoff: Option<f64>,
+
     /// Display name of the input audio file.
     ///
     /// This value is optional and purely informational.
@@ -91,11 +123,14 @@ pub struct LegendContext {
 /// rendered by any text / vector backend.
 #[derive(Debug, Clone)]
 pub enum LegendCommand {
-    /// Draw text at pixel position.
+    /// Draw text with the top-left corner of its line box at pixel position.
     Text {
         x: u32,
         y: u32,
         content: String,
+
+        /// Line height in pixels
+        size: u32,
     },
 
     /// Draw a straight line.
@@ -111,12 +146,19 @@ pub enum LegendCommand {
     /// This is semantically NOT a line.
     /// It represents a continuous color scale from max → min dBFS.
     ///
-    /// - `y_top` corresponds to max dBFS (bright)
-    /// - `y_bottom` corresponds to min dBFS (dark)
+    /// - `y_top` corresponds to `max_db` (bright)
+    /// - `y_bottom` corresponds to `min_db` (dark)
+    ///
+    /// Levels map through the same intensity scale and color mapper
+    /// as the spectrogram.
     DbfsGradient {
         x: u32,
+        width: u32,
         y_top: u32,
         y_bottom: u32,
+        min_db: f32,
+        max_db: f32,
+        scale: IntensityScale,
     },
 }
 
@@ -131,11 +173,27 @@ pub enum LegendCommand {
 /// The renderer:
 /// - MUST NOT modify context
 /// - MUST NOT access audio or render state directly
+///
+/// `font` is the rasterizer that will draw the text commands,
+/// so label extents can be measured exactly.
 pub trait LegendRenderer {
+    /// Margins needed around a `plot_width` × `plot_height` spectrogram.
+    ///
+    /// The spectrogram keeps its size, the image grows to fit the legend.
+    fn layout(
+        &self,
+        settings: &LegendSettings,
+        context: &LegendContext,
+        font: &dyn FontRasterizer,
+        plot_width: u32,
+        plot_height: u32,
+    ) -> LegendMargins;
+
     fn generate(
         &self,
         settings: &LegendSettings,
         context: &LegendContext,
+        font: &dyn FontRasterizer,
         margins: LegendMargins,
         image_width: u32,
         image_height: u32,
@@ -4,20 +4,32 @@
 //! This module does NOT generate legend content.
 //! It only executes drawing commands.
 
+use crate::analysis::fft::db_to_intensity;
+use crate::analysis::IntensityScale;
+use crate::color::ColorMapper;
+use crate::legend::font::FontRasterizer;
 use crate::legend::LegendCommand;
 use crate::render::ImageBuffer;
 
 /// Apply legend commands onto an RGBA image buffer.
 ///
 /// The image buffer is modified in-place.
+///
+/// `color` MUST be the same mapper used to render the spectrogram,
+/// so the dBFS gradient matches the plot exactly.
+///
+/// `font` rasterizes all text commands.
 pub fn apply_legend_overlay(
     image: &mut ImageBuffer,
     commands: &[LegendCommand],
+    color: &dyn ColorMapper,
+    font: &dyn FontRasterizer,
 ) {
     for cmd in commands {
         match cmd {
-            LegendCommand::Text { x, y, content } => {
-                draw_text_stub(image, *x, *y, content);
+            LegendCommand::Text { x, y, content, size } => {
+                // Plain white text, same as the axes
+                font.draw(image, *x, *y, content, *size, [255, 255, 255, 255]);
             }
 
             LegendCommand::Line { x1, y1, x2, y2 } => {
@@ -25,8 +37,17 @@ pub fn apply_legend_overlay(
                 draw_line(image, *x1, *y1, *x2, *y2);
             }
 
-            LegendCommand::DbfsGradient { x, y_top, y_bottom } => {
-                draw_dbfs_gradient(image, *x, *y_top, *y_bottom);
+            LegendCommand::DbfsGradient {
+                x,
+                width,
+                y_top,
+                y_bottom,
+                min_db,
+                max_db,
+                scale,
+            } => {
+                let bar = (*x, *width, *y_top, *y_bottom);
+                draw_dbfs_gradient(image, bar, *min_db, *max_db, *scale, color);
             }
         }
     }
@@ -34,16 +55,24 @@ pub fn apply_legend_overlay(
 
 /// Draw a vertical dBFS gradient bar (Spek-style).
 ///
+/// `bar` is (x, width, y_top, y_bottom).
+///
 /// Semantic guarantees:
-/// - Top = 0 dBFS (bright)
-/// - Bottom = min dBFS (dark)
+/// - Top = `max_db` (bright)
+/// - Bottom = `min_db` (dark)
+/// - Each row has the exact color of its level in the spectrogram
+///   (same intensity scale, noise cutoff and color mapper)
 /// - Does NOT touch any other pixels
 fn draw_dbfs_gradient(
     image: &mut ImageBuffer,
-    x: u32,
-    y_top: u32,
-    y_bottom: u32,
+    bar: (u32, u32, u32, u32),
+    min_db: f32,
+    max_db: f32,
+    scale: IntensityScale,
+    color: &dyn ColorMapper,
 ) {
+    let (x, width, y_top, y_bottom) = bar;
+
     let (start, end) = if y_top <= y_bottom {
         (y_top, y_bottom)
     } else {
@@ -54,36 +83,13 @@ fn draw_dbfs_gradient(
 
     for y in start..=end {
         let t = (y - start) as f32 / height;
-        let a = 1.0 - t; // top = bright
+        let db = max_db - (max_db - min_db) * t; // top = bright
 
-        // Spek-like pseudo-thermal palette
-        let (r, g, b) = spek_palette(a);
+        let [r, g, b, alpha] = color.map(db_to_intensity(db, min_db, max_db, scale));
 
-        put_pixel(image, x, y, r, g, b, 255);
-    }
-}
-
-/// Spek-style palette approximation (matches visual behavior)
-#[inline]
-fn spek_palette(a: f32) -> (u8, u8, u8) {
-    let a = a.clamp(0.0, 1.0);
-
-    if a < 0.25 {
-        // black → blue
-        let t = a / 0.25;
-        (0, 0, (255.0 * t) as u8)
-    } else if a < 0.5 {
-        // blue → cyan
-        let t = (a - 0.25) / 0.25;
-        (0, (255.0 * t) as u8, 255)
-    } else if a < 0.75 {
-        // cyan → yellow
-        let t = (a - 0.5) / 0.25;
-        ((255.0 * t) as u8, 255, (255.0 * (1.0 - t)) as u8)
-    } else {
-        // yellow → white
-        let t = (a - 0.75) / 0.25;
-        (255, 255, (255.0 * t) as u8)
+        for px in x..x + width.max(1) {
+            put_pixel(image, px, y, r, g, b, alpha);
+        }
     }
 }
 
@@ -125,19 +131,6 @@ fn draw_line(
     }
 }
 
-/// Placeholder text renderer.
-///
-/// Real font rasterization will be injected later.
-/// This keeps spek-core font-backend-agnostic.
-fn draw_text_stub(
-    _image: &mut ImageBuffer,
-    _x: u32,
-    _y: u32,
-    _text: &str,
-) {
-    // intentionally empty
-}
-
 /// Write a single RGBA pixel.
 #[inline]
 fn put_pixel(
@@ -161,3 +154,45 @@ fn put_pixel(
         image.data[idx + 3] = a;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::{Palette, SaturatedColorMapper};
+    use crate::legend::font::BitmapFont;
+
+    #[test]
+    fn gradient_rows_match_plot_colors() {
+        let (min_db, max_db) = (-120.0, 0.0);
+        let scale = IntensityScale::Sqrt;
+
+        for saturation in [1.0, 0.4, 1.8] {
+            let color = SaturatedColorMapper::new(Palette::Spek.mapper(), saturation);
+
+            // 100 px bar: one row per 1.2 dB
+            let mut image = ImageBuffer::new(8, 101);
+            let bar = LegendCommand::DbfsGradient {
+                x: 2,
+                width: 4,
+                y_top: 0,
+                y_bottom: 100,
+                min_db,
+                max_db,
+                scale,
+            };
+            apply_legend_overlay(&mut image, &[bar], &color, &BitmapFont::new());
+
+            for (y, db) in [(0, 0.0), (25, -30.0), (50, -60.0), (75, -90.0), (100, -120.0)] {
+                let expected = color.map(db_to_intensity(db, min_db, max_db, scale));
+
+                for x in 2..6 {
+                    let at = (y * image.width + x) * 4;
+                    assert_eq!(image.data[at..at + 4], expected, "{} dB", db);
+                }
+            }
+
+            // Outside the bar: untouched
+            assert_eq!(image.data[..4], [0, 0, 0, 255]);
+        }
+    }
+}
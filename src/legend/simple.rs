@@ -4,10 +4,24 @@
 //! as abstract drawing commands.
 //!
 //! It does NOT rasterize fonts and does NOT touch pixels.
+//! Label extents are measured with the active `FontRasterizer`,
+//! so margins and label positions follow the real font metrics.
 
+use crate::legend::font::FontRasterizer;
+use crate::legend::ticks;
 use crate::legend::{
     LegendCommand, LegendContext, LegendMargins, LegendRenderer, LegendSettings,
 };
+use crate::render::FrequencyScale;
+
+/// Tick length in pixels
+const TICK: u32 = 6;
+
+/// Space between ticks, labels and the gradient bar
+const GAP: u32 = 4;
+
+/// Empty border around the whole image
+const PAD: u32 = 8;
 
 /// Default legend renderer (Spek-style).
 ///
@@ -16,10 +30,13 @@ use crate::legend::{
 /// - Time axis (bottom) with labels + top ticks without labels
 /// - Frequency axis (left + right ticks, labels left only)
 /// - dBFS scale (right)
+/// - Ticks on round values (1-2-5 steps), overlapping labels dropped
 /// - dBFS vertical gradient (semantic, backend-agnostic)
-/// - Correct multi-channel split handling
+/// - One labeled frequency axis per spectrogram panel
+/// - Optional lowpass cutoff marker
 ///
 /// All output is deterministic and resolution-independent.
+#[derive(Debug, Default)]
 pub struct SimpleLegendRenderer;
 
 impl SimpleLegendRenderer {
@@ -29,16 +46,75 @@ impl SimpleLegendRenderer {
 }
 
 impl LegendRenderer for SimpleLegendRenderer {
+    fn layout(
+        &self,
+        settings: &LegendSettings,
+        context: &LegendContext,
+        font: &dyn FontRasterizer,
+        plot_width: u32,
+        plot_height: u32,
+    ) -> LegendMargins {
+        let size = settings.font_size;
+        let width = |s: &str| font.measure(s, size).0;
+        let line_height = font.measure("0", size).1;
+
+        // Time labels centered on their tick may overhang the plot edges
+        let times = time_labels(settings, context, font, plot_width);
+        let overhang = |edge: fn(f64) -> f64| {
+            times
+                .iter()
+                .map(|(t, s)| (width(s) / 2).saturating_sub((plot_width as f64 * edge(*t)) as u32))
+                .max()
+                .unwrap_or(0)
+        };
+        let (overhang_left, overhang_right) = (overhang(|t| t), overhang(|t| 1.0 - t));
+
+        // Left: frequency labels, overhanging time labels
+        let freq_width = panel_heights(context, plot_height)
+            .flat_map(|h| freq_labels(settings, context, font, plot_height, h))
+            .map(|(_, s)| width(&s))
+            .max()
+            .unwrap_or(0);
+
+        let left = (PAD + freq_width + GAP + TICK).max(PAD + overhang_left);
+
+        // Right: dB labels + gradient bar, overhanging time labels
+        let db_width = db_label_width(settings, context, font, plot_height);
+        let right =
+            (TICK + GAP + db_width + GAP + bar_width(line_height) + PAD).max(PAD + overhang_right);
+
+        // Top: header rows above the top ticks
+        let header = header(context, font, size, left, left + plot_width, left + plot_width + right);
+        let rows = header.iter().map(|&(_, row, _)| row + 1).max().unwrap_or(0);
+
+        let top = PAD + rows * (line_height + GAP) + TICK;
+
+        // Bottom: time labels, then the "Time" / "dBFS" titles
+        let bottom = TICK + GAP + line_height + GAP + line_height + PAD;
+
+        LegendMargins {
+            left,
+            right,
+            top,
+            bottom,
+        }
+    }
+
     fn generate(
         &self,
         settings: &LegendSettings,
         context: &LegendContext,
+        font: &dyn FontRasterizer,
         margins: LegendMargins,
         image_width: u32,
         image_height: u32,
     ) -> Vec<LegendCommand> {
         let mut cmds = Vec::new();
 
+        let size = settings.font_size;
+        let width = |s: &str| font.measure(s, size).0;
+        let line_height = font.measure("0", size).1;
+
         let left = margins.left;
         let right = image_width - margins.right;
         let top = margins.top;
@@ -47,43 +123,12 @@ impl LegendRenderer for SimpleLegendRenderer {
         // -----------------------------------------------------------------
         // Header (optional)
         // -----------------------------------------------------------------
-        let header_y = top.saturating_sub(settings.font_size + 8);
+        let header = header(context, font, size, left, right, image_width);
+        let rows = header.iter().map(|&(_, row, _)| row + 1).max().unwrap_or(0);
+        let header_y = top.saturating_sub(TICK + rows * (line_height + GAP));
 
-        if let Some(file_name) = &context.file_name {
-            cmds.push(text(left, header_y, file_name));
-        }
-
-        let channel_str = match context.audio.channels {
-            1 => "Mono".to_string(),
-            2 => "Stereo".to_string(),
-            n => format!("{} ch", n),
-        };
-
-        let bit_depth_str = context
-            .audio
-            .bit_depth
-            .map(|b| format!("{}-bit", b))
-            .unwrap_or_else(|| "unknown bit".to_string());
-
-        let audio_info = format!(
-            "{} Hz · {} · {}",
-            context.audio.sample_rate,
-            channel_str,
-            bit_depth_str
-        );
-
-        cmds.push(text(
-            (left + right) / 2 - 80,
-            header_y,
-            &audio_info,
-        ));
-
-        if let Some(app_version) = &context.app_version {
-            cmds.push(text(
-                right.saturating_sub(140),
-                header_y,
-                app_version,
-            ));
+        for (x, row, content) in header {
+            cmds.push(text(x, header_y + row * (line_height + GAP), &content, size));
         }
 
         // -----------------------------------------------------------------
@@ -94,139 +139,519 @@ impl LegendRenderer for SimpleLegendRenderer {
         cmds.push(line(right, top, right, bottom));
 
         // -----------------------------------------------------------------
-        // Time axis (top ticks + bottom labels)
+        // Time axis (top ticks + bottom labels, centered on their tick)
         //
         // Spek-compatible time format:
         // `m:ss` with minutes NOT capped at 59
         // -----------------------------------------------------------------
-        for i in 0..=settings.time_ticks {
-            let t = i as f64 / settings.time_ticks as f64;
+        let label_y = bottom + TICK + GAP;
+
+        for (t, label) in time_labels(settings, context, font, right - left) {
             let x = left + ((right - left) as f64 * t) as u32;
 
             // Bottom ticks
-            cmds.push(line(x, bottom, x, bottom + 6));
+            cmds.push(line(x, bottom, x, bottom + TICK));
             // Top ticks (no labels)
-            cmds.push(line(x, top.saturating_sub(6), x, top));
+            cmds.push(line(x, top.saturating_sub(TICK), x, top));
 
-            let total_seconds = context.duration_sec * t;
-            cmds.push(text(
-                x.saturating_sub(14),
-                bottom + 10,
-                &format_time_m_ss(total_seconds),
-            ));
+            cmds.push(text(x.saturating_sub(width(&label) / 2), label_y, &label, size));
         }
 
+        let title_y = label_y + line_height + GAP;
+
         cmds.push(text(
-            (left + right) / 2 - 18,
-            bottom + 28,
+            ((left + right) / 2).saturating_sub(width("Time") / 2),
+            title_y,
             "Time",
+            size,
         ));
 
         // -----------------------------------------------------------------
-        // Frequency axis (Spek-accurate)
+        // Frequency axis (Spek-accurate, labels right-aligned to the ticks)
         // -----------------------------------------------------------------
-        let nyquist = context.audio.sample_rate as f64 / 2.0;
+        let min_freq = context.min_freq;
+        let max_freq = context.max_freq;
+        let scale = context.freq_scale;
 
         let height = bottom - top;
-        let max_ticks = settings.freq_ticks.max(2);
-        let tick_step = (height / max_ticks as u32).max(24);
 
-        let split_channels = context.audio.channels > 1;
-        let channel_count = if split_channels { 2 } else { 1 };
-        let channel_height = height / channel_count;
+        // No panel info: one unlabeled panel
+        let panels: &[Option<String>] = match context.panels.len() {
+            0 => &[None],
+            _ => &context.panels,
+        };
+        let panel_count = panels.len() as u32;
 
-        for ch in 0..channel_count {
-            let ch_top = top + ch * channel_height;
-            let ch_bottom = ch_top + channel_height;
+        for (ch, label) in (0..panel_count).zip(panels) {
+            // Same integer geometry as the renderer
+            let ch_top = top + ch * height / panel_count;
+            let ch_bottom = top + (ch + 1) * height / panel_count;
+            let channel_height = ch_bottom - ch_top;
 
-            let ticks = (channel_height / tick_step).max(2);
+            // Panel separator
+            if ch > 0 {
+                cmds.push(line(left, ch_top, right, ch_top));
+            }
 
-            for i in 0..=ticks {
-                let f = i as f64 / ticks as f64;
+            if let Some(label) = label {
+                cmds.push(text(left + GAP, ch_top + GAP, label, size));
+            }
 
-                // 0 kHz at bottom, Nyquist at top
+            for (f, label) in freq_labels(settings, context, font, height, channel_height) {
+                // min_freq (or the log floor) at bottom, max_freq at top
                 let y = ch_bottom.saturating_sub(
                     ((channel_height as f64) * f).round() as u32,
                 );
 
-                let freq = nyquist * f;
-
-                // Skip Nyquist label on lower channel (Spek behavior)
-                if split_channels && ch == 1 && i == ticks {
+                // Skip Nyquist label on lower panels (Spek behavior)
+                if ch > 0 && f >= 1.0 {
                     continue;
                 }
 
-                cmds.push(line(left - 6, y, left, y));
-                cmds.push(line(right, y, right + 6, y));
-
-                let label = if (freq % 1000.0).abs() < 1.0 {
-                    format!("{:.0} kHz", freq / 1000.0)
-                } else {
-                    format!("{:.1} kHz", freq / 1000.0)
-                };
+                cmds.push(line(left - TICK, y, left, y));
+                cmds.push(line(right, y, right + TICK, y));
 
                 cmds.push(text(
-                    4,
-                    y.saturating_sub(settings.font_size / 2),
+                    (left - TICK - GAP).saturating_sub(width(&label)),
+                    y.saturating_sub(line_height / 2),
                     &label,
+                    size,
+                ));
+            }
+
+            // Detected lowpass: long ticks reaching into the plot
+            if let Some(cutoff) = context.cutoff.filter(|&c| c > min_freq && c < max_freq) {
+                let f = scale.to_unit(cutoff, min_freq, max_freq);
+                let y = ch_bottom.saturating_sub(((channel_height as f64) * f).round() as u32);
+
+                cmds.push(line(left - TICK, y, left + 2 * TICK, y));
+                cmds.push(line(right - 2 * TICK, y, right + TICK, y));
+                cmds.push(text(
+                    left + 2 * TICK + GAP,
+                    y.saturating_sub(line_height + 2),
+                    &format!("cutoff {}", format_hz(cutoff)),
+                    size,
                 ));
             }
         }
 
         // -----------------------------------------------------------------
-        // dBFS gradient (semantic command)
+        // dBFS gradient (semantic command), right of the dB labels
         //
         // NO heuristics
         // NO fake lines
         // EXACTLY one semantic gradient
         // -----------------------------------------------------------------
-        let gradient_x = right + 34;
+        let db_x = right + TICK + GAP;
+        let gradient_x = db_x + db_label_width(settings, context, font, bottom - top) + GAP;
 
         cmds.push(LegendCommand::DbfsGradient {
             x: gradient_x,
+            width: bar_width(line_height),
             y_top: top,
             y_bottom: bottom,
+            min_db: context.min_db,
+            max_db: context.max_db,
+            scale: context.scale,
         });
 
         // -----------------------------------------------------------------
         // dBFS scale ticks + labels
         // -----------------------------------------------------------------
-        let db_range = context.max_db - context.min_db;
-
-        for i in 0..=settings.db_ticks {
-            let t = i as f32 / settings.db_ticks as f32;
+        for (t, label) in db_labels(settings, context, font, bottom - top) {
             let y = bottom - ((bottom - top) as f32 * t) as u32;
-            let db = context.min_db + db_range * t;
 
-            cmds.push(line(right, y, right + 6, y));
-            cmds.push(text(
-                right + 10,
-                y.saturating_sub(settings.font_size / 2),
-                &format!("{:.0}", db),
-            ));
+            cmds.push(line(right, y, right + TICK, y));
+            cmds.push(text(db_x, y.saturating_sub(line_height / 2), &label, size));
         }
 
-        cmds.push(text(
-            right + 10,
-            bottom + 28,
-            "dBFS",
-        ));
+        cmds.push(text(db_x, title_y, "dBFS", size));
 
         cmds
     }
 }
 
+// -------------------------------------------------------------------------
+// Label sets (shared by layout and generate)
+// -------------------------------------------------------------------------
+
+/// Header texts as (x, row, text).
+///
+/// One row when file name, audio info and version fit side by side
+/// (left-aligned, centered and right-aligned over the plot),
+/// otherwise one row each, left-aligned and shortened with "...".
+fn header(
+    context: &LegendContext,
+    font: &dyn FontRasterizer,
+    size: u32,
+    left: u32,
+    right: u32,
+    image_width: u32,
+) -> Vec<(u32, u32, String)> {
+    let width = |s: &str| font.measure(s, size).0;
+
+    let channel_str = match context.audio.channels {
+        1 => "Mono".to_string(),
+        2 => "Stereo".to_string(),
+        6 => "5.1".to_string(),
+        8 => "7.1".to_string(),
+        n => format!("{} ch", n),
+    };
+
+    // e.g. "24-bit FLAC"
+    let format_str = match (context.audio.bit_depth, &context.audio.codec) {
+        (Some(b), Some(codec)) => format!("{}-bit {}", b, codec),
+        (Some(b), None) => format!("{}-bit", b),
+        (None, Some(codec)) => codec.clone(),
+        (None, None) => "unknown bit".to_string(),
+    };
+
+    let audio_info = format!(
+        "{} Hz · {} · {}",
+        context.audio.sample_rate,
+        channel_str,
+        format_str
+    );
+
+    // Single row: file | info (centered) | version
+    let info_x = ((left + right) / 2).saturating_sub(width(&audio_info) / 2);
+    let info_end = info_x + width(&audio_info);
+
+    let file_fits = match &context.file_name {
+        Some(f) => left + width(f) + 2 * GAP <= info_x,
+        None => info_x >= PAD,
+    };
+
+    let version_x = context
+        .app_version
+        .as_ref()
+        .map_or(right, |v| right.saturating_sub(width(v)));
+
+    let version_fits = match &context.app_version {
+        Some(_) => info_end + 2 * GAP <= version_x,
+        None => info_end + PAD <= image_width,
+    };
+
+    if file_fits && version_fits {
+        let mut row = Vec::new();
+
+        if let Some(file_name) = &context.file_name {
+            row.push((left, 0, file_name.clone()));
+        }
+        row.push((info_x, 0, audio_info));
+        if let Some(app_version) = &context.app_version {
+            row.push((version_x, 0, app_version.clone()));
+        }
+
+        return row;
+    }
+
+    // Stacked rows
+    let max_width = image_width.saturating_sub(left + PAD);
+
+    context
+        .file_name
+        .iter()
+        .cloned()
+        .chain(Some(audio_info))
+        .chain(context.app_version.iter().cloned())
+        .enumerate()
+        .map(|(row, s)| (left, row as u32, shorten(font, size, &s, max_width)))
+        .collect()
+}
+
+/// Time tick positions (0.0..1.0) and absolute `m:ss` labels.
+///
+/// Ticks sit on round times (see `ticks::time_step`) at least one label
+/// width apart, and at most `time_ticks` across the axis.
+fn time_labels(
+    settings: &LegendSettings,
+    context: &LegendContext,
+    font: &dyn FontRasterizer,
+    plot_width: u32,
+) -> Vec<(f64, String)> {
+    // Absolute time: a cropped excerpt keeps its file position
+    let start = context.time_offset;
+    let duration = context.duration_sec;
+    let end = start + duration;
+
+    if duration <= 0.0 || plot_width == 0 {
+        return vec![(0.0, format_time_m_ss(start, 0))];
+    }
+
+    let seconds_per_px = duration / plot_width as f64;
+    let min_px = (plot_width / settings.time_ticks.max(1) as u32) as f64;
+
+    // The widest label depends on the step (decimals below one second)
+    let mut step = 0.0;
+    let mut decimals = 0;
+    for _ in 0..2 {
+        let widest = font.measure(&format_time_m_ss(end, decimals), settings.font_size).0;
+        step = ticks::time_step(min_px.max((widest + 4 * GAP) as f64) * seconds_per_px);
+        decimals = ticks::decimals(step);
+    }
+
+    let labels: Vec<(f64, String)> = ticks::multiples(start, end, step)
+        .into_iter()
+        .map(|t| ((t - start) / duration, format_time_m_ss(t, decimals)))
+        .collect();
+
+    let spans: Vec<(i64, i64)> = labels
+        .iter()
+        .map(|(t, s)| {
+            let x = (plot_width as f64 * t) as i64;
+            centered(x, font.measure(s, settings.font_size).0)
+        })
+        .collect();
+
+    keep(labels, &spans, &vec![false; spans.len()])
+}
+
+/// Frequency tick positions (0.0..1.0 within the panel) and labels.
+fn freq_labels(
+    settings: &LegendSettings,
+    context: &LegendContext,
+    font: &dyn FontRasterizer,
+    plot_height: u32,
+    channel_height: u32,
+) -> Vec<(f64, String)> {
+    let line_height = font.measure("0", settings.font_size).1;
+
+    match context.freq_scale {
+        FrequencyScale::Linear => linear_ticks(
+            context.min_freq,
+            context.max_freq,
+            channel_height,
+            (2 * line_height).max(plot_height / settings.freq_ticks.max(2) as u32),
+            line_height,
+        ),
+        scale => nice_ticks(
+            scale,
+            context.min_freq,
+            context.max_freq,
+            channel_height,
+            line_height,
+        ),
+    }
+}
+
+/// dB tick positions (0.0 bottom ..1.0 top) and labels.
+///
+/// Round dB values (1-2-5 × 10ⁿ steps), at most `db_ticks` + 1,
+/// overlapping labels dropped.
+fn db_labels(
+    settings: &LegendSettings,
+    context: &LegendContext,
+    font: &dyn FontRasterizer,
+    plot_height: u32,
+) -> Vec<(f32, String)> {
+    let db_range = context.max_db - context.min_db;
+    if db_range <= 0.0 || plot_height == 0 {
+        return Vec::new();
+    }
+
+    let line_height = font.measure("0", settings.font_size).1;
+    let min_px = (2 * line_height).max(plot_height / settings.db_ticks.max(1) as u32);
+
+    let step = ticks::nice_step(min_px as f64 * db_range as f64 / plot_height as f64);
+    let decimals = ticks::decimals(step);
+
+    let labels: Vec<(f32, String)> =
+        ticks::multiples(context.min_db as f64, context.max_db as f64, step)
+            .into_iter()
+            .map(|db| {
+                let t = ((db - context.min_db as f64) / db_range as f64) as f32;
+                // "-0" reads odd next to the other labels
+                let db = if db.abs() < step / 2.0 { 0.0 } else { db };
+                (t, format!("{:.*}", decimals, db))
+            })
+            .collect();
+
+    let spans: Vec<(i64, i64)> = labels
+        .iter()
+        .map(|(t, _)| centered((plot_height as f32 * t) as i64, line_height))
+        .collect();
+
+    keep(labels, &spans, &vec![false; spans.len()])
+}
+
+/// `labels` whose `spans` survive `ticks::without_overlaps`.
+fn keep<T>(labels: Vec<T>, spans: &[(i64, i64)], pinned: &[bool]) -> Vec<T> {
+    let keep = ticks::without_overlaps(spans, pinned, GAP as i64);
+
+    labels
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(label, keep)| keep.then_some(label))
+        .collect()
+}
+
+/// Span of a label `extent` pixels long, centered on `pos`.
+fn centered(pos: i64, extent: u32) -> (i64, i64) {
+    let start = pos - extent as i64 / 2;
+    (start, start + extent as i64)
+}
+
+/// Width of the widest dB label.
+fn db_label_width(
+    settings: &LegendSettings,
+    context: &LegendContext,
+    font: &dyn FontRasterizer,
+    plot_height: u32,
+) -> u32 {
+    db_labels(settings, context, font, plot_height)
+        .iter()
+        .map(|(_, s)| font.measure(s, settings.font_size).0)
+        .chain(Some(font.measure("dBFS", settings.font_size).0))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Width of the dBFS gradient bar, half a text line.
+fn bar_width(line_height: u32) -> u32 {
+    (line_height / 2).max(6)
+}
+
+/// Panel heights, same integer geometry as the renderer.
+fn panel_heights(context: &LegendContext, plot_height: u32) -> impl Iterator<Item = u32> {
+    let n = context.panels.len().max(1) as u32;
+    (0..n).map(move |ch| (ch + 1) * plot_height / n - ch * plot_height / n)
+}
+
+/// `text` cut to `max_width` pixels, ending in "..." if shortened.
+fn shorten(font: &dyn FontRasterizer, size: u32, text: &str, max_width: u32) -> String {
+    if font.measure(text, size).0 <= max_width {
+        return text.to_string();
+    }
+
+    let mut chars: Vec<char> = text.chars().collect();
+    while !chars.is_empty() {
+        chars.pop();
+        let candidate: String = chars.iter().copied().chain("...".chars()).collect();
+        if font.measure(&candidate, size).0 <= max_width {
+            return candidate;
+        }
+    }
+
+    String::new()
+}
+
 // -------------------------------------------------------------------------
 // Helpers
 // -------------------------------------------------------------------------
 
+/// Ticks at round frequencies (1-2-5 × 10ⁿ Hz steps) for the linear scale,
+/// at least `min_px` pixels apart.
+///
+/// The bottom and the top of the axis are always labeled;
+/// round ticks colliding with them are dropped.
+fn linear_ticks(
+    min_freq: f64,
+    max_freq: f64,
+    height: u32,
+    min_px: u32,
+    line_height: u32,
+) -> Vec<(f64, String)> {
+    let span = max_freq - min_freq;
+    if span <= 0.0 || height == 0 {
+        return vec![(0.0, format_khz(min_freq, 0))];
+    }
+
+    let step = ticks::nice_step(min_px as f64 * span / height as f64);
+    let decimals = ticks::decimals(step / 1000.0);
+
+    // Edge labels keep one decimal for values like 22.05 kHz
+    let edge = |freq: f64| {
+        let decimals = if (freq % 1000.0).abs() < 1.0 { decimals } else { decimals.max(1) };
+        format_khz(freq, decimals)
+    };
+
+    let mut labels = vec![(0.0, edge(min_freq))];
+    labels.extend(
+        ticks::multiples(min_freq, max_freq, step)
+            .into_iter()
+            .filter(|&freq| freq > min_freq && freq < max_freq)
+            .map(|freq| ((freq - min_freq) / span, format_khz(freq, decimals))),
+    );
+    labels.push((1.0, edge(max_freq)));
+
+    let spans: Vec<(i64, i64)> = labels
+        .iter()
+        .map(|(f, _)| centered((height as f64 * f).round() as i64, line_height))
+        .collect();
+    let pinned: Vec<bool> = (0..labels.len()).map(|i| i == 0 || i == labels.len() - 1).collect();
+
+    keep(labels, &spans, &pinned)
+}
+
+/// Ticks at 1-2-5 frequencies (50, 100, 200, 500 Hz, 1k, 2k...)
+/// for warped scales.
+///
+/// The bottom and the top of the axis are always labeled;
+/// overlapping labels are dropped from the bottom up.
+fn nice_ticks(
+    scale: FrequencyScale,
+    min_freq: f64,
+    max_freq: f64,
+    height: u32,
+    line_height: u32,
+) -> Vec<(f64, String)> {
+    let bottom = scale.axis_min(min_freq, max_freq);
+
+    let mut labels = vec![(0.0, format_hz(bottom))];
+
+    let mut decade = 10.0;
+    while decade < max_freq {
+        for step in [1.0, 2.0, 5.0] {
+            let freq = decade * step;
+            if freq > bottom && freq < max_freq {
+                labels.push((scale.to_unit(freq, min_freq, max_freq), format_hz(freq)));
+            }
+        }
+        decade *= 10.0;
+    }
+
+    labels.push((1.0, format_hz(max_freq)));
+
+    let spans: Vec<(i64, i64)> = labels
+        .iter()
+        .map(|(f, _)| centered((height as f64 * f).round() as i64, line_height))
+        .collect();
+    let pinned: Vec<bool> = (0..labels.len()).map(|i| i == 0 || i == labels.len() - 1).collect();
+
+    keep(labels, &spans, &pinned)
+}
+
+/// "50 Hz", "500 Hz", "1 kHz", "2.5 kHz", "22.05 kHz".
+fn format_hz(freq: f64) -> String {
+    if freq < 1000.0 {
+        format!("{:.0} Hz", freq)
+    } else {
+        let khz = format!("{:.2}", freq / 1000.0);
+        let khz = khz.trim_end_matches('0').trim_end_matches('.');
+        format!("{} kHz", khz)
+    }
+}
+
+/// "2 kHz", "0.5 kHz" with a fixed number of decimals per axis.
+fn format_khz(freq: f64, decimals: usize) -> String {
+    format!("{:.*} kHz", decimals, freq / 1000.0)
+}
+
 /// Format time exactly like Spek / spek-rs:
 /// `m:ss` with minutes NOT capped at 59.
+///
+/// Sub-second steps add `decimals` places: `0:01.5`.
 #[inline]
-fn format_time_m_ss(total_seconds: f64) -> String {
-    let minutes = (total_seconds / 60.0).floor() as u64;
-    let seconds = (total_seconds % 60.0).floor() as u64;
-    format!("{}:{:02}", minutes, seconds)
+fn format_time_m_ss(total_seconds: f64, decimals: usize) -> String {
+    let scale = 10f64.powi(decimals as i32);
+    let total = (total_seconds * scale).round() / scale;
+
+    let minutes = (total / 60.0).floor() as u64;
+    let seconds = total - minutes as f64 * 60.0;
+    let width = if decimals > 0 { decimals + 3 } else { 2 };
+
+    format!("{}:{:0width$.prec$}", minutes, seconds, width = width, prec = decimals)
 }
 
 #[inline]
@@ -235,10 +660,249 @@ fn line(x1: u32, y1: u32, x2: u32, y2: u32) -> LegendCommand {
 }
 
 #[inline]
-fn text(x: u32, y: u32, content: &str) -> LegendCommand {
+fn text(x: u32, y: u32, content: &str, size: u32) -> LegendCommand {
     LegendCommand::Text {
         x,
         y,
         content: content.to_string(),
+        size,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::AudioMetadata;
+    use crate::legend::font::BitmapFont;
+
+    /// One hour of 96 kHz stereo, two labeled panels.
+    fn context() -> LegendContext {
+        LegendContext {
+            audio: AudioMetadata {
+                sample_rate: 96000,
+                channels: 2,
+                total_samples: 96000 * 3600,
+                bit_depth: Some(24),
+                codec: Some("FLAC".to_string()),
+                tags: Vec::new(),
+            },
+            duration_sec: 3600.0,
+            time_offset: 0.0,
+            min_db: -120.0,
+            max_db: 0.0,
+            scale: crate::analysis::IntensityScale::Linear,
+            panels: vec![Some("L".to_string()), Some("R".to_string())],
+            min_freq: 0.0,
+            max_freq: 48000.0,
+            freq_scale: FrequencyScale::Linear,
+            cutoff: None,
+            file_name: Some("a rather long file name.flac".to_string()),
+            app_version: Some("spek-core 0.1.0".to_string()),
+        }
+    }
+
+    fn settings(font_size: u32) -> LegendSettings {
+        LegendSettings {
+            font_size,
+            freq_ticks: 10,
+            time_ticks: 10,
+            db_ticks: 6,
+        }
+    }
+
+    /// Margins and commands for a `plot_width` x `plot_height` plot.
+    fn draw(
+        settings: &LegendSettings,
+        context: &LegendContext,
+        plot_width: u32,
+        plot_height: u32,
+    ) -> (LegendMargins, Vec<LegendCommand>) {
+        let font = BitmapFont::new();
+        let legend = SimpleLegendRenderer::new();
+
+        let m = legend.layout(settings, context, &font, plot_width, plot_height);
+        let (width, height) = (m.left + plot_width + m.right, m.top + plot_height + m.bottom);
+
+        (m, legend.generate(settings, context, &font, m, width, height))
+    }
+
+    fn texts(cmds: &[LegendCommand]) -> Vec<(u32, u32, &str)> {
+        cmds.iter()
+            .filter_map(|cmd| match cmd {
+                LegendCommand::Text { x, y, content, .. } => Some((*x, *y, content.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn layout_keeps_labels_inside_image() {
+        let context = context();
+        let font = BitmapFont::new();
+
+        for font_size in [12, 36] {
+            let (m, cmds) = draw(&settings(font_size), &context, 300, 200);
+            let (width, height) = (m.left + 300 + m.right, m.top + 200 + m.bottom);
+
+            for cmd in cmds {
+                if let LegendCommand::Text { x, y, content, size } = cmd {
+                    let (w, h) = font.measure(&content, size);
+                    assert!(x + w <= width && y + h <= height, "{:?} clipped", content);
+                }
+            }
+        }
+    }
+
+    /// Parse "500 Hz", "2.5 kHz" back to Hz.
+    fn hz(label: &str) -> f64 {
+        match label.strip_suffix(" kHz") {
+            Some(khz) => khz.parse::<f64>().unwrap() * 1000.0,
+            None => label.strip_suffix(" Hz").unwrap().parse().unwrap(),
+        }
+    }
+
+    /// Pixel positions of labels spaced closer than a text line.
+    fn crowded(positions: &[f64], height: u32, line_height: u32) -> bool {
+        positions
+            .windows(2)
+            .any(|w| ((w[1] - w[0]) * height as f64).abs() < line_height as f64)
+    }
+
+    #[test]
+    fn ticks_land_on_1_2_5_steps() {
+        let font = BitmapFont::new();
+        let mut context = context();
+        let settings = settings(12);
+
+        // Linear: interior ticks on one round step
+        let labels = freq_labels(&settings, &context, &font, 400, 400);
+        let inner: Vec<f64> = labels[1..labels.len() - 1].iter().map(|l| hz(&l.1)).collect();
+        let step = inner[1] - inner[0];
+
+        assert!(inner.len() >= 4);
+        assert_eq!(ticks::nice_step(step), step);
+        assert!(inner.windows(2).all(|w| w[1] - w[0] == step));
+        assert!(inner.iter().all(|f| f % step == 0.0));
+
+        // Log: interior ticks on 1, 2, 5 × 10ⁿ Hz
+        context.freq_scale = FrequencyScale::Log;
+        let labels = freq_labels(&settings, &context, &font, 400, 400);
+
+        assert!(labels.len() >= 6);
+        for (_, label) in &labels[1..labels.len() - 1] {
+            let freq = hz(label);
+            let decade = 10f64.powf(freq.log10().floor());
+            assert!([1.0, 2.0, 5.0].contains(&(freq / decade)), "{}", label);
+        }
+
+        // dB: round steps including 0
+        let labels = db_labels(&settings, &context, &font, 400);
+        let db: Vec<f64> = labels.iter().map(|l| l.1.parse().unwrap()).collect();
+        let step = db[1] - db[0];
+
+        assert_eq!(ticks::nice_step(step), step);
+        assert!(db.windows(2).all(|w| w[1] - w[0] == step));
+        assert_eq!(db.last(), Some(&0.0));
+    }
+
+    #[test]
+    fn overlapping_labels_are_dropped_at_small_heights() {
+        let font = BitmapFont::new();
+        let settings = settings(12);
+        let line_height = font.measure("0", 12).1;
+
+        for scale in [FrequencyScale::Linear, FrequencyScale::Log] {
+            let mut context = context();
+            context.freq_scale = scale;
+
+            let tall = freq_labels(&settings, &context, &font, 400, 400);
+
+            for height in [20, 40, 60, 100] {
+                let labels = freq_labels(&settings, &context, &font, height, height);
+                let positions: Vec<f64> = labels.iter().map(|l| l.0).collect();
+
+                // Both ends stay, the rest thins out without overlaps
+                assert_eq!(labels[0].1, tall[0].1);
+                assert_eq!(labels.last().unwrap().1, tall.last().unwrap().1);
+                assert!(labels.len() < tall.len(), "{:?} at {}", scale, height);
+                assert!(!crowded(&positions, height, line_height), "{:?}", scale);
+            }
+        }
+
+        let context = context();
+        for height in [10, 20, 40] {
+            let labels = db_labels(&settings, &context, &font, height);
+            let positions: Vec<f64> = labels.iter().map(|l| l.0 as f64).collect();
+            assert!(!crowded(&positions, height, line_height), "{}", height);
+        }
+    }
+
+    #[test]
+    fn margins_grow_with_the_labels() {
+        let context = context();
+        let (small, _) = draw(&settings(12), &context, 300, 200);
+        let (large, _) = draw(&settings(24), &context, 300, 200);
+
+        assert!(large.left > small.left);
+        assert!(large.right > small.right);
+        assert!(large.top > small.top);
+        assert!(large.bottom > small.bottom);
+
+        // "192 kHz" is wider than "48 kHz"
+        let mut wide = context;
+        wide.max_freq = 192000.0;
+        let (m, _) = draw(&settings(12), &wide, 300, 200);
+        assert!(m.left > small.left);
+        assert_eq!((m.right, m.top, m.bottom), (small.right, small.top, small.bottom));
+    }
+
+    #[test]
+    fn axis_labels_stay_outside_the_plot() {
+        let font = BitmapFont::new();
+        let mut context = context();
+
+        for (font_size, max_freq) in [(12, 48000.0), (24, 48000.0), (12, 192000.0), (36, 22050.0)] {
+            context.max_freq = max_freq;
+
+            let (m, cmds) = draw(&settings(font_size), &context, 300, 200);
+            let (right, bottom) = (m.left + 300, m.top + 200);
+
+            // Panel labels sit inside their panel on purpose
+            for (x, y, content) in texts(&cmds).into_iter().filter(|t| !["L", "R"].contains(&t.2)) {
+                let (w, h) = font.measure(content, font_size);
+                let outside = x + w <= m.left || x >= right || y + h <= m.top || y >= bottom;
+                assert!(outside, "{:?} at size {} crosses the plot", content, font_size);
+            }
+        }
+    }
+
+    #[test]
+    fn split_panels_get_one_labeled_axis_each() {
+        let mut context = context();
+        context.panels = ["L", "R", "C"].map(|l| Some(l.to_string())).to_vec();
+        context.audio.channels = 3;
+
+        let settings = settings(12);
+        let (m, cmds) = draw(&settings, &context, 300, 300);
+        let texts = texts(&cmds);
+
+        for (ch, label) in ["L", "R", "C"].into_iter().enumerate() {
+            // Label in the top left corner of its panel
+            let top = m.top + ch as u32 * 300 / 3;
+            assert!(texts.contains(&(m.left + GAP, top + GAP, label)), "{}", label);
+
+            // Separator above every panel but the first
+            let separator = cmds.iter().any(|cmd| {
+                matches!(cmd, LegendCommand::Line { x1, y1, x2, y2 }
+                    if (*x1, *x2) == (m.left, m.left + 300) && *y1 == top && *y2 == top)
+            });
+            assert_eq!(separator, ch > 0, "{}", label);
+        }
+
+        // Every panel has its own frequency axis starting at 0
+        let font = BitmapFont::new();
+        let lowest = &freq_labels(&settings, &context, &font, 300, 100)[0];
+        assert_eq!(lowest.0, 0.0);
+        assert_eq!(texts.iter().filter(|t| t.2 == lowest.1).count(), 3);
     }
 }
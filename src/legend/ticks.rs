@@ -0,0 +1,114 @@
+//! Tick placement for legend axes.
+//!
+//! Steps are "nice" numbers (1, 2, 5 × 10ⁿ) chosen from the pixels
+//! available per unit, so labels read 0:10, 0:20 or 2 kHz, 4 kHz
+//! instead of evenly split fractions.
+//!
+//! Labels that would overlap are dropped, never moved.
+
+/// Smallest 1-2-5 × 10ⁿ value ≥ `min`.
+pub fn nice_step(min: f64) -> f64 {
+    if !min.is_finite() || min <= 0.0 {
+        return 1.0;
+    }
+
+    let decade = 10f64.powf(min.log10().floor());
+
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * decade)
+        // Tolerance: `min` may sit on a decade boundary after log10 rounding
+        .find(|&step| step >= min * (1.0 - 1e-9))
+        .unwrap_or(10.0 * decade)
+}
+
+/// Smallest time step (seconds) ≥ `min` that reads well as `m:ss`.
+///
+/// 1-2-5 × 10ⁿ below one second,
+/// then 1, 2, 5, 10, 20 seconds, 1, 2, 5, 10, 20 minutes,
+/// then 1-2-5 × 10ⁿ hours.
+pub fn time_step(min: f64) -> f64 {
+    const STEPS: [f64; 10] = [1.0, 2.0, 5.0, 10.0, 20.0, 60.0, 120.0, 300.0, 600.0, 1200.0];
+
+    if min <= 1.0 {
+        return nice_step(min);
+    }
+
+    STEPS
+        .into_iter()
+        .find(|&step| step >= min)
+        .unwrap_or_else(|| 3600.0 * nice_step(min / 3600.0))
+}
+
+/// Decimal places needed to print multiples of `step` exactly.
+pub fn decimals(step: f64) -> usize {
+    if step >= 1.0 {
+        0
+    } else {
+        (-step.log10()).ceil() as usize
+    }
+}
+
+/// All multiples of `step` in `lo..=hi`, ascending.
+pub fn multiples(lo: f64, hi: f64, step: f64) -> Vec<f64> {
+    if step <= 0.0 || hi < lo {
+        return Vec::new();
+    }
+
+    // Tolerance keeps exact multiples at the range ends
+    let first = (lo / step - 1e-9).ceil() as i64;
+    let last = (hi / step + 1e-9).floor() as i64;
+
+    (first..=last).map(|k| k as f64 * step).collect()
+}
+
+/// Keep-flag per label after dropping overlaps.
+///
+/// `spans` are pixel extents (start, end) along the axis.
+/// Pinned labels (axis ends) are placed first, the others in order,
+/// each only if it stays `gap` pixels clear of every kept label.
+pub fn without_overlaps(spans: &[(i64, i64)], pinned: &[bool], gap: i64) -> Vec<bool> {
+    let mut keep = vec![false; spans.len()];
+    let mut placed: Vec<(i64, i64)> = Vec::new();
+
+    let order = (0..spans.len())
+        .filter(|&i| pinned[i])
+        .chain((0..spans.len()).filter(|&i| !pinned[i]));
+
+    for i in order {
+        let (start, end) = spans[i];
+        let clear = placed
+            .iter()
+            .all(|&(s, e)| end + gap <= s || e + gap <= start);
+
+        if clear {
+            placed.push(spans[i]);
+            keep[i] = true;
+        }
+    }
+
+    keep
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_are_nice() {
+        assert_eq!(nice_step(0.13), 0.2);
+        assert_eq!(nice_step(1.0), 1.0);
+        assert_eq!(nice_step(2196.0), 5000.0);
+        assert_eq!(time_step(13.4), 20.0);
+        assert_eq!(time_step(45.0), 60.0);
+        assert_eq!(time_step(5000.0), 7200.0);
+        assert_eq!(multiples(-97.3, 2.7, 20.0), vec![-80.0, -60.0, -40.0, -20.0, 0.0]);
+    }
+
+    #[test]
+    fn pinned_labels_win() {
+        let spans = [(0, 10), (8, 18), (20, 30), (25, 35)];
+        let keep = without_overlaps(&spans, &[false, false, false, true], 2);
+        assert_eq!(keep, vec![true, false, false, true]);
+    }
+}
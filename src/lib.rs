@@ -7,6 +7,7 @@ pub mod api;
 
 pub mod audio;
 pub mod analysis;
+pub mod color;
 pub mod render;
 pub mod legend;
 
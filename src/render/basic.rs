@@ -0,0 +1,230 @@
+//! Basic CPU renderer for spek-core.
+//!
+//! Implements the block-fill rendering model:
+//! one data point -> one pixel or one constant pixel block.
+//!
+//! - Time runs left -> right
+//! - Frequency runs bottom (`min_freq`) -> top (`max_freq`), warped by `FrequencyScale`
+//! - Split channels are stacked vertically, first channel on top
+//!
+//! No interpolation, no smoothing, no filtering.
+
+use crate::analysis::SpectrogramSet;
+use crate::color::ColorMapper;
+use crate::render::{FrequencyScale, ImageBuffer, RenderError, RenderSettings, Renderer};
+
+use std::ops::Range;
+
+/// Reference CPU renderer.
+///
+/// Colors are taken from the `ColorMapper` passed per call,
+/// the same mapper the legend uses for its dBFS gradient.
+///
+/// This renderer is stateless and deterministic.
+#[derive(Debug, Default)]
+pub struct BasicRenderer;
+
+impl BasicRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Renderer for BasicRenderer {
+    fn render(
+        &self,
+        spectrogram: &SpectrogramSet,
+        settings: &RenderSettings,
+        color: &dyn ColorMapper,
+    ) -> Result<ImageBuffer, RenderError> {
+        let width = settings.width;
+        let height = settings.height;
+        let channels = spectrogram.channels.len();
+
+        if channels == 0
+            || spectrogram
+                .channels
+                .iter()
+                .any(|s| s.freq_bins == 0 || s.time_bins == 0)
+        {
+            return Err(RenderError::EmptySpectrogram);
+        }
+
+        if width == 0 || height < channels {
+            return Err(RenderError::InvalidDimensions);
+        }
+
+        // Opaque black background
+        let mut image = ImageBuffer::new(width, height);
+
+        let mut columns = vec![0usize; width];
+
+        for (ch, spec) in spectrogram.channels.iter().enumerate() {
+            // -------------------------------------------------------------
+            // Channel panel geometry (integer, no rounding drift)
+            // -------------------------------------------------------------
+            let panel_top = ch * height / channels;
+            let panel_bottom = (ch + 1) * height / channels;
+            let panel_height = panel_bottom - panel_top;
+
+            // -------------------------------------------------------------
+            // Column -> time bin mapping (computed once per channel)
+            // -------------------------------------------------------------
+            for (x, column) in columns.iter_mut().enumerate() {
+                *column = x * spec.time_bins / width;
+            }
+
+            let rows = row_bins(
+                settings.freq_scale,
+                panel_height,
+                spec.freq_bins,
+                spectrogram.min_freq as f64,
+                spectrogram.max_freq as f64,
+            );
+
+            for (row, bins) in rows.into_iter().enumerate() {
+                let y = panel_top + row;
+                let line = &mut image.data[y * width * 4..(y + 1) * width * 4];
+
+                for (px, &t) in line.chunks_exact_mut(4).zip(columns.iter()) {
+                    // Peak of all bins in the row: narrow tones never vanish
+                    let value = spec.data[bins.clone()]
+                        .iter()
+                        .fold(0.0f32, |acc, values| acc.max(values[t]));
+
+                    px.copy_from_slice(&color.map(value));
+                }
+            }
+        }
+
+        Ok(image)
+    }
+}
+
+/// Frequency bins covered by each row of a panel, top row first.
+///
+/// Linear keeps the exact one-bin-per-row Spek mapping.
+/// Other scales cover every bin between the row's lower and upper edge.
+fn row_bins(
+    scale: FrequencyScale,
+    panel_height: usize,
+    freq_bins: usize,
+    min_freq: f64,
+    max_freq: f64,
+) -> Vec<Range<usize>> {
+    let span = max_freq - min_freq;
+
+    (0..panel_height)
+        .map(|row| {
+            // Rows counted from the bottom (`min_freq`)
+            let r = panel_height - 1 - row;
+
+            if scale == FrequencyScale::Linear || span <= 0.0 {
+                let bin = r * freq_bins / panel_height;
+                return bin..bin + 1;
+            }
+
+            // Bin k starts at min_freq + k * span / freq_bins
+            let edge = |r: usize| {
+                let freq = scale.from_unit(r as f64 / panel_height as f64, min_freq, max_freq);
+                ((freq - min_freq) / span * freq_bins as f64).round() as usize
+            };
+
+            let lo = edge(r).min(freq_bins - 1);
+            let hi = edge(r + 1).clamp(lo + 1, freq_bins);
+
+            lo..hi
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::{IntensityScale, Spectrogram};
+
+    /// Intensity in the red channel, exact for multiples of 1/255.
+    struct RedMapper;
+
+    impl ColorMapper for RedMapper {
+        fn map(&self, intensity: f32) -> [u8; 4] {
+            [(intensity * 255.0).round() as u8, 0, 0, 255]
+        }
+    }
+
+    fn set(panels: Vec<Vec<Vec<f32>>>) -> SpectrogramSet {
+        SpectrogramSet {
+            channels: panels
+                .into_iter()
+                .map(|data| Spectrogram {
+                    freq_bins: data.len(),
+                    time_bins: data.first().map_or(0, Vec::len),
+                    data,
+                    db: None,
+                    frame_db: None,
+                    label: None,
+                })
+                .collect(),
+            min_db: -120.0,
+            max_db: 0.0,
+            scale: IntensityScale::Linear,
+            min_freq: 0.0,
+            max_freq: 22050.0,
+            frequencies: Vec::new(),
+            times: Vec::new(),
+            frame_times: None,
+        }
+    }
+
+    fn render(set: &SpectrogramSet, width: usize, height: usize) -> Result<ImageBuffer, RenderError> {
+        let settings = RenderSettings {
+            width,
+            height,
+            freq_scale: FrequencyScale::Linear,
+        };
+        BasicRenderer::new().render(set, &settings, &RedMapper)
+    }
+
+    fn red(image: &ImageBuffer, x: usize, y: usize) -> u8 {
+        image.data[(y * image.width + x) * 4]
+    }
+
+    #[test]
+    fn time_bins_fill_column_blocks() {
+        let image = render(&set(vec![vec![vec![0.0, 0.2, 0.6, 1.0]]]), 8, 1).unwrap();
+
+        let columns: Vec<u8> = (0..8).map(|x| red(&image, x, 0)).collect();
+        assert_eq!(columns, [0, 0, 51, 51, 153, 153, 255, 255]);
+    }
+
+    #[test]
+    fn panels_stack_top_down_with_low_frequencies_at_the_bottom() {
+        // Panel 0: low bin dark, high bin bright. Panel 1: flat 0.2.
+        let image = render(
+            &set(vec![vec![vec![0.0], vec![1.0]], vec![vec![0.2], vec![0.2]]]),
+            1,
+            5,
+        )
+        .unwrap();
+
+        // Panel rows: 0..2 and 2..5 (integer split, no gaps)
+        let rows: Vec<u8> = (0..5).map(|y| red(&image, 0, y)).collect();
+        assert_eq!(rows, [255, 0, 51, 51, 51]);
+        assert_eq!((image.width, image.height), (1, 5));
+    }
+
+    #[test]
+    fn empty_input_and_bad_sizes_are_rejected() {
+        let one = set(vec![vec![vec![0.5]]]);
+        let two = set(vec![vec![vec![0.5]], vec![vec![0.5]]]);
+
+        assert!(matches!(render(&set(vec![]), 8, 8), Err(RenderError::EmptySpectrogram)));
+        assert!(matches!(
+            render(&set(vec![vec![Vec::new()]]), 8, 8),
+            Err(RenderError::EmptySpectrogram)
+        ));
+        assert!(matches!(render(&one, 0, 8), Err(RenderError::InvalidDimensions)));
+        assert!(matches!(render(&one, 8, 0), Err(RenderError::InvalidDimensions)));
+        assert!(matches!(render(&two, 8, 1), Err(RenderError::InvalidDimensions)));
+    }
+}
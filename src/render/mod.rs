@@ -0,0 +1,61 @@
+//! Spectrogram rendering for spek-core.
+//!
+//! This module converts a numerical spectrogram grid into
+//! a pixel-exact RGBA image buffer.
+//!
+//! It draws NO axes, NO text and knows NO metadata.
+//! The legend is applied afterwards as a separate step.
+
+pub mod basic;
+pub mod scale;
+
+use crate::analysis::SpectrogramSet;
+use crate::color::ColorMapper;
+
+pub use crate::api::image::ImageBuffer;
+pub use scale::FrequencyScale;
+
+/// Parameters controlling the rendering stage.
+///
+/// These describe the spectrogram area only,
+/// legend margins are NOT included.
+#[derive(Debug, Clone)]
+pub struct RenderSettings {
+    /// Spectrogram width in pixels
+    pub width: usize,
+
+    /// Spectrogram height in pixels
+    pub height: usize,
+
+    /// Vertical frequency mapping
+    pub freq_scale: FrequencyScale,
+}
+
+/// Spectrogram renderer interface.
+///
+/// Converts an analysis result into pixels:
+/// SpectrogramSet -> layout -> color -> RGBA
+///
+/// Implementations MUST be deterministic.
+pub trait Renderer {
+    /// Render `spectrogram` with colors from `color`.
+    ///
+    /// The legend renders its dBFS gradient through the same mapper,
+    /// so both are guaranteed to use identical colors.
+    fn render(
+        &self,
+        spectrogram: &SpectrogramSet,
+        settings: &RenderSettings,
+        color: &dyn ColorMapper,
+    ) -> Result<ImageBuffer, RenderError>;
+}
+
+/// Rendering errors.
+#[derive(Debug)]
+pub enum RenderError {
+    /// Width or height is zero, or too small for the channel layout
+    InvalidDimensions,
+
+    /// Spectrogram contains no channels or no bins
+    EmptySpectrogram,
+}
@@ -0,0 +1,153 @@
+//! Frequency axis scales for spek-core.
+//!
+//! A scale warps frequency (Hz) onto the vertical unit interval:
+//! 0.0 = bottom of a panel (`min_freq`), 1.0 = top (`max_freq`).
+//!
+//! Renderer and legend share these functions,
+//! so pixels and tick labels can never disagree.
+
+/// Lowest frequency shown on a logarithmic axis (log(0) is undefined).
+pub const LOG_MIN_HZ: f64 = 20.0;
+
+/// Vertical frequency axis mapping.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FrequencyScale {
+    /// 0 Hz .. Nyquist, evenly spaced (Spek default)
+    Linear,
+
+    /// `LOG_MIN_HZ` (or higher) .. Nyquist, equal space per octave
+    Log,
+
+    /// Mel scale (O'Shaughnessy): 2595 · log10(1 + f / 700)
+    Mel,
+
+    /// Bark scale (Traunmüller): 26.81 · f / (1960 + f) - 0.53
+    Bark,
+
+    /// ERB-rate scale (Glasberg & Moore): 21.4 · log10(1 + 0.00437 · f)
+    Erb,
+}
+
+impl FrequencyScale {
+    /// Lowest frequency on an axis over data `min_freq..max_freq`.
+    ///
+    /// Equals `min_freq`, except on a log axis starting near 0 Hz.
+    pub fn axis_min(self, min_freq: f64, max_freq: f64) -> f64 {
+        match self {
+            FrequencyScale::Log => min_freq.max(LOG_MIN_HZ.min(max_freq / 2.0)),
+            _ => min_freq,
+        }
+    }
+
+    /// Position of `freq` on the axis `min_freq..max_freq` (0.0..1.0).
+    pub fn to_unit(self, freq: f64, min_freq: f64, max_freq: f64) -> f64 {
+        let lo = self.warp(self.axis_min(min_freq, max_freq));
+        let hi = self.warp(max_freq);
+
+        (self.warp(freq) - lo) / (hi - lo)
+    }
+
+    /// Frequency at position `unit` (0.0..1.0) on the axis `min_freq..max_freq`.
+    pub fn from_unit(self, unit: f64, min_freq: f64, max_freq: f64) -> f64 {
+        let lo = self.warp(self.axis_min(min_freq, max_freq));
+        let hi = self.warp(max_freq);
+
+        self.unwarp(lo + unit * (hi - lo))
+    }
+
+    fn warp(self, f: f64) -> f64 {
+        match self {
+            FrequencyScale::Linear => f,
+            FrequencyScale::Log => f.max(f64::MIN_POSITIVE).ln(),
+            FrequencyScale::Mel => 2595.0 * (1.0 + f / 700.0).log10(),
+            FrequencyScale::Bark => 26.81 * f / (1960.0 + f) - 0.53,
+            FrequencyScale::Erb => 21.4 * (1.0 + 0.00437 * f).log10(),
+        }
+    }
+
+    fn unwarp(self, z: f64) -> f64 {
+        match self {
+            FrequencyScale::Linear => z,
+            FrequencyScale::Log => z.exp(),
+            FrequencyScale::Mel => 700.0 * (10f64.powf(z / 2595.0) - 1.0),
+            FrequencyScale::Bark => 1960.0 * (z + 0.53) / (26.28 - z),
+            FrequencyScale::Erb => (10f64.powf(z / 21.4) - 1.0) / 0.00437,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCALES: [FrequencyScale; 5] = [
+        FrequencyScale::Linear,
+        FrequencyScale::Log,
+        FrequencyScale::Mel,
+        FrequencyScale::Bark,
+        FrequencyScale::Erb,
+    ];
+
+    const AXES: [(f64, f64); 3] = [(0.0, 22050.0), (1000.0, 2000.0), (50.0, 96000.0)];
+
+    #[test]
+    fn from_unit_inverts_to_unit() {
+        for scale in SCALES {
+            for (min_freq, max_freq) in AXES {
+                let lo = scale.axis_min(min_freq, max_freq);
+
+                for i in 0..=64 {
+                    let freq = lo + (max_freq - lo) * i as f64 / 64.0;
+                    let unit = scale.to_unit(freq, min_freq, max_freq);
+                    let back = scale.from_unit(unit, min_freq, max_freq);
+
+                    assert!(
+                        (back - freq).abs() < 1e-9 * max_freq,
+                        "{:?} {}..{}: {} -> {} -> {}",
+                        scale,
+                        min_freq,
+                        max_freq,
+                        freq,
+                        unit,
+                        back
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn axis_ends_map_to_unit_ends() {
+        for scale in SCALES {
+            for (min_freq, max_freq) in AXES {
+                let lo = scale.axis_min(min_freq, max_freq);
+                let tolerance = 1e-9 * max_freq;
+
+                assert!(scale.to_unit(lo, min_freq, max_freq).abs() < 1e-12, "{:?}", scale);
+                assert!((scale.to_unit(max_freq, min_freq, max_freq) - 1.0).abs() < 1e-12);
+                assert!((scale.from_unit(0.0, min_freq, max_freq) - lo).abs() < tolerance);
+                assert!((scale.from_unit(1.0, min_freq, max_freq) - max_freq).abs() < tolerance);
+            }
+        }
+    }
+
+    #[test]
+    fn log_axis_starts_above_zero() {
+        // Starting at 0 Hz: clamped to LOG_MIN_HZ
+        assert_eq!(FrequencyScale::Log.axis_min(0.0, 22050.0), LOG_MIN_HZ);
+
+        // Tiny ranges: at most one octave below the top
+        assert_eq!(FrequencyScale::Log.axis_min(0.0, 30.0), 15.0);
+
+        // A real lower bound is kept
+        assert_eq!(FrequencyScale::Log.axis_min(1000.0, 2000.0), 1000.0);
+
+        for scale in SCALES.into_iter().filter(|&s| s != FrequencyScale::Log) {
+            assert_eq!(scale.axis_min(0.0, 22050.0), 0.0);
+        }
+
+        // Below the axis minimum: outside the panel, never NaN
+        let unit = FrequencyScale::Log.to_unit(0.0, 0.0, 22050.0);
+        assert!(unit < 0.0 && !unit.is_nan());
+    }
+}